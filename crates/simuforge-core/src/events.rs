@@ -0,0 +1,100 @@
+//! Discrete simulation events (collisions, sensors, contact forces)
+
+use serde::{Deserialize, Serialize};
+
+/// A discrete event recorded during simulation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SimEvent {
+    pub step: u64,
+    pub time: f32,
+    #[serde(flatten)]
+    pub kind: EventKind,
+}
+
+impl SimEvent {
+    pub fn new(step: u64, time: f32, kind: EventKind) -> Self {
+        Self { step, time, kind }
+    }
+}
+
+/// Kind of event, with the names of the bodies involved
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum EventKind {
+    CollisionStarted {
+        body_a: String,
+        body_b: String,
+    },
+    CollisionStopped {
+        body_a: String,
+        body_b: String,
+    },
+    SensorEntered {
+        sensor: String,
+        body: String,
+    },
+    SensorExited {
+        sensor: String,
+        body: String,
+    },
+    ContactForce {
+        body_a: String,
+        body_b: String,
+        total_force_magnitude: f32,
+        max_force_magnitude: f32,
+    },
+    ActionApplied {
+        action: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        body: Option<String>,
+    },
+    ActionFailed {
+        action: String,
+        reason: String,
+    },
+    /// Physics or body parameter changed while the simulation was running
+    ParameterChanged {
+        parameter: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        body: Option<String>,
+        previous: String,
+        value: String,
+    },
+}
+
+/// Canonical key for an unordered body pair, used for per-pair metric names
+pub fn pair_key(a: &str, b: &str) -> String {
+    if a <= b {
+        format!("{}.{}", a, b)
+    } else {
+        format!("{}.{}", b, a)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_serialization() {
+        let event = SimEvent::new(
+            12,
+            0.2,
+            EventKind::CollisionStarted {
+                body_a: "ball".to_string(),
+                body_b: "ground".to_string(),
+            },
+        );
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["type"], "collision_started");
+        assert_eq!(json["body_a"], "ball");
+
+        let parsed: SimEvent = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed, event);
+    }
+
+    #[test]
+    fn test_pair_key_is_order_independent() {
+        assert_eq!(pair_key("ground", "ball"), pair_key("ball", "ground"));
+    }
+}
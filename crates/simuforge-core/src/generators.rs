@@ -0,0 +1,263 @@
+//! Procedural body arrays for custom scenarios
+
+use crate::math::Vec3;
+use crate::spec::{BodyConfig, ShapeConfig};
+use nalgebra::{UnitQuaternion, Vector3};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+use std::f32::consts::TAU;
+
+/// Entry in a custom body list: a single body, or a generator expanding into many
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum BodyEntry {
+    Generator(BodyGenerator),
+    Body(BodyConfig),
+}
+
+/// Copies of a template body laid out in a pattern.
+///
+/// Copies are named `<template name>_<index>`; pattern offsets are added to the
+/// template position and pattern rotations applied on top of the template rotation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BodyGenerator {
+    #[serde(flatten)]
+    pub pattern: BodyPattern,
+    pub template: BodyConfig,
+    /// Overrides the physics seed for random patterns
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "generate", rename_all = "snake_case")]
+pub enum BodyPattern {
+    /// Counts along X, Y and Z, filled X first
+    Grid { counts: [u32; 3], spacing: Vec3 },
+    /// Evenly stepped copies, each turned a further `rotation_step` (roll, pitch, yaw in rad)
+    Line {
+        count: u32,
+        step: Vec3,
+        #[serde(default)]
+        rotation_step: Vec3,
+    },
+    /// Copies on a horizontal circle around the template position
+    Ring {
+        count: u32,
+        radius: f32,
+        /// Turn each copy so its local +X axis points away from the center
+        #[serde(default)]
+        align: bool,
+    },
+    /// Copies resting on top of each other, `gap` apart
+    Stack {
+        count: u32,
+        #[serde(default)]
+        gap: f32,
+    },
+    /// Copies at uniformly random offsets inside a box
+    Random {
+        count: u32,
+        min: Vec3,
+        max: Vec3,
+        #[serde(default)]
+        random_rotation: bool,
+    },
+}
+
+/// Offset and extra rotation of one generated copy
+struct Placement {
+    offset: Vector3<f32>,
+    rotation: UnitQuaternion<f32>,
+}
+
+impl Placement {
+    fn offset(offset: Vector3<f32>) -> Self {
+        Self { offset, rotation: UnitQuaternion::identity() }
+    }
+}
+
+impl BodyGenerator {
+    /// Number of bodies the generator expands into
+    pub fn count(&self) -> usize {
+        match &self.pattern {
+            BodyPattern::Grid { counts, .. } => counts.iter().map(|&c| c as usize).product(),
+            BodyPattern::Line { count, .. }
+            | BodyPattern::Ring { count, .. }
+            | BodyPattern::Stack { count, .. }
+            | BodyPattern::Random { count, .. } => *count as usize,
+        }
+    }
+
+    /// Name of the `index`-th generated body
+    pub fn body_name(&self, index: usize) -> String {
+        format!("{}_{}", self.template.name, index)
+    }
+
+    /// Expand into bodies; `seed` is used by random patterns unless the generator sets its own
+    pub fn expand(&self, seed: u64) -> Vec<BodyConfig> {
+        let mut rng = ChaCha8Rng::seed_from_u64(self.seed.unwrap_or(seed));
+        let template_rotation = self.template.rotation
+            .map(|[x, y, z, w]| UnitQuaternion::from_quaternion(nalgebra::Quaternion::new(w, x, y, z)))
+            .unwrap_or_else(UnitQuaternion::identity);
+
+        (0..self.count())
+            .map(|i| {
+                let placement = self.placement(i, &mut rng);
+                let rotation = placement.rotation * template_rotation;
+                let mut body = self.template.clone();
+                body.name = self.body_name(i);
+                body.position = self.template.position + Vec3::from_nalgebra(&placement.offset);
+                body.rotation = Some([rotation.i, rotation.j, rotation.k, rotation.w]);
+                body
+            })
+            .collect()
+    }
+
+    fn placement(&self, index: usize, rng: &mut ChaCha8Rng) -> Placement {
+        let i = index as f32;
+        match &self.pattern {
+            BodyPattern::Grid { counts, spacing } => {
+                let (nx, ny) = (counts[0].max(1) as usize, counts[1].max(1) as usize);
+                let cell = Vector3::new(index % nx, (index / nx) % ny, index / (nx * ny));
+                Placement::offset(cell.cast::<f32>().component_mul(&spacing.to_nalgebra()))
+            }
+            BodyPattern::Line { step, rotation_step, .. } => Placement {
+                offset: step.to_nalgebra() * i,
+                rotation: UnitQuaternion::from_euler_angles(rotation_step.x * i, rotation_step.y * i, rotation_step.z * i),
+            },
+            BodyPattern::Ring { count, radius, align } => {
+                let angle = TAU * i / (*count).max(1) as f32;
+                Placement {
+                    offset: Vector3::new(angle.cos(), 0.0, angle.sin()) * *radius,
+                    // A rotation of -angle about +Y turns the local +X axis outwards
+                    rotation: if *align {
+                        UnitQuaternion::from_axis_angle(&Vector3::y_axis(), -angle)
+                    } else {
+                        UnitQuaternion::identity()
+                    },
+                }
+            }
+            BodyPattern::Stack { gap, .. } => {
+                let height = 2.0 * half_height(&self.template.shape);
+                Placement::offset(Vector3::y() * (height + gap) * i)
+            }
+            BodyPattern::Random { min, max, random_rotation, .. } => {
+                let mut sample = |lo: f32, hi: f32| if hi > lo { rng.gen_range(lo..hi) } else { lo };
+                let offset = Vector3::new(sample(min.x, max.x), sample(min.y, max.y), sample(min.z, max.z));
+                let rotation = if *random_rotation {
+                    // Uniformly distributed rotation from three uniform samples
+                    let (u1, u2, u3): (f32, f32, f32) = (rng.gen(), rng.gen(), rng.gen());
+                    let (a, b) = ((1.0 - u1).sqrt(), u1.sqrt());
+                    UnitQuaternion::from_quaternion(nalgebra::Quaternion::new(
+                        a * (TAU * u2).sin(),
+                        a * (TAU * u2).cos(),
+                        b * (TAU * u3).sin(),
+                        b * (TAU * u3).cos(),
+                    ))
+                } else {
+                    UnitQuaternion::identity()
+                };
+                Placement { offset, rotation }
+            }
+        }
+    }
+
+    pub(crate) fn validate(&self, errors: &mut Vec<String>) {
+        let name = &self.template.name;
+        if self.count() == 0 {
+            errors.push(format!("Generator {}: count must be at least 1", name));
+        }
+        if let BodyPattern::Random { min, max, .. } = &self.pattern {
+            if min.x > max.x || min.y > max.y || min.z > max.z {
+                errors.push(format!("Generator {}: min must not exceed max", name));
+            }
+        }
+    }
+}
+
+/// Half of a shape's extent along its local Y axis
+fn half_height(shape: &ShapeConfig) -> f32 {
+    match shape {
+        ShapeConfig::Box { half_extents } => half_extents.y,
+        ShapeConfig::Sphere { radius } => *radius,
+        ShapeConfig::Capsule { half_height, radius } => half_height + radius,
+        ShapeConfig::Cylinder { half_height, .. } => *half_height,
+    }
+}
+
+/// Expand generator entries into plain bodies, in list order.
+///
+/// Each generator without its own seed gets `seed` offset by its position in
+/// the list, so two random generators do not produce the same layout.
+pub fn expand_bodies(entries: &[BodyEntry], seed: Option<u64>) -> Vec<BodyConfig> {
+    let seed = seed.unwrap_or(0);
+    entries
+        .iter()
+        .enumerate()
+        .flat_map(|(i, entry)| match entry {
+            BodyEntry::Body(body) => vec![body.clone()],
+            BodyEntry::Generator(generator) => generator.expand(seed.wrapping_add(i as u64)),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn template(yaml: &str) -> BodyEntry {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    #[test]
+    fn test_grid_and_stack_layouts() {
+        let grid = template(r#"
+generate: grid
+counts: [3, 1, 2]
+spacing: [2, 0, 1]
+template:
+  name: crate
+  shape: { type: box, half_extents: [0.5, 0.5, 0.5] }
+  position: [0, 0.5, 0]
+"#);
+        let bodies = expand_bodies(&[grid], None);
+        assert_eq!(bodies.len(), 6);
+        assert_eq!(bodies[5].name, "crate_5");
+        assert_eq!(bodies[5].position, Vec3::new(4.0, 0.5, 1.0));
+
+        let stack = template(r#"
+generate: stack
+count: 3
+gap: 0.1
+template:
+  name: ball
+  shape: { type: sphere, radius: 0.5 }
+  position: [0, 0.5, 0]
+"#);
+        let bodies = expand_bodies(&[stack], None);
+        assert!((bodies[2].position.y - 2.7).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_random_layout_follows_seed() {
+        let random = template(r#"
+generate: random
+count: 20
+min: [-1, 0, -1]
+max: [1, 2, 1]
+random_rotation: true
+template:
+  name: pebble
+  shape: { type: sphere, radius: 0.1 }
+  position: [0, 5, 0]
+"#);
+        let a = expand_bodies(std::slice::from_ref(&random), Some(7));
+        let b = expand_bodies(std::slice::from_ref(&random), Some(7));
+        let c = expand_bodies(std::slice::from_ref(&random), Some(8));
+        assert_eq!(a[19].position, b[19].position);
+        assert_ne!(a[19].position, c[19].position);
+        assert!(a.iter().all(|p| p.position.y >= 5.0 && p.position.y < 7.0));
+    }
+}
@@ -7,9 +7,12 @@ pub mod spec;
 pub mod metrics;
 pub mod report;
 pub mod error;
+pub mod events;
+pub mod generators;
 
 pub use math::{Vec3, Quat, Transform};
 pub use spec::{ExperimentSpec, PhysicsConfig, DurationConfig, ScenarioConfig, MetricsConfig, CriteriaConfig};
-pub use metrics::{MetricFrame, AggregateMetrics, ContactMetrics, EnergyMetrics, MomentumMetrics};
-pub use report::{SimulationReport, CriterionResult, BaselineComparison, ReportStatus};
+pub use metrics::{MetricFrame, AggregateMetrics, ContactMetrics, ContactPairForce, EnergyMetrics, MomentumMetrics};
+pub use report::{SimulationReport, CriterionResult, BaselineComparison, ReportStatus, PerformanceMetrics};
 pub use error::SimuForgeError;
+pub use events::{SimEvent, EventKind};
@@ -1,7 +1,9 @@
 //! Metric types for simulation analysis
 
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use crate::{Vec3, Transform};
+use crate::events::{EventKind, SimEvent, pair_key};
 
 /// Per-frame metrics collected during simulation
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -11,8 +13,15 @@ pub struct MetricFrame {
     pub energy: EnergyMetrics,
     pub momentum: MomentumMetrics,
     pub contacts: ContactMetrics,
+    /// Gravity acting on the step that follows this frame
+    #[serde(default)]
+    pub gravity: Vec3,
     #[serde(default)]
     pub bodies: Vec<BodyState>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub events: Vec<SimEvent>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub actuators: Vec<ActuatorState>,
 }
 
 impl MetricFrame {
@@ -23,7 +32,10 @@ impl MetricFrame {
             energy: EnergyMetrics::default(),
             momentum: MomentumMetrics::default(),
             contacts: ContactMetrics::default(),
+            gravity: Vec3::ZERO,
             bodies: Vec::new(),
+            events: Vec::new(),
+            actuators: Vec::new(),
         }
     }
 }
@@ -73,8 +85,34 @@ pub struct ContactMetrics {
     pub max_penetration: f32,
     pub total_penetration: f32,
     pub constraint_violations: u32,
+    #[serde(default)]
+    pub total_normal_impulse: f32,
+    #[serde(default)]
+    pub max_normal_impulse: f32,
+    #[serde(default)]
+    pub max_tangent_impulse: f32,
+    /// Largest tangential/normal impulse ratio relative to the friction coefficient.
+    /// A value of 1.0 means the contact sits on the friction cone and is slipping.
+    #[serde(default)]
+    pub max_friction_ratio: f32,
+    #[serde(default)]
+    pub slipping_contacts: u32,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pairs: Vec<ContactPairForce>,
+}
+
+/// Forces exchanged between one pair of bodies during a step
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ContactPairForce {
+    pub body_a: String,
+    pub body_b: String,
+    pub normal_force: f32,
+    pub tangent_force: f32,
 }
 
+/// Friction ratio above which a contact point is counted as slipping
+pub const SLIP_RATIO_THRESHOLD: f32 = 0.99;
+
 /// State of a single body
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BodyState {
@@ -86,6 +124,21 @@ pub struct BodyState {
     pub sleeping: bool,
 }
 
+/// Tracking state of an actuated joint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActuatorState {
+    pub joint: String,
+    /// Joint coordinate: angle (rad) for revolute joints, offset for prismatic joints
+    pub position: f32,
+    pub velocity: f32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub setpoint: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_velocity: Option<f32>,
+    /// Distance to the setpoint, or to the target velocity when no setpoint is set
+    pub tracking_error: f32,
+}
+
 /// Aggregated metrics computed at the end of simulation
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct AggregateMetrics {
@@ -98,6 +151,37 @@ pub struct AggregateMetrics {
     pub stability_time: Option<f32>,
     pub average_contact_count: f32,
     pub frame_count: u64,
+    #[serde(default)]
+    pub collision_count: u64,
+    #[serde(default)]
+    pub peak_contact_force: f32,
+    /// Collision starts per body pair, keyed by `pair_key`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub collision_counts: BTreeMap<String, u64>,
+    /// Sensor entries per sensor body name
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub sensor_entry_counts: BTreeMap<String, u64>,
+    #[serde(default)]
+    pub max_normal_impulse_ever: f32,
+    #[serde(default)]
+    pub max_tangent_impulse_ever: f32,
+    #[serde(default)]
+    pub max_pair_normal_force: f32,
+    #[serde(default)]
+    pub max_friction_ratio: f32,
+    /// Fraction of frames with at least one slipping contact
+    #[serde(default)]
+    pub slip_frame_fraction: f32,
+    #[serde(default)]
+    pub max_tracking_error: f32,
+    #[serde(default)]
+    pub rms_tracking_error: f32,
+    /// Parameters changed mid-run, explaining shifts in the other metrics
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub parameter_changes: Vec<SimEvent>,
+    /// Metrics computed by the scenario itself, keyed by metric name
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub scenario: BTreeMap<String, f64>,
 }
 
 impl AggregateMetrics {
@@ -137,6 +221,68 @@ impl AggregateMetrics {
             .and_then(|step| frames.iter().find(|f| f.step == step))
             .map(|f| f.time);
 
+        let max_normal_impulse_ever = frames
+            .iter()
+            .map(|f| f.contacts.max_normal_impulse)
+            .fold(0.0f32, f32::max);
+
+        let max_tangent_impulse_ever = frames
+            .iter()
+            .map(|f| f.contacts.max_tangent_impulse)
+            .fold(0.0f32, f32::max);
+
+        let max_pair_normal_force = frames
+            .iter()
+            .flat_map(|f| f.contacts.pairs.iter())
+            .map(|p| p.normal_force)
+            .fold(0.0f32, f32::max);
+
+        let max_friction_ratio = frames
+            .iter()
+            .map(|f| f.contacts.max_friction_ratio)
+            .fold(0.0f32, f32::max);
+
+        let slip_frames = frames.iter().filter(|f| f.contacts.slipping_contacts > 0).count();
+        let slip_frame_fraction = slip_frames as f32 / frames.len() as f32;
+
+        let tracking_errors: Vec<f32> = frames
+            .iter()
+            .flat_map(|f| f.actuators.iter())
+            .map(|a| a.tracking_error)
+            .collect();
+        let max_tracking_error = tracking_errors.iter().copied().fold(0.0f32, f32::max);
+        let rms_tracking_error = if tracking_errors.is_empty() {
+            0.0
+        } else {
+            (tracking_errors.iter().map(|e| e * e).sum::<f32>() / tracking_errors.len() as f32).sqrt()
+        };
+
+        let mut collision_count = 0;
+        let mut peak_contact_force = 0.0f32;
+        let mut collision_counts = BTreeMap::new();
+        let mut sensor_entry_counts = BTreeMap::new();
+        let mut parameter_changes = Vec::new();
+
+        for event in frames.iter().flat_map(|f| f.events.iter()) {
+            match &event.kind {
+                EventKind::CollisionStarted { body_a, body_b } => {
+                    collision_count += 1;
+                    *collision_counts.entry(pair_key(body_a, body_b)).or_insert(0) += 1;
+                }
+                EventKind::SensorEntered { sensor, .. } => {
+                    *sensor_entry_counts.entry(sensor.clone()).or_insert(0) += 1;
+                }
+                EventKind::ContactForce { max_force_magnitude, .. } => {
+                    peak_contact_force = peak_contact_force.max(*max_force_magnitude);
+                }
+                EventKind::ParameterChanged { .. } => parameter_changes.push(event.clone()),
+                EventKind::CollisionStopped { .. }
+                | EventKind::SensorExited { .. }
+                | EventKind::ActionApplied { .. }
+                | EventKind::ActionFailed { .. } => {}
+            }
+        }
+
         Self {
             energy_drift_percent,
             initial_energy,
@@ -147,6 +293,19 @@ impl AggregateMetrics {
             stability_time,
             average_contact_count,
             frame_count: frames.len() as u64,
+            collision_count,
+            peak_contact_force,
+            collision_counts,
+            sensor_entry_counts,
+            max_normal_impulse_ever,
+            max_tangent_impulse_ever,
+            max_pair_normal_force,
+            max_friction_ratio,
+            slip_frame_fraction,
+            max_tracking_error,
+            rms_tracking_error,
+            parameter_changes,
+            scenario: BTreeMap::new(),
         }
     }
 }
@@ -170,7 +329,10 @@ mod tests {
                 energy: EnergyMetrics::new(100.0, 0.0),
                 momentum: MomentumMetrics::default(),
                 contacts: ContactMetrics::default(),
+                gravity: Default::default(),
                 bodies: vec![],
+                events: vec![],
+                actuators: vec![],
             },
             MetricFrame {
                 step: 1,
@@ -178,7 +340,10 @@ mod tests {
                 energy: EnergyMetrics::new(98.0, 0.0),
                 momentum: MomentumMetrics::default(),
                 contacts: ContactMetrics { max_penetration: 0.001, ..Default::default() },
+                gravity: Default::default(),
                 bodies: vec![],
+                events: vec![],
+                actuators: vec![],
             },
         ];
 
@@ -186,4 +351,52 @@ mod tests {
         assert!((agg.energy_drift_percent - (-2.0)).abs() < 0.1);
         assert_eq!(agg.max_penetration_ever, 0.001);
     }
+
+    #[test]
+    fn test_aggregate_event_counts() {
+        let touch = |step| SimEvent::new(step, step as f32 * 0.016, EventKind::CollisionStarted {
+            body_a: "ground".to_string(),
+            body_b: "ball".to_string(),
+        });
+        let mut frame = MetricFrame::new(1, 0.016);
+        frame.events = vec![
+            touch(1),
+            touch(1),
+            SimEvent::new(1, 0.016, EventKind::ContactForce {
+                body_a: "ball".to_string(),
+                body_b: "ground".to_string(),
+                total_force_magnitude: 40.0,
+                max_force_magnitude: 25.0,
+            }),
+        ];
+
+        let agg = AggregateMetrics::compute(&[MetricFrame::new(0, 0.0), frame]);
+        assert_eq!(agg.collision_count, 2);
+        assert_eq!(agg.collision_counts.get("ball.ground"), Some(&2));
+        assert_eq!(agg.peak_contact_force, 25.0);
+    }
+
+    #[test]
+    fn test_aggregate_contact_impulses() {
+        let mut resting = MetricFrame::new(0, 0.0);
+        resting.contacts.max_normal_impulse = 0.5;
+        resting.contacts.max_friction_ratio = 0.2;
+
+        let mut sliding = MetricFrame::new(1, 0.016);
+        sliding.contacts.max_normal_impulse = 2.0;
+        sliding.contacts.max_friction_ratio = 1.0;
+        sliding.contacts.slipping_contacts = 4;
+        sliding.contacts.pairs.push(ContactPairForce {
+            body_a: "box".to_string(),
+            body_b: "ground".to_string(),
+            normal_force: 120.0,
+            tangent_force: 36.0,
+        });
+
+        let agg = AggregateMetrics::compute(&[resting, sliding]);
+        assert_eq!(agg.max_normal_impulse_ever, 2.0);
+        assert_eq!(agg.max_pair_normal_force, 120.0);
+        assert_eq!(agg.max_friction_ratio, 1.0);
+        assert!((agg.slip_frame_fraction - 0.5).abs() < 1e-6);
+    }
 }
@@ -1,8 +1,8 @@
 //! Simulation report types
 
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use crate::{AggregateMetrics, MetricFrame, spec::CriteriaConfig};
+use std::collections::{BTreeMap, HashMap};
+use crate::{AggregateMetrics, MetricFrame, spec::CriteriaConfig, events::pair_key};
 
 /// Final simulation report
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -17,6 +17,23 @@ pub struct SimulationReport {
     pub baseline_comparison: Option<BaselineComparison>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
+    /// Wall-clock cost of the run; varies between machines, so it is never compared against baselines
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub performance: Option<PerformanceMetrics>,
+}
+
+/// Wall-clock timing of a simulation run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PerformanceMetrics {
+    pub wall_time_seconds: f64,
+    pub steps_per_second: f64,
+}
+
+impl PerformanceMetrics {
+    pub fn new(steps: u64, wall_time_seconds: f64) -> Self {
+        let steps_per_second = if wall_time_seconds > 0.0 { steps as f64 / wall_time_seconds } else { 0.0 };
+        Self { wall_time_seconds, steps_per_second }
+    }
 }
 
 impl SimulationReport {
@@ -30,6 +47,7 @@ impl SimulationReport {
             criteria_results: HashMap::new(),
             baseline_comparison: None,
             error: None,
+            performance: None,
         }
     }
 
@@ -43,6 +61,7 @@ impl SimulationReport {
             criteria_results: HashMap::new(),
             baseline_comparison: None,
             error: Some(error),
+            performance: None,
         }
     }
 
@@ -50,6 +69,16 @@ impl SimulationReport {
         &mut self,
         frames: &[MetricFrame],
         criteria: &HashMap<String, CriteriaConfig>,
+    ) {
+        self.finalize_with_scenario(frames, criteria, BTreeMap::new());
+    }
+
+    /// Finalize with additional scenario-specific metrics, which criteria can reference by name
+    pub fn finalize_with_scenario(
+        &mut self,
+        frames: &[MetricFrame],
+        criteria: &HashMap<String, CriteriaConfig>,
+        scenario_metrics: BTreeMap<String, f64>,
     ) {
         if let Some(last_frame) = frames.last() {
             self.total_steps = last_frame.step;
@@ -57,6 +86,7 @@ impl SimulationReport {
         }
 
         self.metrics = AggregateMetrics::compute(frames);
+        self.metrics.scenario = scenario_metrics;
         self.evaluate_criteria(criteria);
     }
 
@@ -66,21 +96,21 @@ impl SimulationReport {
         let mut results = Vec::new();
 
         for (name, config) in criteria {
-            if let Some(&value) = metric_values.get(name.as_str()) {
-                let passed = config.evaluate(value);
-                if !passed {
-                    all_passed = false;
-                }
-                results.push((
-                    name.clone(),
-                    CriterionResult {
-                        value,
-                        min: config.min,
-                        max: config.max,
-                        passed,
-                    },
-                ));
+            // A metric the run could not produce fails its criterion rather than being skipped
+            let value = Self::lookup_metric(&metric_values, name).unwrap_or(f64::NAN);
+            let passed = config.evaluate(value);
+            if !passed {
+                all_passed = false;
             }
+            results.push((
+                name.clone(),
+                CriterionResult {
+                    value,
+                    min: config.min,
+                    max: config.max,
+                    passed,
+                },
+            ));
         }
 
         for (name, result) in results {
@@ -94,18 +124,54 @@ impl SimulationReport {
         };
     }
 
-    fn get_metric_values(&self) -> HashMap<&str, f64> {
+    fn get_metric_values(&self) -> HashMap<String, f64> {
         let mut values = HashMap::new();
-        values.insert("energy_drift_percent", self.metrics.energy_drift_percent);
-        values.insert("max_penetration_ever", self.metrics.max_penetration_ever as f64);
-        values.insert("total_constraint_violations", self.metrics.total_constraint_violations as f64);
-        values.insert("average_contact_count", self.metrics.average_contact_count as f64);
+        values.insert("energy_drift_percent".to_string(), self.metrics.energy_drift_percent);
+        values.insert("max_penetration_ever".to_string(), self.metrics.max_penetration_ever as f64);
+        values.insert("total_constraint_violations".to_string(), self.metrics.total_constraint_violations as f64);
+        values.insert("average_contact_count".to_string(), self.metrics.average_contact_count as f64);
         if let Some(step) = self.metrics.stabilization_step {
-            values.insert("stabilization_step", step as f64);
+            values.insert("stabilization_step".to_string(), step as f64);
+        }
+        values.insert("collision_count".to_string(), self.metrics.collision_count as f64);
+        values.insert("peak_contact_force".to_string(), self.metrics.peak_contact_force as f64);
+        values.insert("max_normal_impulse_ever".to_string(), self.metrics.max_normal_impulse_ever as f64);
+        values.insert("max_tangent_impulse_ever".to_string(), self.metrics.max_tangent_impulse_ever as f64);
+        values.insert("max_pair_normal_force".to_string(), self.metrics.max_pair_normal_force as f64);
+        values.insert("max_friction_ratio".to_string(), self.metrics.max_friction_ratio as f64);
+        values.insert("slip_frame_fraction".to_string(), self.metrics.slip_frame_fraction as f64);
+        values.insert("max_tracking_error".to_string(), self.metrics.max_tracking_error as f64);
+        values.insert("rms_tracking_error".to_string(), self.metrics.rms_tracking_error as f64);
+        for (pair, count) in &self.metrics.collision_counts {
+            values.insert(format!("collisions.{}", pair), *count as f64);
+        }
+        for (sensor, count) in &self.metrics.sensor_entry_counts {
+            values.insert(format!("sensor_entries.{}", sensor), *count as f64);
+        }
+        for (name, value) in &self.metrics.scenario {
+            values.entry(name.clone()).or_insert(*value);
         }
         values
     }
 
+    /// Resolve a criterion name to a metric value.
+    ///
+    /// Event counters are sparse, so a pair that never collided reads as zero
+    /// rather than failing the criterion as missing.
+    fn lookup_metric(values: &HashMap<String, f64>, name: &str) -> Option<f64> {
+        if let Some(&value) = values.get(name) {
+            return Some(value);
+        }
+        if let Some(pair) = name.strip_prefix("collisions.") {
+            let (a, b) = pair.split_once('.')?;
+            return Some(values.get(&format!("collisions.{}", pair_key(a, b))).copied().unwrap_or(0.0));
+        }
+        if name.starts_with("sensor_entries.") {
+            return Some(0.0);
+        }
+        None
+    }
+
     pub fn compare_baseline(&mut self, baseline: &SimulationReport) {
         let mut metrics_improved = Vec::new();
         let mut metrics_regressed = Vec::new();
@@ -158,9 +224,15 @@ pub enum ReportStatus {
     Error,
 }
 
+fn nan_if_null<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<f64, D::Error> {
+    Ok(Option::<f64>::deserialize(deserializer)?.unwrap_or(f64::NAN))
+}
+
 /// Result of evaluating a single criterion
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CriterionResult {
+    /// NaN when the run did not produce the metric; JSON writes that as null
+    #[serde(deserialize_with = "nan_if_null")]
     pub value: f64,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub min: Option<f64>,
@@ -214,4 +286,73 @@ mod tests {
         assert!(comparison.metrics_improved.contains(&"max_penetration".to_string()));
         assert_eq!(comparison.recommendation, ComparisonRecommendation::Accept);
     }
+
+    #[test]
+    fn test_collision_count_criteria() {
+        let mut report = SimulationReport::new("bounces".to_string());
+        report.metrics.collision_counts.insert("ball.ground".to_string(), 5);
+
+        let mut criteria = HashMap::new();
+        criteria.insert("collisions.ground.ball".to_string(), CriteriaConfig {
+            min: None,
+            max: None,
+            equals: Some(5.0),
+            tolerance: None,
+        });
+        criteria.insert("collisions.ball.wall".to_string(), CriteriaConfig {
+            min: None,
+            max: Some(0.0),
+            equals: None,
+            tolerance: None,
+        });
+        report.evaluate_criteria(&criteria);
+
+        assert_eq!(report.status, ReportStatus::Passed);
+        assert_eq!(report.criteria_results["collisions.ground.ball"].value, 5.0);
+        assert_eq!(report.criteria_results["collisions.ball.wall"].value, 0.0);
+    }
+
+    #[test]
+    fn test_performance_is_not_a_criterion() {
+        let mut report = SimulationReport::new("timed".to_string());
+        report.performance = Some(PerformanceMetrics::new(100, 0.5));
+
+        let mut criteria = HashMap::new();
+        criteria.insert("steps_per_second".to_string(), CriteriaConfig {
+            min: Some(0.0),
+            max: None,
+            equals: None,
+            tolerance: None,
+        });
+        report.evaluate_criteria(&criteria);
+
+        // Wall-clock timing depends on the machine, so it is not a metric criteria can see
+        assert_eq!(report.status, ReportStatus::Failed);
+        assert!(report.criteria_results["steps_per_second"].value.is_nan());
+    }
+
+    #[test]
+    fn test_missing_metric_fails_criterion() {
+        let mut report = SimulationReport::new("short".to_string());
+        report.metrics.scenario.insert("energy_retained_per_cycle".to_string(), f64::NAN);
+
+        let mut criteria = HashMap::new();
+        for name in ["energy_retained_per_cycle", "stabilization_step"] {
+            criteria.insert(name.to_string(), CriteriaConfig {
+                min: Some(0.0),
+                max: None,
+                equals: None,
+                tolerance: None,
+            });
+        }
+        report.evaluate_criteria(&criteria);
+
+        assert_eq!(report.status, ReportStatus::Failed);
+        assert!(report.criteria_results.values().all(|result| !result.passed));
+
+        // Saved as a baseline, the missing value must read back
+        let json = serde_json::to_string(&report.criteria_results).unwrap();
+        let loaded: HashMap<String, CriterionResult> = serde_json::from_str(&json).unwrap();
+        assert!(loaded["stabilization_step"].value.is_nan());
+    }
 }
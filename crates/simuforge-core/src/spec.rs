@@ -3,6 +3,7 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use crate::Vec3;
+use crate::generators::{expand_bodies, BodyEntry};
 
 /// Root experiment specification
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -38,6 +39,21 @@ impl ExperimentSpec {
             errors.push("Solver iterations must be at least 1".to_string());
         }
 
+        self.spec.scenario.validate(self.spec.physics.seed, &mut errors);
+
+        for actuator in &self.spec.actuators {
+            actuator.validate(&mut errors);
+        }
+
+        for (i, action) in self.spec.actions.iter().enumerate() {
+            match (action.step, action.time) {
+                (Some(_), None) => {}
+                (None, Some(time)) if time >= 0.0 => {}
+                (None, Some(_)) => errors.push(format!("Action {}: time must not be negative", i)),
+                _ => errors.push(format!("Action {}: exactly one of step or time must be set", i)),
+            }
+        }
+
         if errors.is_empty() {
             Ok(())
         } else {
@@ -66,6 +82,10 @@ pub struct ExperimentConfig {
     pub metrics: MetricsConfig,
     #[serde(default)]
     pub criteria: HashMap<String, CriteriaConfig>,
+    #[serde(default)]
+    pub actions: Vec<ActionConfig>,
+    #[serde(default)]
+    pub actuators: Vec<ActuatorConfig>,
 }
 
 /// Physics engine configuration
@@ -126,8 +146,199 @@ pub enum ScenarioConfig {
         params: HashMap<String, serde_yaml::Value>,
     },
     Custom {
-        bodies: Vec<BodyConfig>,
+        bodies: Vec<BodyEntry>,
+        #[serde(default)]
+        joints: Vec<JointConfig>,
+    },
+    /// Several scenarios placed side by side, plus extra custom bodies and joints
+    Composite {
+        instances: Vec<ScenarioInstance>,
+        #[serde(default)]
+        bodies: Vec<BodyEntry>,
+        #[serde(default)]
+        joints: Vec<JointConfig>,
+    },
+}
+
+/// Separator between an instance name and the body, joint or metric names inside it
+pub const INSTANCE_SEPARATOR: char = '/';
+
+/// Scenario placed inside a composite under its own name prefix
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScenarioInstance {
+    /// Prefix for the instance's body, joint and metric names
+    pub name: String,
+    /// Translation applied to every body of the instance
+    #[serde(default)]
+    pub offset: Vec3,
+    /// Scenario to instance; filled in from `include` when the experiment is loaded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scenario: Option<Box<ScenarioConfig>>,
+    /// Experiment file, relative to the including file, whose scenario is instanced
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub include: Option<String>,
+    /// Whether the instance keeps the static body named `ground` its scenario creates
+    #[serde(default)]
+    pub ground: InstanceGround,
+}
+
+/// Ground handling for a composite instance
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InstanceGround {
+    /// Keep the scenario's own ground
+    #[default]
+    Own,
+    /// Drop the scenario's ground and rest on one provided by another instance or the composite's bodies
+    Shared,
+}
+
+impl ScenarioConfig {
+    fn validate(&self, seed: Option<u64>, errors: &mut Vec<String>) {
+        match self {
+            ScenarioConfig::Builtin { .. } => {}
+            ScenarioConfig::Custom { bodies, joints } => {
+                let bodies = validate_bodies(bodies, seed, errors);
+                for joint in joints {
+                    joint.validate(errors);
+                    for name in [&joint.body_a, &joint.body_b] {
+                        if !bodies.iter().any(|b| &b.name == name) {
+                            errors.push(format!("Joint {}: unknown body {}", joint.name, name));
+                        }
+                    }
+                }
+            }
+            ScenarioConfig::Composite { instances, bodies, joints } => {
+                for (i, instance) in instances.iter().enumerate() {
+                    if instance.name.is_empty() || instance.name.contains(INSTANCE_SEPARATOR) {
+                        errors.push(format!("Instance {}: name must be non-empty and must not contain '{}'", i, INSTANCE_SEPARATOR));
+                    }
+                    if instances[..i].iter().any(|other| other.name == instance.name) {
+                        errors.push(format!("Instance {}: duplicate name", instance.name));
+                    }
+                    match (&instance.scenario, &instance.include) {
+                        (Some(scenario), _) => scenario.validate(seed, errors),
+                        (None, Some(include)) => errors.push(format!(
+                            "Instance {}: include {} is not resolved; load the experiment from its file",
+                            instance.name, include
+                        )),
+                        (None, None) => errors.push(format!("Instance {}: scenario or include is required", instance.name)),
+                    }
+                }
+                let bodies = validate_bodies(bodies, seed, errors);
+                // Names with an instance prefix refer to bodies created by that instance
+                for joint in joints {
+                    joint.validate(errors);
+                    for name in [&joint.body_a, &joint.body_b] {
+                        let known = match name.split_once(INSTANCE_SEPARATOR) {
+                            Some((prefix, _)) => instances.iter().any(|i| i.name == prefix),
+                            None => bodies.iter().any(|b| &b.name == name),
+                        };
+                        if !known {
+                            errors.push(format!("Joint {}: unknown body {}", joint.name, name));
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Validate body entries and return them with generators expanded
+fn validate_bodies(entries: &[BodyEntry], seed: Option<u64>, errors: &mut Vec<String>) -> Vec<BodyConfig> {
+    for entry in entries {
+        if let BodyEntry::Generator(generator) = entry {
+            generator.validate(errors);
+        }
+    }
+    let bodies = expand_bodies(entries, seed);
+    for (i, body) in bodies.iter().enumerate() {
+        body.validate(errors);
+        if bodies[..i].iter().any(|other| other.name == body.name) {
+            errors.push(format!("Body {}: duplicate name", body.name));
+        }
+    }
+    bodies
+}
+
+/// Joint connecting two named bodies
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JointConfig {
+    pub name: String,
+    pub body_a: String,
+    pub body_b: String,
+    #[serde(flatten)]
+    pub kind: JointKind,
+    /// Anchor in body A's local frame
+    #[serde(default)]
+    pub anchor_a: Vec3,
+    /// Anchor in body B's local frame
+    #[serde(default)]
+    pub anchor_b: Vec3,
+    /// Build as a reduced-coordinate multibody link of body A instead of an impulse constraint
+    #[serde(default)]
+    pub multibody: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum JointKind {
+    Fixed,
+    Revolute {
+        axis: Vec3,
+        #[serde(default)]
+        limits: Option<[f32; 2]>,
+    },
+    Prismatic {
+        axis: Vec3,
+        #[serde(default)]
+        limits: Option<[f32; 2]>,
     },
+    Spherical,
+}
+
+/// Motor driving a revolute or prismatic joint towards a target
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActuatorConfig {
+    pub joint: String,
+    #[serde(default)]
+    pub target_position: Option<f32>,
+    #[serde(default)]
+    pub target_velocity: Option<f32>,
+    #[serde(default)]
+    pub stiffness: f32,
+    #[serde(default)]
+    pub damping: f32,
+    #[serde(default)]
+    pub max_force: Option<f32>,
+}
+
+impl JointConfig {
+    fn validate(&self, errors: &mut Vec<String>) {
+        if let JointKind::Revolute { axis, limits } | JointKind::Prismatic { axis, limits } = &self.kind {
+            let length = axis.magnitude();
+            if !length.is_finite() || length < 1e-6 {
+                errors.push(format!("Joint {}: axis must be finite and non-zero", self.name));
+            }
+            if limits.is_some_and(|[min, max]| min.is_nan() || max.is_nan() || min > max) {
+                errors.push(format!("Joint {}: limits must be [min, max] with min <= max", self.name));
+            }
+        }
+    }
+}
+
+impl ActuatorConfig {
+    fn validate(&self, errors: &mut Vec<String>) {
+        if self.target_position.is_none() && self.target_velocity.is_none() {
+            errors.push(format!("Actuator {}: target_position or target_velocity is required", self.joint));
+        }
+        if self.target_position.is_some() && self.stiffness <= 0.0 {
+            errors.push(format!("Actuator {}: position target requires positive stiffness", self.joint));
+        }
+        if self.target_position.is_none() && self.target_velocity.is_some() && self.damping <= 0.0 {
+            errors.push(format!("Actuator {}: velocity target requires positive damping", self.joint));
+        }
+    }
 }
 
 /// Body configuration for custom scenarios
@@ -146,6 +357,81 @@ pub struct BodyConfig {
     pub body_type: BodyType,
     #[serde(default)]
     pub material: MaterialConfig,
+    /// Sensor colliders report enter/exit events instead of generating contacts
+    #[serde(default)]
+    pub sensor: bool,
+    /// Prescribed trajectory, only valid for kinematic bodies
+    #[serde(default)]
+    pub motion: Option<MotionConfig>,
+}
+
+impl BodyConfig {
+    fn validate(&self, errors: &mut Vec<String>) {
+        let Some(motion) = &self.motion else {
+            return;
+        };
+        if self.body_type != BodyType::Kinematic {
+            errors.push(format!("Body {}: motion requires body_type kinematic", self.name));
+        }
+        match motion {
+            MotionConfig::Keyframes { keyframes, .. } => {
+                if keyframes.is_empty() {
+                    errors.push(format!("Body {}: keyframes cannot be empty", self.name));
+                }
+                if keyframes.windows(2).any(|w| w[1].time <= w[0].time) {
+                    errors.push(format!("Body {}: keyframe times must be increasing", self.name));
+                }
+            }
+            MotionConfig::Path { waypoints, speed, .. } => {
+                if waypoints.len() < 2 {
+                    errors.push(format!("Body {}: path needs at least two waypoints", self.name));
+                }
+                if *speed <= 0.0 {
+                    errors.push(format!("Body {}: path speed must be positive", self.name));
+                }
+            }
+            MotionConfig::Oscillate { .. } | MotionConfig::Rotate { .. } => {}
+        }
+    }
+}
+
+/// Trajectory driving a kinematic body
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum MotionConfig {
+    /// Linear interpolation through absolute poses
+    Keyframes {
+        keyframes: Vec<Keyframe>,
+        #[serde(default, rename = "loop")]
+        repeat: bool,
+    },
+    /// Sinusoidal offset from the initial position: `amplitude * sin(2π f t + phase)`
+    Oscillate {
+        amplitude: Vec3,
+        frequency: f32,
+        #[serde(default)]
+        phase: f32,
+    },
+    /// Constant spin about the body's initial position (rad/s)
+    Rotate {
+        angular_velocity: Vec3,
+    },
+    /// Piecewise-linear path through absolute waypoints at constant speed
+    Path {
+        waypoints: Vec<Vec3>,
+        speed: f32,
+        #[serde(default, rename = "loop")]
+        repeat: bool,
+    },
+}
+
+/// Pose of a kinematic body at a point in time
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Keyframe {
+    pub time: f32,
+    pub position: Vec3,
+    #[serde(default)]
+    pub rotation: Option<[f32; 4]>,
 }
 
 fn default_body_type() -> BodyType { BodyType::Dynamic }
@@ -191,6 +477,121 @@ impl Default for MaterialConfig {
     }
 }
 
+/// Scripted action executed once at a given step or simulation time
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActionConfig {
+    #[serde(default)]
+    pub step: Option<u64>,
+    #[serde(default)]
+    pub time: Option<f32>,
+    #[serde(flatten)]
+    pub action: Action,
+}
+
+impl ActionConfig {
+    /// Whether the action should fire at the given step.
+    ///
+    /// Times are matched to the step whose start lies within half a timestep.
+    pub fn is_due(&self, step: u64, time: f32, timestep: f32) -> bool {
+        match (self.step, self.time) {
+            (Some(at), _) => step >= at,
+            (None, Some(at)) => time + timestep * 0.5 >= at,
+            (None, None) => true,
+        }
+    }
+}
+
+/// Action applied to the world during a run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Action {
+    /// Continuous force; applied for a single step unless `duration` (seconds) is given
+    ApplyForce {
+        body: String,
+        force: Vec3,
+        #[serde(default)]
+        duration: Option<f32>,
+    },
+    /// Continuous torque; applied for a single step unless `duration` (seconds) is given
+    ApplyTorque {
+        body: String,
+        torque: Vec3,
+        #[serde(default)]
+        duration: Option<f32>,
+    },
+    /// Instantaneous impulse, at the center of mass or at a world-space point
+    ApplyImpulse {
+        body: String,
+        impulse: Vec3,
+        #[serde(default)]
+        point: Option<Vec3>,
+    },
+    ApplyTorqueImpulse {
+        body: String,
+        torque_impulse: Vec3,
+    },
+    SetVelocity {
+        body: String,
+        #[serde(default)]
+        linear: Option<Vec3>,
+        #[serde(default)]
+        angular: Option<Vec3>,
+    },
+    Teleport {
+        body: String,
+        position: Vec3,
+        #[serde(default)]
+        rotation: Option<[f32; 4]>,
+    },
+    SetBodyType {
+        body: String,
+        body_type: BodyType,
+    },
+    Spawn {
+        body: BodyConfig,
+    },
+    Remove {
+        body: String,
+    },
+    SetGravity {
+        gravity: Vec3,
+    },
+}
+
+impl Action {
+    /// Short name used in event logs
+    pub fn kind_name(&self) -> &'static str {
+        match self {
+            Action::ApplyForce { .. } => "apply_force",
+            Action::ApplyTorque { .. } => "apply_torque",
+            Action::ApplyImpulse { .. } => "apply_impulse",
+            Action::ApplyTorqueImpulse { .. } => "apply_torque_impulse",
+            Action::SetVelocity { .. } => "set_velocity",
+            Action::Teleport { .. } => "teleport",
+            Action::SetBodyType { .. } => "set_body_type",
+            Action::Spawn { .. } => "spawn",
+            Action::Remove { .. } => "remove",
+            Action::SetGravity { .. } => "set_gravity",
+        }
+    }
+
+    /// Name of the body the action targets, if any
+    pub fn target(&self) -> Option<&str> {
+        match self {
+            Action::ApplyForce { body, .. }
+            | Action::ApplyTorque { body, .. }
+            | Action::ApplyImpulse { body, .. }
+            | Action::ApplyTorqueImpulse { body, .. }
+            | Action::SetVelocity { body, .. }
+            | Action::Teleport { body, .. }
+            | Action::SetBodyType { body, .. }
+            | Action::Remove { body } => Some(body),
+            Action::Spawn { body } => Some(&body.name),
+            Action::SetGravity { .. } => None,
+        }
+    }
+}
+
 /// Metrics collection configuration
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct MetricsConfig {
@@ -198,6 +599,9 @@ pub struct MetricsConfig {
     pub per_frame: Vec<String>,
     #[serde(default)]
     pub aggregate: Vec<String>,
+    /// Record contact force events for pairs whose total force exceeds this value
+    #[serde(default)]
+    pub contact_force_threshold: Option<f32>,
 }
 
 /// Pass/fail criteria configuration
@@ -215,6 +619,10 @@ pub struct CriteriaConfig {
 
 impl CriteriaConfig {
     pub fn evaluate(&self, value: f64) -> bool {
+        // NaN marks a metric the run could not compute
+        if value.is_nan() {
+            return false;
+        }
         if let Some(min) = self.min {
             if value < min {
                 return false;
@@ -276,4 +684,228 @@ spec:
         assert!(!criteria.evaluate(6.0));
         assert!(!criteria.evaluate(-1.0));
     }
+
+    #[test]
+    fn test_parse_actions() {
+        let yaml = r#"
+apiVersion: simuforge/v1
+kind: Experiment
+metadata:
+  name: kick-tower
+spec:
+  physics: {}
+  duration:
+    type: fixed
+    steps: 300
+  scenario:
+    type: builtin
+    name: box_stack
+  actions:
+    - time: 2.0
+      type: apply_impulse
+      body: box_9
+      impulse: [5, 0, 0]
+    - step: 10
+      type: set_gravity
+      gravity: [0, -1.62, 0]
+"#;
+        let spec: ExperimentSpec = serde_yaml::from_str(yaml).unwrap();
+        assert!(spec.validate().is_ok());
+        assert_eq!(spec.spec.actions.len(), 2);
+        assert_eq!(spec.spec.actions[0].action.kind_name(), "apply_impulse");
+        assert_eq!(spec.spec.actions[0].action.target(), Some("box_9"));
+
+        let dt = 1.0 / 60.0;
+        assert!(!spec.spec.actions[0].is_due(119, 119.0 * dt, dt));
+        assert!(spec.spec.actions[0].is_due(120, 120.0 * dt, dt));
+        assert!(spec.spec.actions[1].is_due(10, 10.0 * dt, dt));
+    }
+
+    #[test]
+    fn test_motion_requires_kinematic_body() {
+        let yaml = r#"
+apiVersion: simuforge/v1
+kind: Experiment
+metadata:
+  name: shaking-table
+spec:
+  physics: {}
+  duration:
+    type: fixed
+    steps: 100
+  scenario:
+    type: custom
+    bodies:
+      - name: table
+        shape: { type: box, half_extents: [2, 0.1, 2] }
+        position: [0, 0, 0]
+        motion: { type: oscillate, amplitude: [0.1, 0, 0], frequency: 5 }
+"#;
+        let mut spec: ExperimentSpec = serde_yaml::from_str(yaml).unwrap();
+        let errors = spec.validate().unwrap_err();
+        assert!(errors[0].contains("kinematic"));
+
+        if let ScenarioConfig::Custom { bodies, .. } = &mut spec.spec.scenario {
+            if let BodyEntry::Body(body) = &mut bodies[0] {
+                body.body_type = BodyType::Kinematic;
+            }
+        }
+        assert!(spec.validate().is_ok());
+    }
+
+    #[test]
+    fn test_parse_joints_and_actuators() {
+        let yaml = r#"
+apiVersion: simuforge/v1
+kind: Experiment
+metadata:
+  name: arm
+spec:
+  physics: {}
+  duration:
+    type: fixed
+    steps: 100
+  scenario:
+    type: custom
+    bodies:
+      - name: base
+        shape: { type: box, half_extents: [0.5, 0.5, 0.5] }
+        position: [0, 0, 0]
+        body_type: static
+      - name: link
+        shape: { type: box, half_extents: [1, 0.1, 0.1] }
+        position: [1, 0, 0]
+    joints:
+      - name: shoulder
+        type: revolute
+        body_a: base
+        body_b: link
+        axis: [0, 0, 1]
+        anchor_b: [-1, 0, 0]
+  actuators:
+    - joint: shoulder
+      target_position: 0.5
+      stiffness: 200
+      damping: 20
+"#;
+        let spec: ExperimentSpec = serde_yaml::from_str(yaml).unwrap();
+        assert!(spec.validate().is_ok());
+        match &spec.spec.scenario {
+            ScenarioConfig::Custom { joints, .. } => {
+                assert!(matches!(joints[0].kind, JointKind::Revolute { .. }));
+            }
+            _ => panic!("expected custom scenario"),
+        }
+
+        let mut bad = spec.clone();
+        bad.spec.actuators[0].stiffness = 0.0;
+        assert!(bad.validate().is_err());
+
+        let mut bad = spec.clone();
+        if let ScenarioConfig::Custom { joints, .. } = &mut bad.spec.scenario {
+            joints[0].kind = JointKind::Revolute { axis: Vec3::ZERO, limits: Some([1.0, -1.0]) };
+        }
+        assert_eq!(bad.validate().unwrap_err().len(), 2);
+    }
+
+    #[test]
+    fn test_composite_validation() {
+        let yaml = r#"
+apiVersion: simuforge/v1
+kind: Experiment
+metadata:
+  name: stacks
+spec:
+  physics: {}
+  duration:
+    type: fixed
+    steps: 100
+  scenario:
+    type: composite
+    instances:
+      - name: left
+        offset: [-3, 0, 0]
+        scenario: { type: builtin, name: box_stack }
+      - name: right
+        include: box-stack-10.yaml
+    bodies:
+      - name: anchor
+        shape: { type: sphere, radius: 0.1 }
+        position: [0, 5, 0]
+        body_type: static
+    joints:
+      - name: tether
+        type: spherical
+        body_a: anchor
+        body_b: left/box_4
+"#;
+        let mut spec: ExperimentSpec = serde_yaml::from_str(yaml).unwrap();
+        let errors = spec.validate().unwrap_err();
+        assert!(errors.len() == 1 && errors[0].contains("not resolved"), "{:?}", errors);
+
+        // Loading the file fills in the included scenario
+        if let ScenarioConfig::Composite { instances, .. } = &mut spec.spec.scenario {
+            instances[1].scenario = Some(Box::new(instances[0].scenario.as_deref().unwrap().clone()));
+        }
+        assert!(spec.validate().is_ok(), "{:?}", spec.validate());
+
+        let mut bad = spec.clone();
+        if let ScenarioConfig::Composite { instances, joints, .. } = &mut bad.spec.scenario {
+            instances[1].name = "left".to_string();
+            joints[0].body_b = "middle/box_4".to_string();
+        }
+        let errors = bad.validate().unwrap_err();
+        assert_eq!(errors.len(), 2, "{:?}", errors);
+    }
+
+    #[test]
+    fn test_generated_bodies_validation() {
+        let yaml = r#"
+apiVersion: simuforge/v1
+kind: Experiment
+metadata:
+  name: chain
+spec:
+  physics: {}
+  duration:
+    type: fixed
+    steps: 100
+  scenario:
+    type: custom
+    bodies:
+      - name: ground
+        shape: { type: box, half_extents: [10, 0.5, 10] }
+        position: [0, -0.5, 0]
+        body_type: static
+      - generate: line
+        count: 4
+        step: [1, 0, 0]
+        template:
+          name: link
+          shape: { type: sphere, radius: 0.2 }
+          position: [0, 3, 0]
+    joints:
+      - name: tail
+        type: spherical
+        body_a: link_2
+        body_b: link_3
+"#;
+        let spec: ExperimentSpec = serde_yaml::from_str(yaml).unwrap();
+        assert!(spec.validate().is_ok(), "{:?}", spec.validate());
+
+        let mut bad = spec.clone();
+        if let ScenarioConfig::Custom { joints, .. } = &mut bad.spec.scenario {
+            joints[0].body_b = "link_4".to_string();
+        }
+        assert!(bad.validate().is_err());
+
+        // An explicit body clashing with a generated name
+        let mut clash = spec.clone();
+        if let ScenarioConfig::Custom { bodies, .. } = &mut clash.spec.scenario {
+            let BodyEntry::Body(ground) = &mut bodies[0] else { panic!("expected a body") };
+            ground.name = "link_1".to_string();
+        }
+        let errors = clash.validate().unwrap_err();
+        assert_eq!(errors, vec!["Body link_1: duplicate name".to_string()]);
+    }
 }
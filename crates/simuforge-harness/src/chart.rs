@@ -0,0 +1,200 @@
+//! Headless SVG line charts of recorded runs
+
+use std::fmt::Write;
+
+const WIDTH: f32 = 800.0;
+const PANEL_HEIGHT: f32 = 240.0;
+const MARGIN_LEFT: f32 = 80.0;
+const MARGIN_RIGHT: f32 = 20.0;
+const MARGIN_TOP: f32 = 44.0;
+const MARGIN_BOTTOM: f32 = 30.0;
+const PALETTE: [&str; 6] = ["#1f77b4", "#d62728", "#2ca02c", "#ff7f0e", "#9467bd", "#17becf"];
+
+/// One line of a chart
+struct Series {
+    label: String,
+    points: Vec<(f32, f32)>,
+    color: usize,
+    dashed: bool,
+}
+
+/// Line chart over simulation time
+pub struct Chart {
+    title: String,
+    series: Vec<Series>,
+}
+
+impl Chart {
+    pub fn new(title: impl Into<String>) -> Self {
+        Self { title: title.into(), series: Vec::new() }
+    }
+
+    /// Add a line drawn in palette color `color`; dashed lines are used for baselines.
+    /// Non-finite points are left out.
+    pub fn line(&mut self, label: impl Into<String>, points: Vec<(f32, f32)>, color: usize, dashed: bool) {
+        let points = points.into_iter().filter(|(x, y)| x.is_finite() && y.is_finite()).collect();
+        self.series.push(Series { label: label.into(), points, color, dashed });
+    }
+}
+
+/// Render charts stacked vertically into one SVG document
+pub fn render_svg(charts: &[Chart]) -> String {
+    let height = PANEL_HEIGHT * charts.len() as f32;
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{WIDTH}\" height=\"{height}\" viewBox=\"0 0 {WIDTH} {height}\" \
+         font-family=\"sans-serif\" font-size=\"11\">\n<rect width=\"100%\" height=\"100%\" fill=\"white\"/>\n"
+    );
+    for (i, chart) in charts.iter().enumerate() {
+        render_panel(&mut svg, chart, PANEL_HEIGHT * i as f32);
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+fn render_panel(svg: &mut String, chart: &Chart, top: f32) {
+    let (left, right) = (MARGIN_LEFT, WIDTH - MARGIN_RIGHT);
+    let (plot_top, plot_bottom) = (top + MARGIN_TOP, top + PANEL_HEIGHT - MARGIN_BOTTOM);
+
+    let points = || chart.series.iter().flat_map(|s| &s.points);
+    let (x_min, x_max) = padded_range(points().map(|p| p.0), false);
+    let (y_min, y_max) = padded_range(points().map(|p| p.1), true);
+    let to_x = |x: f32| left + (x - x_min) / (x_max - x_min) * (right - left);
+    let to_y = |y: f32| plot_bottom - (y - y_min) / (y_max - y_min) * (plot_bottom - plot_top);
+
+    let _ = writeln!(svg, "<g>\n<text x=\"{left}\" y=\"{}\" font-size=\"14\" font-weight=\"bold\">{}</text>", top + 16.0, escape(&chart.title));
+
+    // Legend row under the title; dashed lines share a single "baseline" entry
+    let mut legend: Vec<(&str, String)> = chart.series.iter().filter(|s| !s.dashed).map(|s| (s.label.as_str(), stroke(s))).collect();
+    if chart.series.iter().any(|s| s.dashed) {
+        legend.push(("baseline", "stroke=\"#666\" stroke-width=\"1.5\" stroke-dasharray=\"5,3\"".to_string()));
+    }
+    let (mut x, y) = (left, top + 30.0);
+    for (label, stroke) in legend {
+        let _ = writeln!(
+            svg,
+            "<line x1=\"{x}\" y1=\"{y}\" x2=\"{}\" y2=\"{y}\" {stroke}/><text x=\"{}\" y=\"{}\">{}</text>",
+            x + 16.0,
+            x + 20.0,
+            y + 4.0,
+            escape(label)
+        );
+        x += 30.0 + 6.5 * label.chars().count() as f32;
+    }
+
+    // Grid lines and tick labels
+    let _ = writeln!(svg, "<rect x=\"{left}\" y=\"{plot_top}\" width=\"{}\" height=\"{}\" fill=\"none\" stroke=\"#999\"/>", right - left, plot_bottom - plot_top);
+    for (value, label) in ticks(y_min, y_max) {
+        let y = to_y(value);
+        let _ = writeln!(svg, "<line x1=\"{left}\" y1=\"{y:.1}\" x2=\"{right}\" y2=\"{y:.1}\" stroke=\"#eee\"/>");
+        let _ = writeln!(svg, "<text x=\"{}\" y=\"{:.1}\" text-anchor=\"end\">{label}</text>", left - 6.0, y + 4.0);
+    }
+    for (value, label) in ticks(x_min, x_max) {
+        let x = to_x(value);
+        let _ = writeln!(svg, "<line x1=\"{x:.1}\" y1=\"{plot_top}\" x2=\"{x:.1}\" y2=\"{plot_bottom}\" stroke=\"#eee\"/>");
+        let _ = writeln!(svg, "<text x=\"{x:.1}\" y=\"{}\" text-anchor=\"middle\">{label}</text>", plot_bottom + 16.0);
+    }
+    let _ = writeln!(svg, "<text x=\"{right}\" y=\"{}\" text-anchor=\"end\">time (s)</text>", plot_bottom + 28.0);
+
+    for series in &chart.series {
+        if series.points.is_empty() {
+            continue;
+        }
+        let coords: Vec<String> = decimate(&series.points, x_min, x_max, (right - left) as usize)
+            .iter()
+            .map(|&(x, y)| format!("{:.1},{:.1}", to_x(x), to_y(y)))
+            .collect();
+        let _ = writeln!(svg, "<polyline fill=\"none\" {} points=\"{}\"/>", stroke(series), coords.join(" "));
+    }
+    svg.push_str("</g>\n");
+}
+
+fn stroke(series: &Series) -> String {
+    let color = PALETTE[series.color % PALETTE.len()];
+    let dash = if series.dashed { " stroke-dasharray=\"5,3\"" } else { "" };
+    format!("stroke=\"{color}\" stroke-width=\"1.5\"{dash}")
+}
+
+/// Value range of the data, widened when flat; the y range also gets a little headroom
+fn padded_range(values: impl Iterator<Item = f32>, headroom: bool) -> (f32, f32) {
+    let (min, max) = values.fold((f32::INFINITY, f32::NEG_INFINITY), |(lo, hi), v| (lo.min(v), hi.max(v)));
+    if min > max {
+        return (0.0, 1.0);
+    }
+    if max - min <= f32::EPSILON * min.abs().max(1.0) {
+        let pad = (min.abs() * 0.1).max(1e-3);
+        return (min - pad, max + pad);
+    }
+    let pad = if headroom { (max - min) * 0.05 } else { 0.0 };
+    (min - pad, max + pad)
+}
+
+/// Evenly spaced tick values at 1, 2 or 5 times a power of ten, with their labels
+fn ticks(min: f32, max: f32) -> Vec<(f32, String)> {
+    let raw = (max - min) / 5.0;
+    let magnitude = 10f32.powf(raw.log10().floor());
+    let step = [1.0, 2.0, 5.0, 10.0].into_iter().map(|m| m * magnitude).find(|s| *s >= raw).unwrap_or(raw);
+    let decimals = (-step.log10().floor()).max(0.0) as usize;
+
+    let first = (min / step).ceil() as i64;
+    let last = (max / step + 1e-3).floor() as i64;
+    (first..=last)
+        .map(|i| {
+            let value = i as f32 * step;
+            // `i == 0` avoids printing "-0"
+            (value, format!("{:.*}", decimals, if i == 0 { 0.0 } else { value }))
+        })
+        .collect()
+}
+
+/// Keep the first, lowest, highest and last point of each pixel column, so long
+/// runs stay small without hiding spikes
+fn decimate(points: &[(f32, f32)], x_min: f32, x_max: f32, columns: usize) -> Vec<(f32, f32)> {
+    if points.len() <= columns * 4 {
+        return points.to_vec();
+    }
+    let column = |x: f32| ((x - x_min) / (x_max - x_min) * columns as f32) as i64;
+    let mut kept = Vec::new();
+    for bucket in points.chunk_by(|a, b| column(a.0) == column(b.0)) {
+        let low = bucket.iter().enumerate().min_by(|a, b| a.1 .1.total_cmp(&b.1 .1)).map(|(i, _)| i).unwrap_or(0);
+        let high = bucket.iter().enumerate().max_by(|a, b| a.1 .1.total_cmp(&b.1 .1)).map(|(i, _)| i).unwrap_or(0);
+        let mut indices = vec![0, low, high, bucket.len() - 1];
+        indices.sort_unstable();
+        indices.dedup();
+        kept.extend(indices.into_iter().map(|i| bucket[i]));
+    }
+    kept
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_chart_with_baseline() {
+        let mut chart = Chart::new("Energy <J>");
+        chart.line("total", (0..20000).map(|i| (i as f32 / 60.0, (i as f32).sin())).collect(), 0, false);
+        chart.line("total (baseline)", vec![(0.0, 0.0), (1.0, f32::NAN), (2.0, 1.0)], 0, true);
+        let svg = render_svg(&[chart, Chart::new("Empty")]);
+
+        assert!(svg.starts_with("<svg") && svg.trim_end().ends_with("</svg>"));
+        assert!(svg.contains("Energy &lt;J&gt;"));
+        assert_eq!(svg.matches("<polyline").count(), 2);
+        assert_eq!(svg.matches("stroke-dasharray").count(), 2, "dashed legend entry and line");
+        assert!(svg.contains(">baseline</text>") && !svg.contains("total (baseline)"));
+        // Long series are thinned to a few points per pixel column
+        let first = svg.split("<polyline").nth(1).unwrap();
+        assert!(first.split(' ').count() <= 4 * 700 + 10);
+    }
+
+    #[test]
+    fn test_ticks_are_round_numbers() {
+        let labels: Vec<String> = ticks(-0.013, 0.087).into_iter().map(|(_, l)| l).collect();
+        assert_eq!(labels, ["0.00", "0.02", "0.04", "0.06", "0.08"]);
+        let labels: Vec<String> = ticks(0.0, 16.5).into_iter().map(|(_, l)| l).collect();
+        assert_eq!(labels, ["0", "5", "10", "15"]);
+    }
+}
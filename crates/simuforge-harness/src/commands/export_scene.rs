@@ -0,0 +1,48 @@
+//! Export-scene command implementation
+
+use anyhow::Result;
+use clap::ValueEnum;
+use crate::gltf::GltfDocument;
+use crate::runner::load_experiment;
+use simuforge_physics::ExperimentRunner;
+
+/// Output format of an exported scene
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum SceneFormat {
+    /// Body transforms and collider shapes as JSON
+    Json,
+    /// Binary glTF (.glb) with tessellated collider meshes
+    Gltf,
+}
+
+/// Execute the export-scene command
+pub fn execute(experiment_path: &str, output_path: Option<&str>, format: SceneFormat, step: u64, pretty: bool) -> Result<()> {
+    let spec = load_experiment(experiment_path)?;
+    spec.validate().map_err(|e| anyhow::anyhow!("Validation: {}", e.join(", ")))?;
+
+    let mut runner = ExperimentRunner::new(spec).map_err(anyhow::Error::msg)?;
+    let steps = step.min(runner.target_steps());
+    runner.world_mut().run(steps);
+    let scene = runner.world().scene_description();
+
+    let output = match format {
+        SceneFormat::Json if pretty => serde_json::to_vec_pretty(&scene)?,
+        SceneFormat::Json => serde_json::to_vec(&scene)?,
+        SceneFormat::Gltf => {
+            let mut document = GltfDocument::default();
+            document.add_scene(&scene);
+            document.to_glb()
+        }
+    };
+
+    match output_path {
+        Some(path) => {
+            std::fs::write(path, &output)?;
+            eprintln!("Scene at step {} written to: {}", steps, path);
+        }
+        None if matches!(format, SceneFormat::Gltf) => anyhow::bail!("glTF output needs an output file (-o)"),
+        None => println!("{}", String::from_utf8(output)?),
+    }
+
+    Ok(())
+}
@@ -3,3 +3,5 @@
 pub mod run;
 pub mod baseline;
 pub mod suite;
+pub mod export_scene;
+pub mod plot;
@@ -0,0 +1,107 @@
+//! Plot command implementation
+
+use anyhow::{Context, Result};
+use crate::chart::{render_svg, Chart};
+use serde::Deserialize;
+use serde_json::Value;
+
+/// The parts of `simuforge run` output that get plotted. Frames stay untyped:
+/// non-finite values are written as `null` and are left out of the plots.
+#[derive(Deserialize)]
+struct RecordedRun {
+    experiment_name: String,
+    #[serde(default)]
+    frames: Vec<Value>,
+}
+
+/// Pulls the points of one line out of recorded frames
+type Extract<'a> = dyn Fn(&[Value]) -> Vec<(f32, f32)> + 'a;
+
+/// Time and value at a JSON pointer of each frame that has both
+fn points(frames: &[Value], pointer: &str) -> Vec<(f32, f32)> {
+    frames
+        .iter()
+        .filter_map(|f| Some((f["time"].as_f64()? as f32, f.pointer(pointer)?.as_f64()? as f32)))
+        .collect()
+}
+
+/// Position component of a named body in each frame it exists in
+fn body_points(frames: &[Value], name: &str, axis: usize) -> Vec<(f32, f32)> {
+    frames
+        .iter()
+        .filter_map(|f| {
+            let body = f["bodies"].as_array()?.iter().find(|b| b["name"] == name)?;
+            Some((f["time"].as_f64()? as f32, body["transform"]["position"][axis].as_f64()? as f32))
+        })
+        .collect()
+}
+
+fn load_run(path: &str) -> Result<RecordedRun> {
+    let content = std::fs::read_to_string(path).with_context(|| format!("Failed to read run output: {}", path))?;
+    serde_json::from_str(&content).with_context(|| format!("Failed to parse run output JSON: {}", path))
+}
+
+/// Execute the plot command
+pub fn execute(run_path: &str, output_path: Option<&str>, baseline_path: Option<&str>, bodies: &[String]) -> Result<()> {
+    let run = load_run(run_path)?;
+    if run.frames.is_empty() {
+        anyhow::bail!("{} has no frames; record them with `simuforge run --frames`", run_path);
+    }
+
+    let baseline = match baseline_path.map(load_run).transpose()? {
+        Some(baseline) if baseline.frames.is_empty() => {
+            eprintln!("Baseline {} has no frames, skipping the overlay", baseline.experiment_name);
+            None
+        }
+        baseline => baseline,
+    };
+    let baseline_frames = baseline.as_ref().map(|b| b.frames.as_slice());
+
+    // Each line is drawn for the run, then dashed in the same color for the baseline
+    let series = |chart: &mut Chart, label: &str, color: usize, extract: &Extract| {
+        chart.line(label, extract(&run.frames), color, false);
+        if let Some(frames) = baseline_frames {
+            chart.line(format!("{} (baseline)", label), extract(frames), color, true);
+        }
+    };
+
+    let mut energy = Chart::new(format!("{}: energy (J)", run.experiment_name));
+    series(&mut energy, "total", 0, &|f| points(f, "/energy/total"));
+    series(&mut energy, "kinetic", 1, &|f| points(f, "/energy/kinetic"));
+    series(&mut energy, "potential", 2, &|f| points(f, "/energy/potential"));
+
+    let mut momentum = Chart::new("Momentum magnitude");
+    series(&mut momentum, "linear", 0, &|f| points(f, "/momentum/linear_magnitude"));
+    series(&mut momentum, "angular", 1, &|f| points(f, "/momentum/angular_magnitude"));
+
+    let mut contacts = Chart::new("Contact count");
+    series(&mut contacts, "contacts", 0, &|f| points(f, "/contacts/contact_count"));
+
+    let mut penetration = Chart::new("Max penetration (m)");
+    series(&mut penetration, "max penetration", 0, &|f| points(f, "/contacts/max_penetration"));
+
+    let mut charts = vec![energy, momentum, contacts, penetration];
+
+    for name in bodies {
+        // Bodies spawned or removed mid-run only have points while they exist
+        if body_points(&run.frames, name, 0).is_empty() {
+            anyhow::bail!("Body '{}' not found in the recorded frames", name);
+        }
+        let mut chart = Chart::new(format!("{} position (m)", name));
+        for (axis, label) in ["x", "y", "z"].into_iter().enumerate() {
+            series(&mut chart, label, axis, &|f| body_points(f, name, axis));
+        }
+        charts.push(chart);
+    }
+
+    let svg = render_svg(&charts);
+    match output_path {
+        Some(path) => {
+            std::fs::write(path, svg)?;
+            eprintln!("Plots written to: {}", path);
+        }
+        None => print!("{}", svg),
+    }
+
+    Ok(())
+}
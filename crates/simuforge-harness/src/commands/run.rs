@@ -1,9 +1,13 @@
 //! Run command implementation
 
 use anyhow::Result;
-use crate::runner::{load_experiment, load_baseline, run_experiment, save_report};
+use crate::gltf::GltfDocument;
+use crate::runner::{load_experiment, load_baseline, run_experiment_observed};
 use serde::Serialize;
-use simuforge_core::{SimulationReport, MetricFrame};
+use simuforge_core::{SimulationReport, MetricFrame, SimEvent, EventKind};
+use simuforge_physics::SceneDescription;
+use std::fs::File;
+use std::io::{BufWriter, Write};
 
 /// Extended report including optional frame data
 #[derive(Serialize)]
@@ -12,6 +16,16 @@ struct ExtendedReport {
     report: SimulationReport,
     #[serde(skip_serializing_if = "Option::is_none")]
     frames: Option<Vec<MetricFrame>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    events: Option<Vec<SimEvent>>,
+}
+
+/// Optional files written alongside the report
+pub struct RunExports<'a> {
+    /// Debug geometry JSON lines path, written every N steps
+    pub debug_geometry: Option<(&'a str, u64)>,
+    /// glTF animation path, keeping every N-th frame
+    pub gltf: Option<(&'a str, usize)>,
 }
 
 /// Execute the run command
@@ -20,7 +34,9 @@ pub fn execute(
     output_path: Option<&str>,
     baseline_path: Option<&str>,
     include_frames: bool,
+    include_events: bool,
     pretty: bool,
+    exports: RunExports,
 ) -> Result<()> {
     // Load experiment
     let spec = load_experiment(experiment_path)?;
@@ -34,8 +50,43 @@ pub fn execute(
         anyhow::bail!("Invalid experiment specification");
     }
 
-    // Run experiment
-    let (mut report, frames) = run_experiment(&spec)?;
+    // Run experiment, writing debug geometry as JSON lines if requested
+    let mut debug_output = match exports.debug_geometry {
+        Some((path, every)) => Some((BufWriter::new(File::create(path)?), every.max(1), path)),
+        None => None,
+    };
+    // Collider geometry is captured whenever bodies are added or removed, so
+    // bodies spawned mid-run still get a mesh in the glTF export
+    let mut scene = SceneDescription { bodies: Vec::new() };
+    let mut layout_version = None;
+    let (mut report, frames) = run_experiment_observed(&spec, |world| {
+        if let Some((out, every, _)) = &mut debug_output {
+            if world.step_count().is_multiple_of(*every) {
+                serde_json::to_writer(&mut *out, &world.debug_geometry())?;
+                writeln!(out)?;
+            }
+        }
+        if exports.gltf.is_some() && layout_version != Some(world.body_layout_version()) {
+            layout_version = Some(world.body_layout_version());
+            for body in world.scene_description().bodies {
+                if !scene.bodies.iter().any(|b| b.id == body.id) {
+                    scene.bodies.push(body);
+                }
+            }
+        }
+        Ok(())
+    })?;
+    if let Some((mut out, _, path)) = debug_output {
+        out.flush()?;
+        eprintln!("Debug geometry written to: {}", path);
+    }
+    if let Some((path, every)) = exports.gltf {
+        let mut document = GltfDocument::default();
+        let nodes = document.add_scene(&scene);
+        document.add_run_animation(&report.experiment_name, &scene, &nodes, &frames, every);
+        std::fs::write(path, document.to_glb())?;
+        eprintln!("glTF animation written to: {}", path);
+    }
 
     // Compare to baseline if provided
     if let Some(baseline_path) = baseline_path {
@@ -44,9 +95,15 @@ pub fn execute(
     }
 
     // Build output
+    let events = if include_events {
+        Some(frames.iter().flat_map(|f| f.events.iter().cloned()).collect())
+    } else {
+        None
+    };
     let extended = ExtendedReport {
         report: report.clone(),
         frames: if include_frames { Some(frames) } else { None },
+        events,
     };
 
     let output = if pretty {
@@ -75,6 +132,21 @@ pub fn execute(
     eprintln!("  Energy drift: {:.2}%", report.metrics.energy_drift_percent);
     eprintln!("  Max penetration: {:.6}", report.metrics.max_penetration_ever);
     eprintln!("  Constraint violations: {}", report.metrics.total_constraint_violations);
+    eprintln!("  Collisions: {}", report.metrics.collision_count);
+    if let Some(performance) = &report.performance {
+        eprintln!("  Performance: {:.0} steps/s ({:.3}s wall)", performance.steps_per_second, performance.wall_time_seconds);
+    }
+
+    if !report.metrics.parameter_changes.is_empty() {
+        eprintln!();
+        eprintln!("Parameter changes:");
+        for event in &report.metrics.parameter_changes {
+            if let EventKind::ParameterChanged { parameter, body, previous, value } = &event.kind {
+                let target = body.as_ref().map(|b| format!("{}.", b)).unwrap_or_default();
+                eprintln!("  step {}: {}{} {} -> {}", event.step, target, parameter, previous, value);
+            }
+        }
+    }
 
     if !report.criteria_results.is_empty() {
         eprintln!();
@@ -0,0 +1,358 @@
+//! Binary glTF (GLB) export of collider geometry and recorded runs
+
+use serde_json::{json, Value};
+use simuforge_core::{MetricFrame, Transform};
+use simuforge_physics::{SceneBody, SceneDescription};
+use std::collections::HashMap;
+
+const FLOAT: u32 = 5126;
+const UNSIGNED_INT: u32 = 5125;
+const ARRAY_BUFFER: u32 = 34962;
+const ELEMENT_ARRAY_BUFFER: u32 = 34963;
+
+/// Keyframes of one node, one translation and rotation per key time
+struct AnimationTrack {
+    node: usize,
+    translations: Vec<[f32; 3]>,
+    rotations: Vec<[f32; 4]>,
+    /// Zero while the body does not exist, one while it does; empty if it exists throughout
+    scales: Vec<[f32; 3]>,
+}
+
+/// glTF document assembled in memory, with a single binary buffer
+#[derive(Default)]
+pub struct GltfDocument {
+    nodes: Vec<Value>,
+    meshes: Vec<Value>,
+    accessors: Vec<Value>,
+    buffer_views: Vec<Value>,
+    animations: Vec<Value>,
+    /// Root nodes of the scene
+    roots: Vec<usize>,
+    bin: Vec<u8>,
+}
+
+impl GltfDocument {
+    /// Add one node per body, each with a child node per collider.
+    /// Returns the body node indices in scene order.
+    pub fn add_scene(&mut self, scene: &SceneDescription) -> Vec<usize> {
+        scene.bodies.iter().map(|body| self.add_body(body)).collect()
+    }
+
+    fn add_body(&mut self, body: &SceneBody) -> usize {
+        let material = match body.body_type {
+            "dynamic" => 0,
+            "kinematic" => 2,
+            _ => 1,
+        };
+
+        let mut children = Vec::new();
+        for collider in &body.colliders {
+            let (vertices, indices) = collider.shape.triangles();
+            if indices.is_empty() {
+                continue;
+            }
+            let positions = self.add_positions(&vertices);
+            let indices = self.add_indices(&indices);
+            self.meshes.push(json!({
+                "primitives": [{
+                    "attributes": { "POSITION": positions },
+                    "indices": indices,
+                    "material": if collider.sensor { 3 } else { material },
+                }],
+            }));
+
+            let offset = &collider.offset;
+            self.nodes.push(json!({
+                "mesh": self.meshes.len() - 1,
+                "translation": offset.position,
+                "rotation": [offset.rotation.x, offset.rotation.y, offset.rotation.z, offset.rotation.w],
+            }));
+            children.push(self.nodes.len() - 1);
+        }
+
+        let transform = &body.transform;
+        let mut node = json!({
+            "name": body.name,
+            "translation": transform.position,
+            "rotation": [transform.rotation.x, transform.rotation.y, transform.rotation.z, transform.rotation.w],
+        });
+        if !children.is_empty() {
+            node["children"] = json!(children);
+        }
+        self.nodes.push(node);
+        self.roots.push(self.nodes.len() - 1);
+        self.nodes.len() - 1
+    }
+
+    /// Animate body nodes through recorded frames, keeping every `every`-th frame
+    /// and the last. Bodies absent from a frame hold their nearest recorded pose
+    /// and are scaled to zero until they appear again; bodies that exist
+    /// throughout and never move get no channels.
+    pub fn add_run_animation(&mut self, name: &str, scene: &SceneDescription, nodes: &[usize], frames: &[MetricFrame], every: usize) {
+        let every = every.max(1);
+        let last = frames.len().saturating_sub(1);
+        let kept: Vec<&MetricFrame> = frames
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| i % every == 0 || *i == last)
+            .map(|(_, frame)| frame)
+            .collect();
+        if kept.len() < 2 {
+            return;
+        }
+
+        let poses: Vec<HashMap<u64, &Transform>> = kept
+            .iter()
+            .map(|frame| frame.bodies.iter().map(|b| (b.id, &b.transform)).collect())
+            .collect();
+        let mut tracks = Vec::new();
+        for (body, &node) in scene.bodies.iter().zip(nodes) {
+            let Some(first) = poses.iter().find_map(|p| p.get(&body.id).copied()) else {
+                continue;
+            };
+            let mut current = first;
+            let keys: Vec<&Transform> = poses
+                .iter()
+                .map(|p| {
+                    current = p.get(&body.id).copied().unwrap_or(current);
+                    current
+                })
+                .collect();
+            let present: Vec<bool> = poses.iter().map(|p| p.contains_key(&body.id)).collect();
+            let always_present = present.iter().all(|&p| p);
+            if always_present && keys.iter().all(|k| *k == first) {
+                continue;
+            }
+            let translations = keys.iter().map(|k| [k.position.x, k.position.y, k.position.z]).collect();
+            let rotations = keys.iter().map(|k| [k.rotation.x, k.rotation.y, k.rotation.z, k.rotation.w]).collect();
+            let scales = if always_present {
+                Vec::new()
+            } else {
+                present.iter().map(|&p| if p { [1.0; 3] } else { [0.0; 3] }).collect()
+            };
+            tracks.push(AnimationTrack { node, translations, rotations, scales });
+        }
+
+        let start = kept[0].time;
+        let times: Vec<f32> = kept.iter().map(|frame| frame.time - start).collect();
+        self.add_animation(name, &times, &tracks);
+    }
+
+    /// Animate node translations and rotations, interpolating linearly between key times.
+    /// Scales switch at key times instead, so hidden bodies do not shrink or grow.
+    fn add_animation(&mut self, name: &str, times: &[f32], tracks: &[AnimationTrack]) {
+        if tracks.is_empty() {
+            return;
+        }
+        let input = self.add_floats(times, "SCALAR");
+        self.accessors[input]["min"] = json!([times.first()]);
+        self.accessors[input]["max"] = json!([times.last()]);
+
+        let mut samplers = Vec::new();
+        let mut channels = Vec::new();
+        for track in tracks {
+            let translation = self.add_floats(track.translations.as_flattened(), "VEC3");
+            let rotation = self.add_floats(track.rotations.as_flattened(), "VEC4");
+            let mut outputs = vec![(translation, "translation", "LINEAR"), (rotation, "rotation", "LINEAR")];
+            if !track.scales.is_empty() {
+                outputs.push((self.add_floats(track.scales.as_flattened(), "VEC3"), "scale", "STEP"));
+            }
+            for (output, path, interpolation) in outputs {
+                samplers.push(json!({ "input": input, "output": output, "interpolation": interpolation }));
+                channels.push(json!({ "sampler": samplers.len() - 1, "target": { "node": track.node, "path": path } }));
+            }
+        }
+        self.animations.push(json!({ "name": name, "samplers": samplers, "channels": channels }));
+    }
+
+    /// Serialize as a GLB file
+    pub fn to_glb(&self) -> Vec<u8> {
+        let mut document = json!({
+            "asset": { "version": "2.0", "generator": "simuforge" },
+            "scene": 0,
+            "scenes": [{ "nodes": self.roots }],
+            "nodes": self.nodes,
+            "meshes": self.meshes,
+            "materials": [
+                material("dynamic", [0.3, 0.6, 0.9, 1.0]),
+                material("fixed", [0.4, 0.4, 0.4, 1.0]),
+                material("kinematic", [0.9, 0.6, 0.2, 1.0]),
+                json!({
+                    "name": "sensor",
+                    "alphaMode": "BLEND",
+                    "doubleSided": true,
+                    "pbrMetallicRoughness": { "baseColorFactor": [0.2, 0.9, 0.4, 0.25], "metallicFactor": 0.0 },
+                }),
+            ],
+            "accessors": self.accessors,
+            "bufferViews": self.buffer_views,
+            "buffers": [{ "byteLength": self.bin.len() }],
+        });
+        if !self.animations.is_empty() {
+            document["animations"] = json!(self.animations);
+        }
+        // glTF forbids empty arrays and buffers, as in a scene without geometry
+        if self.bin.is_empty() {
+            for key in ["meshes", "accessors", "bufferViews", "buffers"] {
+                document.as_object_mut().expect("document is an object").remove(key);
+            }
+        }
+
+        let mut json = serde_json::to_vec(&document).expect("glTF JSON serializes");
+        pad(&mut json, b' ');
+        let mut bin = self.bin.clone();
+        pad(&mut bin, 0);
+
+        let mut chunks = vec![(json, b"JSON")];
+        if !bin.is_empty() {
+            chunks.push((bin, b"BIN\0"));
+        }
+        let length = 12 + chunks.iter().map(|(chunk, _)| 8 + chunk.len()).sum::<usize>();
+        let mut glb = Vec::with_capacity(length);
+        glb.extend_from_slice(b"glTF");
+        glb.extend_from_slice(&2u32.to_le_bytes());
+        glb.extend_from_slice(&(length as u32).to_le_bytes());
+        for (chunk, kind) in chunks {
+            glb.extend_from_slice(&(chunk.len() as u32).to_le_bytes());
+            glb.extend_from_slice(kind);
+            glb.extend_from_slice(&chunk);
+        }
+        glb
+    }
+
+    fn add_positions(&mut self, vertices: &[[f32; 3]]) -> usize {
+        let mut min = [f32::INFINITY; 3];
+        let mut max = [f32::NEG_INFINITY; 3];
+        for v in vertices {
+            for i in 0..3 {
+                min[i] = min[i].min(v[i]);
+                max[i] = max[i].max(v[i]);
+            }
+        }
+        let view = self.add_view(vertices.iter().flatten().flat_map(|f| f.to_le_bytes()), Some(ARRAY_BUFFER));
+        self.accessors.push(json!({
+            "bufferView": view,
+            "componentType": FLOAT,
+            "count": vertices.len(),
+            "type": "VEC3",
+            "min": min,
+            "max": max,
+        }));
+        self.accessors.len() - 1
+    }
+
+    fn add_indices(&mut self, indices: &[[u32; 3]]) -> usize {
+        let view = self.add_view(indices.iter().flatten().flat_map(|i| i.to_le_bytes()), Some(ELEMENT_ARRAY_BUFFER));
+        self.accessors.push(json!({
+            "bufferView": view,
+            "componentType": UNSIGNED_INT,
+            "count": indices.len() * 3,
+            "type": "SCALAR",
+        }));
+        self.accessors.len() - 1
+    }
+
+    fn add_floats(&mut self, values: &[f32], kind: &str) -> usize {
+        let components = match kind {
+            "VEC3" => 3,
+            "VEC4" => 4,
+            _ => 1,
+        };
+        let view = self.add_view(values.iter().flat_map(|f| f.to_le_bytes()), None);
+        self.accessors.push(json!({
+            "bufferView": view,
+            "componentType": FLOAT,
+            "count": values.len() / components,
+            "type": kind,
+        }));
+        self.accessors.len() - 1
+    }
+
+    fn add_view(&mut self, bytes: impl Iterator<Item = u8>, target: Option<u32>) -> usize {
+        let offset = self.bin.len();
+        self.bin.extend(bytes);
+        let mut view = json!({ "buffer": 0, "byteOffset": offset, "byteLength": self.bin.len() - offset });
+        if let Some(target) = target {
+            view["target"] = json!(target);
+        }
+        self.buffer_views.push(view);
+        self.buffer_views.len() - 1
+    }
+}
+
+fn material(name: &str, color: [f32; 4]) -> Value {
+    json!({
+        "name": name,
+        "pbrMetallicRoughness": { "baseColorFactor": color, "metallicFactor": 0.0, "roughnessFactor": 0.8 },
+    })
+}
+
+/// Pad to the 4-byte alignment GLB chunks require
+fn pad(bytes: &mut Vec<u8>, fill: u8) {
+    while !bytes.len().is_multiple_of(4) {
+        bytes.push(fill);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use simuforge_core::PhysicsConfig;
+    use simuforge_physics::{create_scenario, MetricWorld};
+
+    #[test]
+    fn test_glb_layout() {
+        let config = serde_yaml::from_str("type: builtin\nname: box_stack\nparams: { count: 2 }").unwrap();
+        let mut world = MetricWorld::new(&PhysicsConfig::default());
+        create_scenario(&config).setup(&mut world).unwrap();
+
+        let mut document = GltfDocument::default();
+        let nodes = document.add_scene(&world.scene_description());
+        assert_eq!(nodes.len(), world.body_count());
+        let glb = document.to_glb();
+
+        assert_eq!(&glb[0..4], b"glTF");
+        assert_eq!(u32::from_le_bytes(glb[8..12].try_into().unwrap()) as usize, glb.len());
+        let json_len = u32::from_le_bytes(glb[12..16].try_into().unwrap()) as usize;
+        let json: Value = serde_json::from_slice(&glb[20..20 + json_len]).unwrap();
+        assert_eq!(json["scenes"][0]["nodes"].as_array().unwrap().len(), nodes.len());
+        let bin_len = u32::from_le_bytes(glb[20 + json_len..24 + json_len].try_into().unwrap()) as usize;
+        assert!(bin_len >= json["buffers"][0]["byteLength"].as_u64().unwrap() as usize);
+    }
+
+    #[test]
+    fn test_run_animation_channels() {
+        let config = serde_yaml::from_str("type: builtin\nname: bouncing_ball").unwrap();
+        let mut world = MetricWorld::new(&PhysicsConfig::default());
+        create_scenario(&config).setup(&mut world).unwrap();
+        let scene = world.scene_description();
+        world.run(100);
+
+        let mut document = GltfDocument::default();
+        let nodes = document.add_scene(&scene);
+        document.add_run_animation("run", &scene, &nodes, world.frames(), 10);
+
+        // Only the ball moves; 101 frames decimated to every 10th plus the last
+        let animation = &document.animations[0];
+        assert_eq!(animation["channels"].as_array().unwrap().len(), 2);
+        let input = animation["samplers"][0]["input"].as_u64().unwrap() as usize;
+        assert_eq!(document.accessors[input]["count"], 11);
+        let duration = document.accessors[input]["max"][0].as_f64().unwrap();
+        assert!((duration - 100.0 / 60.0).abs() < 1e-3, "{}", duration);
+
+        // A body removed mid-run is hidden from then on, even though it never moved
+        let mut frames = world.frames().to_vec();
+        for frame in &mut frames[50..] {
+            frame.bodies.retain(|b| b.name != "ground");
+        }
+        let mut document = GltfDocument::default();
+        let nodes = document.add_scene(&scene);
+        document.add_run_animation("run", &scene, &nodes, &frames, 10);
+        let channels = document.animations[0]["channels"].as_array().unwrap();
+        let scale = channels.iter().find(|c| c["target"]["path"] == "scale").unwrap();
+        let sampler = &document.animations[0]["samplers"][scale["sampler"].as_u64().unwrap() as usize];
+        assert_eq!(sampler["interpolation"], "STEP");
+        assert_eq!(document.accessors[sampler["output"].as_u64().unwrap() as usize]["count"], 11);
+    }
+}
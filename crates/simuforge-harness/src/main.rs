@@ -5,8 +5,10 @@ use anyhow::Result;
 
 mod runner;
 mod commands;
+mod gltf;
+mod chart;
 
-use commands::{run, baseline, suite};
+use commands::{run, baseline, suite, export_scene, plot};
 
 #[derive(Parser)]
 #[command(name = "simuforge")]
@@ -35,9 +37,29 @@ enum Commands {
         #[arg(long)]
         frames: bool,
 
+        /// Include the collision/sensor/contact-force event log in output
+        #[arg(long)]
+        events: bool,
+
         /// Pretty-print JSON output
         #[arg(long)]
         pretty: bool,
+
+        /// Write contacts, AABBs, joints and centers of mass as JSON lines, one per step
+        #[arg(long, value_name = "FILE")]
+        debug_geometry: Option<String>,
+
+        /// Only write debug geometry every N steps
+        #[arg(long, value_name = "N", default_value_t = 1, requires = "debug_geometry")]
+        debug_every: u64,
+
+        /// Write colliders as meshes and the recorded body motion as a glTF animation (.glb)
+        #[arg(long, value_name = "FILE")]
+        export_gltf: Option<String>,
+
+        /// Only keep every N-th frame in the glTF animation
+        #[arg(long, value_name = "N", default_value_t = 1, requires = "export_gltf")]
+        gltf_every: usize,
     },
 
     /// Generate a baseline from an experiment
@@ -64,6 +86,46 @@ enum Commands {
         fail_fast: bool,
     },
 
+    /// Export body transforms and collider geometry for renderers
+    ExportScene {
+        /// Path to experiment YAML file
+        experiment: String,
+
+        /// Output file (default: stdout, JSON only)
+        #[arg(short, long)]
+        output: Option<String>,
+
+        /// Output format
+        #[arg(long, value_enum, default_value = "json")]
+        format: export_scene::SceneFormat,
+
+        /// Simulate this many steps before exporting
+        #[arg(long, default_value_t = 0)]
+        step: u64,
+
+        /// Pretty-print JSON output
+        #[arg(long)]
+        pretty: bool,
+    },
+
+    /// Plot energy, momentum, contacts and body positions of a run as SVG
+    Plot {
+        /// Run output JSON recorded with `simuforge run --frames`
+        run: String,
+
+        /// Output SVG file (default: stdout)
+        #[arg(short, long)]
+        output: Option<String>,
+
+        /// Run output with frames to overlay as dashed lines
+        #[arg(short, long)]
+        baseline: Option<String>,
+
+        /// Also plot the position of this body (repeatable)
+        #[arg(long = "body", value_name = "NAME")]
+        bodies: Vec<String>,
+    },
+
     /// List available built-in scenarios
     Scenarios,
 
@@ -83,8 +145,19 @@ fn main() -> Result<()> {
             output,
             baseline,
             frames,
+            events,
             pretty,
-        } => run::execute(&experiment, output.as_deref(), baseline.as_deref(), frames, pretty),
+            debug_geometry,
+            debug_every,
+            export_gltf,
+            gltf_every,
+        } => {
+            let exports = run::RunExports {
+                debug_geometry: debug_geometry.as_deref().map(|path| (path, debug_every)),
+                gltf: export_gltf.as_deref().map(|path| (path, gltf_every)),
+            };
+            run::execute(&experiment, output.as_deref(), baseline.as_deref(), frames, events, pretty, exports)
+        }
 
         Commands::Baseline { experiment, output } => baseline::execute(&experiment, &output),
 
@@ -94,12 +167,36 @@ fn main() -> Result<()> {
             fail_fast,
         } => suite::execute(&directory, &output, fail_fast),
 
+        Commands::ExportScene {
+            experiment,
+            output,
+            format,
+            step,
+            pretty,
+        } => export_scene::execute(&experiment, output.as_deref(), format, step, pretty),
+
+        Commands::Plot {
+            run,
+            output,
+            baseline,
+            bodies,
+        } => plot::execute(&run, output.as_deref(), baseline.as_deref(), &bodies),
+
         Commands::Scenarios => {
             println!("Available built-in scenarios:");
             println!("  box_stack      - Stack of boxes on ground plane");
             println!("  rolling_sphere - Sphere rolling on flat surface");
             println!("  bouncing_ball  - Ball dropped from height");
             println!("  friction_ramp  - Object sliding down inclined ramp");
+            println!("  newtons_cradle - Row of pendulum spheres transferring momentum");
+            println!("  box_pyramid    - Pyramid of boxes on ground plane");
+            println!("  brick_wall     - Staggered brick wall on ground plane");
+            println!("  domino_chain   - Chain of dominoes toppling in sequence");
+            println!("  simple_pendulum - Pendulum compared against its analytic period");
+            println!("  double_pendulum - Chaotic double pendulum, long-run energy drift");
+            println!("  granular_pile  - Seeded grains poured through a hopper or into a bin");
+            println!("  articulated_chain - Hinged chain, multibody vs impulse joint drift and energy");
+            println!("  vehicle        - Motor-driven four-wheeled vehicle over bumps or a heightfield");
             Ok(())
         }
 
@@ -1,23 +1,15 @@
 //! Experiment runner
 
 use anyhow::{Context, Result};
-use simuforge_core::{
-    ExperimentSpec, SimulationReport, MetricFrame,
-    spec::DurationConfig,
-};
-use simuforge_physics::{MetricWorld, create_scenario};
+use simuforge_core::{ExperimentSpec, SimulationReport, MetricFrame, PerformanceMetrics};
+use simuforge_physics::{ExperimentRunner, MetricWorld};
 use std::fs;
 use std::path::Path;
+use std::time::Instant;
 
 /// Load experiment specification from YAML file
 pub fn load_experiment(path: &str) -> Result<ExperimentSpec> {
-    let content = fs::read_to_string(path)
-        .with_context(|| format!("Failed to read experiment file: {}", path))?;
-
-    let spec: ExperimentSpec = serde_yaml::from_str(&content)
-        .with_context(|| format!("Failed to parse experiment YAML: {}", path))?;
-
-    Ok(spec)
+    simuforge_physics::load_experiment(Path::new(path)).map_err(anyhow::Error::msg)
 }
 
 /// Load baseline report from JSON file
@@ -47,43 +39,31 @@ pub fn save_report(report: &SimulationReport, path: &str, pretty: bool) -> Resul
 
 /// Run an experiment and return the report
 pub fn run_experiment(spec: &ExperimentSpec) -> Result<(SimulationReport, Vec<MetricFrame>)> {
-    // Create physics world
-    let mut world = MetricWorld::from_spec(spec);
-
-    // Set up scenario
-    let scenario = create_scenario(&spec.spec.scenario);
-    scenario.setup(&mut world);
-
-    // Determine step count
-    let steps = match &spec.spec.duration {
-        DurationConfig::Fixed { steps } => *steps,
-        DurationConfig::Time { seconds } => {
-            (*seconds / spec.spec.physics.timestep) as u64
-        }
-        DurationConfig::UntilStable { max_steps, .. } => *max_steps,
-    };
+    run_experiment_observed(spec, |_| Ok(()))
+}
 
-    // Run simulation
-    world.run(steps);
+/// Run an experiment, handing the world to `observe` before every step and once
+/// more after the last
+pub fn run_experiment_observed(
+    spec: &ExperimentSpec,
+    mut observe: impl FnMut(&MetricWorld) -> Result<()>,
+) -> Result<(SimulationReport, Vec<MetricFrame>)> {
+    let mut runner = ExperimentRunner::new(spec.clone()).map_err(anyhow::Error::msg)?;
 
-    // Build report
-    let frames = world.frames().to_vec();
-    let mut report = SimulationReport::new(spec.metadata.name.clone());
-    report.finalize(&frames, &spec.spec.criteria);
+    let started = Instant::now();
+    runner.run_observed(&mut observe)?;
+    let elapsed = started.elapsed().as_secs_f64();
 
-    Ok((report, frames))
-}
+    let mut report = runner.finish();
+    report.performance = Some(PerformanceMetrics::new(runner.target_steps(), elapsed));
 
-/// Result of running an experiment
-pub struct ExperimentResult {
-    pub report: SimulationReport,
-    pub frames: Vec<MetricFrame>,
-    pub experiment_path: String,
+    Ok((report, runner.frames().to_vec()))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use simuforge_core::ScenarioConfig;
 
     #[test]
     fn test_run_minimal_experiment() {
@@ -110,4 +90,16 @@ spec:
         assert!(frames.len() > 0);
         assert_eq!(report.experiment_name, "test");
     }
+
+    #[test]
+    fn test_composite_includes_resolved() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/../../experiments/benchmarks/composite-stacks.yaml");
+        let spec = load_experiment(path).unwrap();
+        let ScenarioConfig::Composite { instances, .. } = &spec.spec.scenario else {
+            panic!("expected composite scenario");
+        };
+        assert!(instances.iter().all(|i| i.scenario.is_some()));
+        assert!(matches!(instances[0].scenario.as_deref(), Some(ScenarioConfig::Builtin { name, .. }) if name == "box_stack"));
+        assert!(spec.validate().is_ok());
+    }
 }
@@ -0,0 +1,256 @@
+//! Scripted actions executed on a timeline during a run
+
+use crate::MetricWorld;
+use crate::body_builder::rigid_body_type;
+use rapier3d::prelude::*;
+use simuforge_core::{
+    SimEvent, EventKind,
+    spec::{Action, ActionConfig},
+};
+
+/// Force or torque held on a body until `until` (simulation time). It is added to
+/// the body's user forces once and taken back off when it expires, so forces
+/// applied by other code are left alone.
+#[derive(Debug, Clone)]
+pub(crate) struct ActiveForce {
+    pub handle: RigidBodyHandle,
+    pub force: Vector<f32>,
+    pub torque: Vector<f32>,
+    pub until: f32,
+}
+
+impl MetricWorld {
+    /// Schedule actions to be executed as the simulation reaches their step or time
+    pub fn set_actions(&mut self, actions: Vec<ActionConfig>) {
+        self.actions = actions;
+    }
+
+    /// Number of scheduled actions that have not fired yet
+    pub fn pending_action_count(&self) -> usize {
+        self.actions.len()
+    }
+
+    /// Execute every scheduled action that is due at the current step
+    pub(crate) fn run_due_actions(&mut self) {
+        if self.actions.is_empty() {
+            return;
+        }
+
+        let (due, remaining): (Vec<_>, Vec<_>) = std::mem::take(&mut self.actions)
+            .into_iter()
+            .partition(|a| a.is_due(self.current_step, self.current_time, self.timestep));
+        self.actions = remaining;
+
+        for config in due {
+            let kind = match self.apply_action(&config.action) {
+                Ok(()) => EventKind::ActionApplied {
+                    action: config.action.kind_name().to_string(),
+                    body: config.action.target().map(str::to_string),
+                },
+                Err(reason) => EventKind::ActionFailed {
+                    action: config.action.kind_name().to_string(),
+                    reason,
+                },
+            };
+            self.pending_events.push(SimEvent::new(self.current_step, self.current_time, kind));
+        }
+    }
+
+    /// Apply a single action immediately
+    pub fn apply_action(&mut self, action: &Action) -> Result<(), String> {
+        match action {
+            Action::ApplyForce { body, force, duration } => {
+                let handle = self.require_body(body)?;
+                self.hold_force(handle, force.to_nalgebra(), Vector::zeros(), *duration);
+            }
+            Action::ApplyTorque { body, torque, duration } => {
+                let handle = self.require_body(body)?;
+                self.hold_force(handle, Vector::zeros(), torque.to_nalgebra(), *duration);
+            }
+            Action::ApplyImpulse { body, impulse, point } => {
+                let handle = self.require_body(body)?;
+                let rb = &mut self.rigid_body_set[handle];
+                match point {
+                    Some(p) => rb.apply_impulse_at_point(impulse.to_nalgebra(), point![p.x, p.y, p.z], true),
+                    None => rb.apply_impulse(impulse.to_nalgebra(), true),
+                }
+            }
+            Action::ApplyTorqueImpulse { body, torque_impulse } => {
+                let handle = self.require_body(body)?;
+                self.rigid_body_set[handle].apply_torque_impulse(torque_impulse.to_nalgebra(), true);
+            }
+            Action::SetVelocity { body, linear, angular } => {
+                let handle = self.require_body(body)?;
+                let rb = &mut self.rigid_body_set[handle];
+                if let Some(v) = linear {
+                    rb.set_linvel(v.to_nalgebra(), true);
+                }
+                if let Some(w) = angular {
+                    rb.set_angvel(w.to_nalgebra(), true);
+                }
+            }
+            Action::Teleport { body, position, rotation } => {
+                let handle = self.require_body(body)?;
+                let rb = &mut self.rigid_body_set[handle];
+                let rotation = match rotation {
+                    Some([x, y, z, w]) => {
+                        nalgebra::UnitQuaternion::from_quaternion(nalgebra::Quaternion::new(*w, *x, *y, *z))
+                    }
+                    None => *rb.rotation(),
+                };
+                let translation = nalgebra::Translation3::from(position.to_nalgebra());
+                rb.set_position(Isometry::from_parts(translation, rotation), true);
+            }
+            Action::SetBodyType { body, body_type } => {
+                let handle = self.require_body(body)?;
+                self.rigid_body_set[handle].set_body_type(rigid_body_type(body_type), true);
+            }
+            Action::Spawn { body } => {
+                if self.handle_by_name(&body.name).is_some() {
+                    return Err(format!("Body already exists: {}", body.name));
+                }
+                self.add_body_from_config(body);
+            }
+            Action::Remove { body } => {
+                let handle = self.require_body(body)?;
+                self.remove_body(handle);
+            }
+            Action::SetGravity { gravity } => {
+                self.set_gravity(*gravity)?;
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn require_body(&self, name: &str) -> Result<RigidBodyHandle, String> {
+        self.handle_by_name(name)
+            .ok_or_else(|| format!("Body not found: {}", name))
+    }
+
+    fn hold_force(
+        &mut self,
+        handle: RigidBodyHandle,
+        force: Vector<f32>,
+        torque: Vector<f32>,
+        duration: Option<f32>,
+    ) {
+        // A force without duration acts on exactly the next step
+        let hold = duration.unwrap_or(self.timestep * 0.5);
+        if let Some(rb) = self.rigid_body_set.get_mut(handle) {
+            rb.add_force(force, true);
+            rb.add_torque(torque, true);
+        }
+        self.active_forces.push(ActiveForce {
+            handle,
+            force,
+            torque,
+            until: self.current_time + hold,
+        });
+    }
+
+    /// Take expired held forces back off their bodies and keep the rest awake
+    pub(crate) fn apply_active_forces(&mut self) {
+        if self.active_forces.is_empty() {
+            return;
+        }
+
+        let now = self.current_time;
+        let (active, expired): (Vec<_>, Vec<_>) = std::mem::take(&mut self.active_forces)
+            .into_iter()
+            .partition(|f| f.until > now);
+        self.active_forces = active;
+
+        for force in &expired {
+            if let Some(rb) = self.rigid_body_set.get_mut(force.handle) {
+                rb.add_force(-force.force, false);
+                rb.add_torque(-force.torque, false);
+            }
+        }
+        for force in &self.active_forces {
+            if let Some(rb) = self.rigid_body_set.get_mut(force.handle) {
+                rb.wake_up(true);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BodyBuilder;
+    use simuforge_core::{PhysicsConfig, Vec3};
+
+    fn world_with_box() -> MetricWorld {
+        let mut world = MetricWorld::new(&PhysicsConfig {
+            gravity: Vec3::ZERO,
+            ..Default::default()
+        });
+        let (body, collider, name) = BodyBuilder::new("box")
+            .box_shape(0.5, 0.5, 0.5)
+            .build();
+        let handle = world.add_body(body, name);
+        world.add_collider(collider, handle);
+        world
+    }
+
+    fn action(yaml: &str) -> ActionConfig {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    #[test]
+    fn test_impulse_at_time() {
+        let mut world = world_with_box();
+        world.set_actions(vec![action("{time: 0.5, type: apply_impulse, body: box, impulse: [2, 0, 0]}")]);
+
+        world.run(20);
+        assert!(world.get_body_by_name("box").unwrap().linvel().x.abs() < 1e-6);
+
+        world.run(20);
+        // Unit box with density 1 has unit mass
+        assert!((world.get_body_by_name("box").unwrap().linvel().x - 2.0).abs() < 1e-3);
+        assert_eq!(world.pending_action_count(), 0);
+        assert!(world.events().any(|e| matches!(&e.kind, EventKind::ActionApplied { action, .. } if action == "apply_impulse")));
+    }
+
+    #[test]
+    fn test_force_held_for_duration() {
+        let mut world = world_with_box();
+        world.set_actions(vec![action("{step: 0, type: apply_force, body: box, force: [0, 0, 6], duration: 0.5}")]);
+
+        world.run(60);
+        // v = F * t / m with the force released after half a second
+        let vz = world.get_body_by_name("box").unwrap().linvel().z;
+        assert!((vz - 3.0).abs() < 0.15);
+    }
+
+    #[test]
+    fn test_expiring_force_keeps_other_user_forces() {
+        let mut world = world_with_box();
+        let handle = world.handle_by_name("box").unwrap();
+        world.rigid_body_set[handle].add_force(vector![1.0, 0.0, 0.0], true);
+        world.set_actions(vec![action("{step: 0, type: apply_force, body: box, force: [0, 0, 6], duration: 0.25}")]);
+
+        world.run(30);
+        let rb = world.get_body_by_name("box").unwrap();
+        assert!((rb.user_force() - vector![1.0, 0.0, 0.0]).norm() < 1e-5);
+        // Half a second of the outside force, a quarter second of the held one
+        assert!((rb.linvel().x - 0.5).abs() < 0.05);
+        assert!((rb.linvel().z - 1.5).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_spawn_and_remove() {
+        let mut world = world_with_box();
+        world.set_actions(vec![
+            action("{step: 2, type: spawn, body: {name: extra, shape: {type: sphere, radius: 0.5}, position: [3, 0, 0]}}"),
+            action("{step: 4, type: remove, body: box}"),
+            action("{step: 5, type: remove, body: missing}"),
+        ]);
+
+        world.run(10);
+        assert!(world.get_body_by_name("extra").is_some());
+        assert!(world.get_body_by_name("box").is_none());
+        assert_eq!(world.body_count(), 1);
+        assert!(world.events().any(|e| matches!(e.kind, EventKind::ActionFailed { .. })));
+    }
+}
@@ -4,6 +4,15 @@ use rapier3d::prelude::*;
 use nalgebra::UnitQuaternion;
 use simuforge_core::{Vec3, spec::{BodyConfig, BodyType as SpecBodyType, ShapeConfig, MaterialConfig}};
 
+/// Map a spec body type onto the Rapier body type
+pub(crate) fn rigid_body_type(body_type: &SpecBodyType) -> RigidBodyType {
+    match body_type {
+        SpecBodyType::Dynamic => RigidBodyType::Dynamic,
+        SpecBodyType::Static => RigidBodyType::Fixed,
+        SpecBodyType::Kinematic => RigidBodyType::KinematicPositionBased,
+    }
+}
+
 /// Builder for creating physics bodies with colliders
 pub struct BodyBuilder {
     name: String,
@@ -16,6 +25,7 @@ pub struct BodyBuilder {
     friction: f32,
     restitution: f32,
     density: f32,
+    sensor: bool,
 }
 
 impl BodyBuilder {
@@ -31,18 +41,16 @@ impl BodyBuilder {
             friction: 0.5,
             restitution: 0.3,
             density: 1.0,
+            sensor: false,
         }
     }
 
     pub fn from_config(config: &BodyConfig) -> Self {
         let mut builder = Self::new(&config.name)
             .position(config.position)
-            .body_type(match config.body_type {
-                SpecBodyType::Dynamic => RigidBodyType::Dynamic,
-                SpecBodyType::Static => RigidBodyType::Fixed,
-                SpecBodyType::Kinematic => RigidBodyType::KinematicPositionBased,
-            })
-            .material(&config.material);
+            .body_type(rigid_body_type(&config.body_type))
+            .material(&config.material)
+            .sensor(config.sensor);
 
         if let Some(rotation) = config.rotation {
             builder = builder.rotation_quat(rotation[0], rotation[1], rotation[2], rotation[3]);
@@ -161,6 +169,11 @@ impl BodyBuilder {
         self
     }
 
+    pub fn sensor(mut self, sensor: bool) -> Self {
+        self.sensor = sensor;
+        self
+    }
+
     pub fn material(mut self, material: &MaterialConfig) -> Self {
         self.friction = material.friction;
         self.restitution = material.restitution;
@@ -183,6 +196,7 @@ impl BodyBuilder {
             .friction(self.friction)
             .restitution(self.restitution)
             .density(self.density)
+            .sensor(self.sensor)
             .build();
 
         (body, collider, self.name)
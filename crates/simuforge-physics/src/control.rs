@@ -0,0 +1,310 @@
+//! Joint actuators and feedback controllers
+
+use crate::MetricWorld;
+use crate::joints::JointRef;
+use simuforge_core::{metrics::ActuatorState, spec::ActuatorConfig};
+
+/// Motor target for a single joint
+#[derive(Debug, Clone, PartialEq)]
+pub struct MotorCommand {
+    pub joint: String,
+    pub target_position: f32,
+    pub target_velocity: f32,
+    pub stiffness: f32,
+    pub damping: f32,
+    pub max_force: Option<f32>,
+    /// Joint position the command is trying to reach, used for tracking error
+    pub setpoint: Option<f32>,
+}
+
+impl MotorCommand {
+    /// Spring-damper motor pulling the joint towards `position`
+    pub fn position(joint: impl Into<String>, position: f32, stiffness: f32, damping: f32) -> Self {
+        Self {
+            joint: joint.into(),
+            target_position: position,
+            target_velocity: 0.0,
+            stiffness,
+            damping,
+            max_force: None,
+            setpoint: Some(position),
+        }
+    }
+
+    /// Velocity motor; `damping` is the gain on velocity error
+    pub fn velocity(joint: impl Into<String>, velocity: f32, damping: f32) -> Self {
+        Self {
+            joint: joint.into(),
+            target_position: 0.0,
+            target_velocity: velocity,
+            stiffness: 0.0,
+            damping,
+            max_force: None,
+            setpoint: None,
+        }
+    }
+
+    pub fn from_config(config: &ActuatorConfig) -> Self {
+        Self {
+            joint: config.joint.clone(),
+            target_position: config.target_position.unwrap_or(0.0),
+            target_velocity: config.target_velocity.unwrap_or(0.0),
+            stiffness: config.stiffness,
+            damping: config.damping,
+            max_force: config.max_force,
+            setpoint: config.target_position,
+        }
+    }
+}
+
+/// Controller called once per step, before the physics update
+pub trait Controller {
+    fn name(&self) -> &str;
+    fn update(&mut self, world: &MetricWorld, dt: f32) -> Vec<MotorCommand>;
+
+    /// Copy of the controller and its internal state, for world snapshots.
+    /// Controllers that return `None` make the world impossible to snapshot.
+    fn snapshot(&self) -> Option<Box<dyn Controller>> {
+        None
+    }
+}
+
+/// PID position controller commanding a joint velocity motor
+#[derive(Debug, Clone)]
+pub struct PidController {
+    pub joint: String,
+    pub setpoint: f32,
+    pub kp: f32,
+    pub ki: f32,
+    pub kd: f32,
+    /// Gain of the underlying velocity motor
+    pub motor_gain: f32,
+    pub max_force: Option<f32>,
+    integral: f32,
+    previous_error: Option<f32>,
+}
+
+impl PidController {
+    pub fn new(joint: impl Into<String>, setpoint: f32, kp: f32, ki: f32, kd: f32) -> Self {
+        Self {
+            joint: joint.into(),
+            setpoint,
+            kp,
+            ki,
+            kd,
+            motor_gain: 10.0,
+            max_force: None,
+            integral: 0.0,
+            previous_error: None,
+        }
+    }
+
+    pub fn with_motor_gain(mut self, gain: f32) -> Self {
+        self.motor_gain = gain;
+        self
+    }
+
+    pub fn with_max_force(mut self, max_force: f32) -> Self {
+        self.max_force = Some(max_force);
+        self
+    }
+}
+
+impl Controller for PidController {
+    fn name(&self) -> &str {
+        &self.joint
+    }
+
+    fn update(&mut self, world: &MetricWorld, dt: f32) -> Vec<MotorCommand> {
+        let Some(state) = world.joint_state(&self.joint) else {
+            return Vec::new();
+        };
+
+        let error = self.setpoint - state.position;
+        self.integral += error * dt;
+        let derivative = self.previous_error.map(|prev| (error - prev) / dt).unwrap_or(0.0);
+        self.previous_error = Some(error);
+
+        let velocity = self.kp * error + self.ki * self.integral + self.kd * derivative;
+        let mut command = MotorCommand::velocity(self.joint.clone(), velocity, self.motor_gain);
+        command.max_force = self.max_force;
+        command.setpoint = Some(self.setpoint);
+        vec![command]
+    }
+
+    fn snapshot(&self) -> Option<Box<dyn Controller>> {
+        Some(Box::new(self.clone()))
+    }
+}
+
+/// Target tracked for an actuated joint
+#[derive(Debug, Clone)]
+pub(crate) struct ActuatorTarget {
+    pub joint: String,
+    pub setpoint: Option<f32>,
+    pub target_velocity: Option<f32>,
+}
+
+impl MetricWorld {
+    /// Configure joint motors from spec actuators
+    pub fn set_actuators(&mut self, actuators: &[ActuatorConfig]) -> Result<(), String> {
+        for actuator in actuators {
+            self.apply_motor_command(&MotorCommand::from_config(actuator))?;
+        }
+        Ok(())
+    }
+
+    /// Register a controller to be updated before every step
+    pub fn add_controller(&mut self, controller: Box<dyn Controller>) {
+        self.controllers.push(controller);
+    }
+
+    /// Drive a named joint's motor and track its target
+    pub fn apply_motor_command(&mut self, command: &MotorCommand) -> Result<(), String> {
+        let named = self.joints.iter()
+            .find(|j| j.name == command.joint)
+            .ok_or_else(|| format!("Joint not found: {}", command.joint))?;
+        let axis = named.axis
+            .ok_or_else(|| format!("Joint {} has no single motor axis", command.joint))?;
+        let handle = named.handle;
+        let (_, body1, body2) = self.joint_parts(handle)
+            .ok_or_else(|| format!("Joint not found: {}", command.joint))?;
+        let data = match handle {
+            JointRef::Impulse(handle) => self.impulse_joint_set.get_mut(handle).map(|j| &mut j.data),
+            JointRef::Multibody(handle) => self.multibody_joint_set
+                .get_mut(handle)
+                .and_then(|(multibody, id)| multibody.link_mut(id))
+                .map(|link| &mut link.joint.data),
+        }
+        .ok_or_else(|| format!("Joint not found: {}", command.joint))?;
+
+        data.set_motor(axis, command.target_position, command.target_velocity, command.stiffness, command.damping);
+        if let Some(max_force) = command.max_force {
+            data.set_motor_max_force(axis, max_force);
+        }
+
+        for body in [body1, body2] {
+            if let Some(rb) = self.rigid_body_set.get_mut(body) {
+                rb.wake_up(true);
+            }
+        }
+
+        let target = ActuatorTarget {
+            joint: command.joint.clone(),
+            setpoint: command.setpoint,
+            target_velocity: if command.setpoint.is_none() { Some(command.target_velocity) } else { None },
+        };
+        match self.actuator_targets.iter_mut().find(|t| t.joint == command.joint) {
+            Some(existing) => *existing = target,
+            None => self.actuator_targets.push(target),
+        }
+        Ok(())
+    }
+
+    /// Let every controller issue motor commands for the upcoming step
+    pub(crate) fn run_controllers(&mut self) {
+        if self.controllers.is_empty() {
+            return;
+        }
+
+        let mut controllers = std::mem::take(&mut self.controllers);
+        for controller in controllers.iter_mut() {
+            for command in controller.update(self, self.timestep) {
+                // Commands for unknown joints are ignored; controllers may outlive removed joints
+                let _ = self.apply_motor_command(&command);
+            }
+        }
+        self.controllers = controllers;
+    }
+
+    /// Tracking state of every actuated joint
+    pub(crate) fn collect_actuator_states(&self) -> Vec<ActuatorState> {
+        self.actuator_targets
+            .iter()
+            .filter_map(|target| {
+                let state = self.joint_state(&target.joint)?;
+                let tracking_error = match (target.setpoint, target.target_velocity) {
+                    (Some(setpoint), _) => (state.position - setpoint).abs(),
+                    (None, Some(velocity)) => (state.velocity - velocity).abs(),
+                    (None, None) => 0.0,
+                };
+                Some(ActuatorState {
+                    joint: target.joint.clone(),
+                    position: state.position,
+                    velocity: state.velocity,
+                    setpoint: target.setpoint,
+                    target_velocity: target.target_velocity,
+                    tracking_error,
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BodyBuilder;
+    use rapier3d::prelude::*;
+    use simuforge_core::{PhysicsConfig, Vec3};
+
+    fn hinge_world() -> MetricWorld {
+        let mut world = MetricWorld::new(&PhysicsConfig {
+            gravity: Vec3::ZERO,
+            ..Default::default()
+        });
+        let (base, _, _) = BodyBuilder::new("base").fixed().build();
+        let base = world.add_body(base, "base".to_string());
+        let (link, collider, name) = BodyBuilder::new("link")
+            .position_xyz(1.0, 0.0, 0.0)
+            .box_shape(1.0, 0.1, 0.1)
+            .build();
+        let link = world.add_body(link, name);
+        world.add_collider(collider, link);
+        world.add_joint(
+            "shoulder",
+            base,
+            link,
+            RevoluteJointBuilder::new(Vector::z_axis()).local_anchor2(point![-1.0, 0.0, 0.0]),
+        );
+        world
+    }
+
+    #[test]
+    fn test_position_actuator_tracks_target() {
+        let mut world = hinge_world();
+        world.set_actuators(&[ActuatorConfig {
+            joint: "shoulder".to_string(),
+            target_position: Some(0.5),
+            target_velocity: None,
+            stiffness: 100.0,
+            damping: 20.0,
+            max_force: None,
+        }]).unwrap();
+
+        world.run(240);
+        let last = world.frames().last().unwrap();
+        assert_eq!(last.actuators.len(), 1);
+        assert!(last.actuators[0].tracking_error < 0.01);
+    }
+
+    #[test]
+    fn test_pid_controller_reaches_setpoint() {
+        let mut world = hinge_world();
+        world.add_controller(Box::new(PidController::new("shoulder", -0.8, 4.0, 0.0, 0.1).with_motor_gain(50.0)));
+
+        world.run(300);
+        let state = world.joint_state("shoulder").unwrap();
+        assert!((state.position + 0.8).abs() < 0.01);
+
+        let first = &world.frames()[1].actuators[0];
+        assert!(first.tracking_error > 0.7);
+    }
+
+    #[test]
+    fn test_unknown_joint_rejected() {
+        let mut world = hinge_world();
+        let result = world.apply_motor_command(&MotorCommand::velocity("elbow", 1.0, 1.0));
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,167 @@
+//! Debug geometry for visual inspection of contacts, bounds and joints
+
+use crate::MetricWorld;
+use rapier3d::prelude::*;
+use serde::Serialize;
+use simuforge_core::Vec3;
+
+/// Everything a debug overlay draws for one step
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DebugGeometry {
+    pub step: u64,
+    pub time: f32,
+    pub bodies: Vec<DebugBody>,
+    pub aabbs: Vec<DebugAabb>,
+    pub contacts: Vec<DebugContact>,
+    pub joints: Vec<DebugJoint>,
+}
+
+/// Center of mass marker of a body
+#[derive(Debug, Clone, Serialize)]
+pub struct DebugBody {
+    pub id: u64,
+    pub name: String,
+    pub center_of_mass: Vec3,
+    pub sleeping: bool,
+}
+
+/// World-space bounding box of one collider
+#[derive(Debug, Clone, Serialize)]
+pub struct DebugAabb {
+    pub body: String,
+    pub min: Vec3,
+    pub max: Vec3,
+    pub sensor: bool,
+}
+
+/// Contact point the solver acts on in the coming step
+#[derive(Debug, Clone, Serialize)]
+pub struct DebugContact {
+    pub body_a: String,
+    pub body_b: String,
+    pub point: Vec3,
+    /// Unit normal pointing from `body_a` towards `body_b`
+    pub normal: Vec3,
+    /// Penetration depth; negative while the shapes are still apart
+    pub depth: f32,
+}
+
+/// Anchors of a joint on both bodies, plus its free axis for single-axis joints
+#[derive(Debug, Clone, Serialize)]
+pub struct DebugJoint {
+    pub name: String,
+    pub anchor_a: Vec3,
+    pub anchor_b: Vec3,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub axis: Option<Vec3>,
+}
+
+impl MetricWorld {
+    /// Collect debug geometry for the current state
+    pub fn debug_geometry(&self) -> DebugGeometry {
+        DebugGeometry {
+            step: self.current_step,
+            time: self.current_time,
+            bodies: self.debug_bodies(),
+            aabbs: self.debug_aabbs(),
+            contacts: self.debug_contacts(),
+            joints: self.debug_joints(),
+        }
+    }
+
+    fn debug_bodies(&self) -> Vec<DebugBody> {
+        self.body_order
+            .iter()
+            .filter_map(|handle| {
+                let body = self.rigid_body_set.get(*handle)?;
+                Some(DebugBody {
+                    id: *self.body_ids.get(handle)?,
+                    name: self.body_names.get(handle)?.clone(),
+                    center_of_mass: Vec3::from_nalgebra(&body.center_of_mass().coords),
+                    sleeping: body.is_sleeping(),
+                })
+            })
+            .collect()
+    }
+
+    fn debug_aabbs(&self) -> Vec<DebugAabb> {
+        self.collider_set
+            .iter()
+            .filter_map(|(_, collider)| {
+                let aabb = collider.compute_aabb();
+                Some(DebugAabb {
+                    body: self.body_names.get(&collider.parent()?)?.clone(),
+                    min: Vec3::from_nalgebra(&aabb.mins.coords),
+                    max: Vec3::from_nalgebra(&aabb.maxs.coords),
+                    sensor: collider.is_sensor(),
+                })
+            })
+            .collect()
+    }
+
+    fn debug_contacts(&self) -> Vec<DebugContact> {
+        let mut contacts = Vec::new();
+        for pair in self.narrow_phase.contact_pairs() {
+            let (Some(body_a), Some(body_b)) = (self.collider_body_name(pair.collider1), self.collider_body_name(pair.collider2)) else {
+                continue;
+            };
+            for manifold in &pair.manifolds {
+                let normal = Vec3::from_nalgebra(&manifold.data.normal);
+                for contact in &manifold.data.solver_contacts {
+                    contacts.push(DebugContact {
+                        body_a: body_a.clone(),
+                        body_b: body_b.clone(),
+                        point: Vec3::from_nalgebra(&contact.point.coords),
+                        normal,
+                        depth: -contact.dist,
+                    });
+                }
+            }
+        }
+        contacts
+    }
+
+    fn debug_joints(&self) -> Vec<DebugJoint> {
+        self.joints
+            .iter()
+            .filter_map(|named| {
+                let (data, body1, body2) = self.joint_parts(named.handle)?;
+                let frame1 = self.rigid_body_set.get(body1)?.position() * data.local_frame1;
+                let frame2 = self.rigid_body_set.get(body2)?.position() * data.local_frame2;
+                // Free axes are always the joint frame's X axis
+                let axis = named.axis.map(|_| Vec3::from_nalgebra(&(frame1.rotation * Vector::x())));
+                Some(DebugJoint {
+                    name: named.name.clone(),
+                    anchor_a: Vec3::from_nalgebra(&frame1.translation.vector),
+                    anchor_b: Vec3::from_nalgebra(&frame2.translation.vector),
+                    axis,
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::scenarios::BoxStackScenario;
+    use crate::{MetricWorld, Scenario};
+    use simuforge_core::PhysicsConfig;
+
+    #[test]
+    fn test_stack_debug_geometry() {
+        let mut world = MetricWorld::new(&PhysicsConfig::default());
+        BoxStackScenario::new(3).setup(&mut world).unwrap();
+        world.run(60);
+
+        let geometry = world.debug_geometry();
+        assert_eq!(geometry.step, 60);
+        assert_eq!(geometry.bodies.len(), world.body_count());
+        assert_eq!(geometry.aabbs.len(), world.body_count());
+        assert!(!geometry.contacts.is_empty());
+        // Resting boxes push against each other along the vertical
+        assert!(geometry.contacts.iter().all(|c| c.normal.y.abs() > 0.9 && c.depth < 0.05));
+        for aabb in &geometry.aabbs {
+            assert!(aabb.min.x <= aabb.max.x && aabb.min.y <= aabb.max.y);
+        }
+    }
+}
@@ -0,0 +1,221 @@
+//! Interactive picking, dragging and poking of bodies
+
+use crate::MetricWorld;
+use rapier3d::prelude::*;
+use simuforge_core::{EventKind, SimEvent, Vec3};
+
+/// Default drag spring stiffness, as acceleration per metre of stretch (1/s²)
+pub const DEFAULT_DRAG_STIFFNESS: f32 = 100.0;
+
+/// Body hit by a ray
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct RayHit {
+    pub body_id: u64,
+    pub body: String,
+    /// Hit point in world space
+    pub point: Vec3,
+    pub normal: Vec3,
+    pub distance: f32,
+}
+
+/// Spring pulling a point on a body towards a target, like a mouse joint
+#[derive(Debug, Clone)]
+pub(crate) struct DragSpring {
+    pub handle: RigidBodyHandle,
+    /// Grabbed point in the body's local frame
+    pub local_point: Point<f32>,
+    pub target: Point<f32>,
+    pub stiffness: f32,
+    pub damping: f32,
+}
+
+impl MetricWorld {
+    /// Cast a ray and return the closest body it hits within `max_distance`.
+    /// Sensors are ignored.
+    pub fn raycast(&mut self, origin: Vec3, direction: Vec3, max_distance: f32) -> Option<RayHit> {
+        let direction = direction.to_nalgebra().try_normalize(f32::EPSILON)?;
+        // Bodies may have been added or moved since the last step
+        self.query_pipeline.update(&self.collider_set);
+
+        let ray = Ray::new(point![origin.x, origin.y, origin.z], direction);
+        let filter = QueryFilter::default().exclude_sensors();
+        let (collider, intersection) = self.query_pipeline.cast_ray_and_get_normal(
+            &self.rigid_body_set,
+            &self.collider_set,
+            &ray,
+            max_distance,
+            true,
+            filter,
+        )?;
+
+        let handle = self.collider_set.get(collider)?.parent()?;
+        Some(RayHit {
+            body_id: *self.body_ids.get(&handle)?,
+            body: self.body_names.get(&handle)?.clone(),
+            point: Vec3::from_nalgebra(&ray.point_at(intersection.time_of_impact).coords),
+            normal: Vec3::from_nalgebra(&intersection.normal),
+            distance: intersection.time_of_impact,
+        })
+    }
+
+    /// Grab a dynamic body at a world-space point and start pulling it towards
+    /// the drag target, initially the grabbed point itself. `damping` defaults
+    /// to critical damping for the given stiffness.
+    pub fn start_drag(&mut self, body_id: u64, point: Vec3, stiffness: f32, damping: Option<f32>) -> Result<(), String> {
+        let handle = self.dynamic_body(body_id)?;
+        let body = &self.rigid_body_set[handle];
+        if !stiffness.is_finite() || stiffness <= 0.0 {
+            return Err(format!("Drag stiffness must be positive, got {}", stiffness));
+        }
+        if let Some(damping) = damping.filter(|d| !d.is_finite() || *d < 0.0) {
+            return Err(format!("Drag damping must not be negative, got {}", damping));
+        }
+
+        let target = point![point.x, point.y, point.z];
+        self.drag = Some(DragSpring {
+            handle,
+            local_point: body.position().inverse_transform_point(&target),
+            target,
+            stiffness,
+            damping: damping.unwrap_or(2.0 * stiffness.sqrt()),
+        });
+        self.record_interaction("drag", handle);
+        Ok(())
+    }
+
+    /// Move the drag target; does nothing when no body is being dragged
+    pub fn move_drag(&mut self, target: Vec3) {
+        if let Some(drag) = &mut self.drag {
+            drag.target = point![target.x, target.y, target.z];
+        }
+    }
+
+    /// Release the dragged body
+    pub fn end_drag(&mut self) {
+        self.drag = None;
+    }
+
+    /// Name of the body currently being dragged
+    pub fn dragged_body(&self) -> Option<&str> {
+        let drag = self.drag.as_ref()?;
+        self.body_names.get(&drag.handle).map(String::as_str)
+    }
+
+    /// Apply an impulse at a world-space point of a dynamic body
+    pub fn apply_impulse_at(&mut self, body_id: u64, impulse: Vec3, point: Vec3) -> Result<(), String> {
+        let handle = self.dynamic_body(body_id)?;
+        self.rigid_body_set[handle].apply_impulse_at_point(impulse.to_nalgebra(), point![point.x, point.y, point.z], true);
+        self.record_interaction("impulse", handle);
+        Ok(())
+    }
+
+    /// Only dynamic bodies respond to drags and impulses
+    fn dynamic_body(&self, body_id: u64) -> Result<RigidBodyHandle, String> {
+        let handle = self.handle_by_id(body_id).ok_or_else(|| format!("Body not found: {}", body_id))?;
+        if !self.rigid_body_set[handle].is_dynamic() {
+            return Err(format!("Body is not dynamic: {}", self.body_names[&handle]));
+        }
+        Ok(handle)
+    }
+
+    /// Push the drag spring's impulse for the coming step
+    pub(crate) fn apply_drag(&mut self) {
+        let Some(drag) = &self.drag else {
+            return;
+        };
+        let Some(body) = self.rigid_body_set.get_mut(drag.handle) else {
+            self.drag = None;
+            return;
+        };
+
+        // Applied as an impulse so it does not linger in the body's user forces
+        let grabbed = body.position() * drag.local_point;
+        let velocity = body.velocity_at_point(&grabbed);
+        let acceleration = (drag.target - grabbed) * drag.stiffness - velocity * drag.damping;
+        let impulse = acceleration * body.mass() * self.timestep;
+        body.apply_impulse_at_point(impulse, grabbed, true);
+    }
+
+    /// Interactions show up in the event log like scripted actions
+    fn record_interaction(&mut self, action: &str, handle: RigidBodyHandle) {
+        let kind = EventKind::ActionApplied {
+            action: action.to_string(),
+            body: self.body_names.get(&handle).cloned(),
+        };
+        self.pending_events.push(SimEvent::new(self.current_step, self.current_time, kind));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BodyBuilder;
+    use simuforge_core::PhysicsConfig;
+
+    fn world_with_box() -> (MetricWorld, u64) {
+        let mut world = MetricWorld::new(&PhysicsConfig {
+            gravity: Vec3::ZERO,
+            ..Default::default()
+        });
+        let (body, collider, name) = BodyBuilder::new("box")
+            .position_xyz(0.0, 1.0, 0.0)
+            .box_shape(0.5, 0.5, 0.5)
+            .build();
+        let handle = world.add_body(body, name);
+        world.add_collider(collider, handle);
+        let id = world.body_ids[&handle];
+        (world, id)
+    }
+
+    #[test]
+    fn test_raycast_hits_box() {
+        let (mut world, id) = world_with_box();
+        let hit = world.raycast(Vec3::new(0.0, 1.0, -5.0), Vec3::new(0.0, 0.0, 2.0), 100.0).unwrap();
+
+        assert_eq!(hit.body_id, id);
+        assert_eq!(hit.body, "box");
+        assert!((hit.distance - 4.5).abs() < 1e-4);
+        assert!((hit.normal.z + 1.0).abs() < 1e-4);
+        assert!(world.raycast(Vec3::new(0.0, 5.0, -5.0), Vec3::new(0.0, 0.0, 1.0), 100.0).is_none());
+    }
+
+    #[test]
+    fn test_drag_pulls_body_to_target() {
+        let (mut world, id) = world_with_box();
+        let grab = Vec3::new(0.0, 1.0, 0.0);
+        assert!(world.start_drag(id, grab, f32::NAN, None).is_err());
+        assert!(world.start_drag(id, grab, 0.0, None).is_err());
+        assert!(world.start_drag(id, grab, DEFAULT_DRAG_STIFFNESS, Some(-1.0)).is_err());
+        assert!(world.dragged_body().is_none());
+
+        world.start_drag(id, Vec3::new(0.0, 1.0, 0.0), DEFAULT_DRAG_STIFFNESS, None).unwrap();
+        world.move_drag(Vec3::new(2.0, 1.0, 0.0));
+        world.run(180);
+
+        let x = world.get_body_by_name("box").unwrap().translation().x;
+        assert!((x - 2.0).abs() < 0.05, "{}", x);
+        assert!(world.events().any(|e| matches!(&e.kind, EventKind::ActionApplied { action, .. } if action == "drag")));
+
+        world.end_drag();
+        assert!(world.dragged_body().is_none());
+    }
+
+    #[test]
+    fn test_impulse_needs_dynamic_body() {
+        let (mut world, id) = world_with_box();
+        let (body, collider, name) = BodyBuilder::new("wall").position_xyz(3.0, 1.0, 0.0).box_shape(0.5, 0.5, 0.5).fixed().build();
+        let handle = world.add_body(body, name);
+        world.add_collider(collider, handle);
+        let wall = world.body_ids[&handle];
+
+        let error = world.apply_impulse_at(wall, Vec3::new(1.0, 0.0, 0.0), Vec3::new(3.0, 1.0, 0.0)).unwrap_err();
+        assert_eq!(error, "Body is not dynamic: wall");
+        assert!(world.start_drag(wall, Vec3::new(3.0, 1.0, 0.0), DEFAULT_DRAG_STIFFNESS, None).is_err());
+        world.apply_impulse_at(id, Vec3::new(1.0, 0.0, 0.0), Vec3::new(0.0, 1.0, 0.0)).unwrap();
+
+        // Only the impulse that reached a body is reported
+        world.step();
+        let applied = world.frames().iter().flat_map(|f| &f.events).filter(|e| matches!(e.kind, EventKind::ActionApplied { .. })).count();
+        assert_eq!(applied, 1);
+    }
+}
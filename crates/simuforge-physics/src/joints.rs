@@ -0,0 +1,248 @@
+//! Named joints between bodies
+
+use crate::MetricWorld;
+use rapier3d::prelude::*;
+use simuforge_core::spec::{JointConfig, JointKind};
+
+/// Joint registered under a name, with the axis its coordinate is measured along
+#[derive(Debug, Clone)]
+pub(crate) struct NamedJoint {
+    pub name: String,
+    pub handle: JointRef,
+    pub axis: Option<JointAxis>,
+}
+
+/// Handle into whichever joint set holds a named joint
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum JointRef {
+    /// Maximal-coordinate joint solved as a constraint between two bodies
+    Impulse(ImpulseJointHandle),
+    /// Reduced-coordinate link of a multibody articulation
+    Multibody(MultibodyJointHandle),
+}
+
+/// Coordinate of a single-axis joint
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct JointState {
+    /// Angle (rad) for revolute joints, offset for prismatic joints
+    pub position: f32,
+    pub velocity: f32,
+}
+
+impl MetricWorld {
+    /// Connect two bodies with a joint registered under `name`
+    pub fn add_joint(
+        &mut self,
+        name: impl Into<String>,
+        body_a: RigidBodyHandle,
+        body_b: RigidBodyHandle,
+        joint: impl Into<GenericJoint>,
+    ) -> ImpulseJointHandle {
+        let joint = joint.into();
+        let axis = free_axis(&joint);
+        let handle = self.impulse_joint_set.insert(body_a, body_b, joint, true);
+        self.joints.push(NamedJoint { name: name.into(), handle: JointRef::Impulse(handle), axis });
+        handle
+    }
+
+    /// Connect two bodies with a reduced-coordinate multibody joint registered under `name`.
+    ///
+    /// `body_b` becomes a link of the articulation rooted at `body_a`. Returns
+    /// `None` when the joint would close a kinematic loop, which multibodies cannot represent.
+    pub fn add_multibody_joint(
+        &mut self,
+        name: impl Into<String>,
+        body_a: RigidBodyHandle,
+        body_b: RigidBodyHandle,
+        joint: impl Into<GenericJoint>,
+    ) -> Option<MultibodyJointHandle> {
+        let joint = joint.into();
+        let axis = free_axis(&joint);
+        let handle = self.multibody_joint_set.insert(body_a, body_b, joint, true)?;
+        self.joints.push(NamedJoint { name: name.into(), handle: JointRef::Multibody(handle), axis });
+        Some(handle)
+    }
+
+    /// Add a joint described by a spec configuration
+    pub fn add_joint_from_config(&mut self, config: &JointConfig) -> Result<(), String> {
+        let body_a = self.handle_by_name(&config.body_a)
+            .ok_or_else(|| format!("Joint {}: unknown body {}", config.name, config.body_a))?;
+        let body_b = self.handle_by_name(&config.body_b)
+            .ok_or_else(|| format!("Joint {}: unknown body {}", config.name, config.body_b))?;
+
+        let anchor_a = point![config.anchor_a.x, config.anchor_a.y, config.anchor_a.z];
+        let anchor_b = point![config.anchor_b.x, config.anchor_b.y, config.anchor_b.z];
+
+        let joint: GenericJoint = match &config.kind {
+            JointKind::Fixed => FixedJointBuilder::new()
+                .local_anchor1(anchor_a)
+                .local_anchor2(anchor_b)
+                .into(),
+            JointKind::Spherical => SphericalJointBuilder::new()
+                .local_anchor1(anchor_a)
+                .local_anchor2(anchor_b)
+                .into(),
+            JointKind::Revolute { axis, limits } => {
+                let axis = unit_axis(axis.to_nalgebra()).map_err(|e| format!("Joint {}: {}", config.name, e))?;
+                let mut builder = RevoluteJointBuilder::new(axis)
+                    .local_anchor1(anchor_a)
+                    .local_anchor2(anchor_b);
+                if let Some(limits) = limits {
+                    builder = builder.limits(*limits);
+                }
+                builder.into()
+            }
+            JointKind::Prismatic { axis, limits } => {
+                let axis = unit_axis(axis.to_nalgebra()).map_err(|e| format!("Joint {}: {}", config.name, e))?;
+                let mut builder = PrismaticJointBuilder::new(axis)
+                    .local_anchor1(anchor_a)
+                    .local_anchor2(anchor_b);
+                if let Some(limits) = limits {
+                    builder = builder.limits(*limits);
+                }
+                builder.into()
+            }
+        };
+
+        if config.multibody {
+            self.add_multibody_joint(config.name.clone(), body_a, body_b, joint)
+                .map(|_| ())
+                .ok_or_else(|| format!("Joint {}: would close a loop in a multibody", config.name))
+        } else {
+            self.add_joint(config.name.clone(), body_a, body_b, joint);
+            Ok(())
+        }
+    }
+
+    /// Get impulse joint handle by name
+    pub fn joint_handle(&self, name: &str) -> Option<ImpulseJointHandle> {
+        match self.joints.iter().find(|j| j.name == name)?.handle {
+            JointRef::Impulse(handle) => Some(handle),
+            JointRef::Multibody(_) => None,
+        }
+    }
+
+    /// Get multibody joint handle by name
+    pub fn multibody_joint_handle(&self, name: &str) -> Option<MultibodyJointHandle> {
+        match self.joints.iter().find(|j| j.name == name)?.handle {
+            JointRef::Multibody(handle) => Some(handle),
+            JointRef::Impulse(_) => None,
+        }
+    }
+
+    /// Names of all registered joints
+    pub fn joint_names(&self) -> impl Iterator<Item = &str> {
+        self.joints.iter().map(|j| j.name.as_str())
+    }
+
+    /// Current coordinate of a revolute or prismatic joint
+    pub fn joint_state(&self, name: &str) -> Option<JointState> {
+        let named = self.joints.iter().find(|j| j.name == name)?;
+        let axis = named.axis?;
+        let (data, body1, body2) = self.joint_parts(named.handle)?;
+        let rb1 = self.rigid_body_set.get(body1)?;
+        let rb2 = self.rigid_body_set.get(body2)?;
+        Some(joint_coordinate(data, rb1, rb2, axis))
+    }
+
+    /// Joint data and the parent and child bodies it connects
+    pub(crate) fn joint_parts(&self, handle: JointRef) -> Option<(&GenericJoint, RigidBodyHandle, RigidBodyHandle)> {
+        match handle {
+            JointRef::Impulse(handle) => {
+                let joint = self.impulse_joint_set.get(handle)?;
+                Some((&joint.data, joint.body1, joint.body2))
+            }
+            JointRef::Multibody(handle) => {
+                let (multibody, id) = self.multibody_joint_set.get(handle)?;
+                let link = multibody.link(id)?;
+                let parent = multibody.link(link.parent_id()?)?;
+                Some((&link.joint.data, parent.rigid_body_handle(), link.rigid_body_handle()))
+            }
+        }
+    }
+
+    pub(crate) fn joint_exists(&self, handle: JointRef) -> bool {
+        match handle {
+            JointRef::Impulse(handle) => self.impulse_joint_set.contains(handle),
+            JointRef::Multibody(handle) => self.multibody_joint_set.get(handle).is_some(),
+        }
+    }
+}
+
+/// The single free axis of revolute and prismatic joints
+fn free_axis(joint: &GenericJoint) -> Option<JointAxis> {
+    if joint.locked_axes == JointAxesMask::LOCKED_REVOLUTE_AXES {
+        Some(JointAxis::AngX)
+    } else if joint.locked_axes == JointAxesMask::LOCKED_PRISMATIC_AXES {
+        Some(JointAxis::LinX)
+    } else {
+        None
+    }
+}
+
+fn unit_axis(axis: Vector<f32>) -> Result<UnitVector<f32>, String> {
+    UnitVector::try_new(axis, 1e-6).ok_or_else(|| "Joint axis must be non-zero".to_string())
+}
+
+/// Measure a joint coordinate from the relative pose of its two joint frames.
+///
+/// Rapier aligns the local X axis of both frames with the joint axis, so the
+/// coordinate is the rotation about (or translation along) that X axis.
+pub(crate) fn joint_coordinate(
+    joint: &GenericJoint,
+    rb1: &RigidBody,
+    rb2: &RigidBody,
+    axis: JointAxis,
+) -> JointState {
+    let frame1 = rb1.position() * joint.local_frame1;
+    let frame2 = rb2.position() * joint.local_frame2;
+    let relative = frame1.inverse() * frame2;
+    let world_axis = frame1.rotation * Vector::x();
+
+    match axis {
+        JointAxis::LinX => JointState {
+            position: relative.translation.vector.x,
+            velocity: (rb2.linvel() - rb1.linvel()).dot(&world_axis),
+        },
+        _ => {
+            let q = relative.rotation;
+            JointState {
+                position: 2.0 * q.i.atan2(q.w),
+                velocity: (rb2.angvel() - rb1.angvel()).dot(&world_axis),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BodyBuilder;
+    use simuforge_core::PhysicsConfig;
+
+    #[test]
+    fn test_revolute_joint_angle() {
+        let mut world = MetricWorld::new(&PhysicsConfig::default());
+        let (pivot, _, _) = BodyBuilder::new("pivot").fixed().build();
+        let pivot = world.add_body(pivot, "pivot".to_string());
+        let (bob, collider, name) = BodyBuilder::new("bob")
+            .position_xyz(1.0, 0.0, 0.0)
+            .sphere(0.1)
+            .build();
+        let bob = world.add_body(bob, name);
+        world.add_collider(collider, bob);
+
+        let joint = RevoluteJointBuilder::new(Vector::z_axis()).local_anchor2(point![-1.0, 0.0, 0.0]);
+        world.add_joint("hinge", pivot, bob, joint);
+
+        assert!(world.joint_state("hinge").unwrap().position.abs() < 1e-6);
+
+        // Released horizontally, the bob swings down: negative rotation about +Z
+        world.run(30);
+        let state = world.joint_state("hinge").unwrap();
+        let bob_pos = world.get_body_by_name("bob").unwrap().translation();
+        let expected = bob_pos.y.atan2(bob_pos.x);
+        assert!((state.position - expected).abs() < 0.02);
+        assert!(state.velocity < 0.0);
+    }
+}
@@ -6,8 +6,26 @@
 pub mod world;
 pub mod metrics;
 pub mod scenarios;
+mod actions;
 mod body_builder;
+mod control;
+mod debug;
+mod interaction;
+mod joints;
+mod motion;
+mod parameters;
+mod runner;
+mod scene;
+mod snapshot;
 
-pub use world::MetricWorld;
+pub use world::{MetricWorld, TRANSFORM_STRIDE, interpolate_transforms};
 pub use body_builder::BodyBuilder;
 pub use scenarios::{Scenario, create_scenario};
+pub use motion::motion_pose;
+pub use control::{Controller, MotorCommand, PidController};
+pub use joints::JointState;
+pub use debug::{DebugAabb, DebugBody, DebugContact, DebugGeometry, DebugJoint};
+pub use interaction::{RayHit, DEFAULT_DRAG_STIFFNESS};
+pub use snapshot::{SnapshotHistory, WorldInput, WorldSnapshot, DEFAULT_SNAPSHOT_INTERVAL, DEFAULT_SNAPSHOT_MEMORY_CAP};
+pub use scene::{SceneBody, SceneCollider, SceneDescription, ShapeDescription};
+pub use runner::{ExperimentRunner, load_experiment};
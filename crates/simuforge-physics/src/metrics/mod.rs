@@ -1,6 +1,6 @@
 //! Metric collection implementations
 
-use simuforge_core::{MetricFrame, AggregateMetrics};
+use simuforge_core::MetricFrame;
 
 /// Analyze metric frames for stability detection
 pub fn detect_stability(frames: &[MetricFrame], velocity_threshold: f32) -> Option<u64> {
@@ -63,7 +63,10 @@ mod tests {
                 energy: EnergyMetrics::new(100.0, 0.0),
                 momentum: Default::default(),
                 contacts: Default::default(),
+                gravity: Default::default(),
                 bodies: vec![],
+                events: vec![],
+                actuators: vec![],
             },
             MetricFrame {
                 step: 1,
@@ -71,7 +74,10 @@ mod tests {
                 energy: EnergyMetrics::new(95.0, 0.0),
                 momentum: Default::default(),
                 contacts: Default::default(),
+                gravity: Default::default(),
                 bodies: vec![],
+                events: vec![],
+                actuators: vec![],
             },
         ];
 
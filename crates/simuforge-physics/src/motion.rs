@@ -0,0 +1,168 @@
+//! Prescribed trajectories for kinematic bodies
+
+use crate::MetricWorld;
+use nalgebra::{Quaternion, Translation3, UnitQuaternion};
+use rapier3d::prelude::*;
+use simuforge_core::spec::{Keyframe, MotionConfig};
+use std::f32::consts::TAU;
+
+/// Motion attached to a kinematic body, relative to its pose at registration
+#[derive(Debug, Clone)]
+pub(crate) struct KinematicMotion {
+    pub handle: RigidBodyHandle,
+    pub motion: MotionConfig,
+    pub base: Isometry<f32>,
+}
+
+impl MetricWorld {
+    /// Drive a kinematic body along a trajectory, starting from its current pose
+    pub fn set_body_motion(&mut self, handle: RigidBodyHandle, motion: MotionConfig) {
+        let Some(body) = self.rigid_body_set.get(handle) else {
+            return;
+        };
+        let base = *body.position();
+        self.motions.retain(|m| m.handle != handle);
+        self.motions.push(KinematicMotion { handle, motion, base });
+    }
+
+    /// Set next kinematic positions for the step ending at the current time plus one timestep
+    pub(crate) fn update_kinematic_motions(&mut self) {
+        let t = self.current_time + self.timestep;
+        for motion in &self.motions {
+            if let Some(body) = self.rigid_body_set.get_mut(motion.handle) {
+                body.set_next_kinematic_position(motion_pose(&motion.motion, &motion.base, t));
+            }
+        }
+    }
+}
+
+/// Evaluate a motion at time `t` given the body's initial pose
+pub fn motion_pose(motion: &MotionConfig, base: &Isometry<f32>, t: f32) -> Isometry<f32> {
+    match motion {
+        MotionConfig::Keyframes { keyframes, repeat } => keyframe_pose(keyframes, *repeat, base, t),
+        MotionConfig::Oscillate { amplitude, frequency, phase } => {
+            let offset = amplitude.to_nalgebra() * (TAU * frequency * t + phase).sin();
+            Isometry::from_parts(
+                Translation3::from(base.translation.vector + offset),
+                base.rotation,
+            )
+        }
+        MotionConfig::Rotate { angular_velocity } => {
+            let spin = UnitQuaternion::new(angular_velocity.to_nalgebra() * t);
+            Isometry::from_parts(base.translation, spin * base.rotation)
+        }
+        MotionConfig::Path { waypoints, speed, repeat } => {
+            let points: Vec<Vector<f32>> = waypoints.iter().map(|w| w.to_nalgebra()).collect();
+            let position = path_position(&points, *repeat, speed * t)
+                .unwrap_or(base.translation.vector);
+            Isometry::from_parts(Translation3::from(position), base.rotation)
+        }
+    }
+}
+
+fn keyframe_pose(keyframes: &[Keyframe], repeat: bool, base: &Isometry<f32>, t: f32) -> Isometry<f32> {
+    let (Some(first), Some(last)) = (keyframes.first(), keyframes.last()) else {
+        return *base;
+    };
+
+    let t = if repeat && last.time > first.time {
+        first.time + (t - first.time).rem_euclid(last.time - first.time)
+    } else {
+        t.clamp(first.time, last.time)
+    };
+
+    let next = keyframes.iter().position(|k| k.time >= t).unwrap_or(keyframes.len() - 1);
+    let k1 = &keyframes[next];
+    let k0 = &keyframes[next.saturating_sub(1)];
+    let alpha = if k1.time > k0.time { (t - k0.time) / (k1.time - k0.time) } else { 1.0 };
+
+    let position = k0.position.to_nalgebra().lerp(&k1.position.to_nalgebra(), alpha);
+    let r0 = keyframe_rotation(k0, base);
+    let r1 = keyframe_rotation(k1, base);
+    let rotation = r0.try_slerp(&r1, alpha, 1e-6).unwrap_or(r1);
+
+    Isometry::from_parts(Translation3::from(position), rotation)
+}
+
+fn keyframe_rotation(keyframe: &Keyframe, base: &Isometry<f32>) -> UnitQuaternion<f32> {
+    match keyframe.rotation {
+        Some([x, y, z, w]) => UnitQuaternion::from_quaternion(Quaternion::new(w, x, y, z)),
+        None => base.rotation,
+    }
+}
+
+/// Point at arc length `distance` along a polyline; looping paths close back to the start
+fn path_position(points: &[Vector<f32>], repeat: bool, distance: f32) -> Option<Vector<f32>> {
+    let mut segments: Vec<(Vector<f32>, Vector<f32>)> = points.windows(2).map(|w| (w[0], w[1])).collect();
+    if repeat {
+        segments.push((*points.last()?, *points.first()?));
+    }
+
+    let total: f32 = segments.iter().map(|(a, b)| (b - a).norm()).sum();
+    if total <= 0.0 {
+        return points.first().copied();
+    }
+
+    let mut remaining = if repeat { distance.rem_euclid(total) } else { distance.clamp(0.0, total) };
+    for (a, b) in &segments {
+        let length = (b - a).norm();
+        if remaining <= length {
+            return Some(a.lerp(b, if length > 0.0 { remaining / length } else { 0.0 }));
+        }
+        remaining -= length;
+    }
+    segments.last().map(|(_, b)| *b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BodyBuilder;
+    use simuforge_core::{PhysicsConfig, Vec3};
+
+    fn motion(yaml: &str) -> MotionConfig {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    #[test]
+    fn test_keyframe_interpolation() {
+        let m = motion("{type: keyframes, keyframes: [{time: 0, position: [0, 0, 0]}, {time: 2, position: [4, 0, 0]}]}");
+        let pose = motion_pose(&m, &Isometry::identity(), 1.0);
+        assert!((pose.translation.x - 2.0).abs() < 1e-5);
+
+        let held = motion_pose(&m, &Isometry::identity(), 5.0);
+        assert!((held.translation.x - 4.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_looping_path() {
+        let m = motion("{type: path, waypoints: [[0, 0, 0], [2, 0, 0], [2, 2, 0]], speed: 1.0, loop: true}");
+        let pose = motion_pose(&m, &Isometry::identity(), 3.0);
+        assert!((pose.translation.vector - vector![2.0, 1.0, 0.0]).norm() < 1e-5);
+
+        // Closing segment has length sqrt(8), so the loop repeats after 4 + sqrt(8) seconds
+        let period = 4.0 + 8.0f32.sqrt();
+        let wrapped = motion_pose(&m, &Isometry::identity(), period + 1.0);
+        assert!((wrapped.translation.vector - vector![1.0, 0.0, 0.0]).norm() < 1e-4);
+    }
+
+    #[test]
+    fn test_oscillating_platform_moves() {
+        let mut world = MetricWorld::new(&PhysicsConfig::default());
+        let (body, collider, name) = BodyBuilder::new("table")
+            .position_xyz(0.0, 1.0, 0.0)
+            .box_shape(2.0, 0.1, 2.0)
+            .kinematic()
+            .build();
+        let handle = world.add_body(body, name);
+        world.add_collider(collider, handle);
+        world.set_body_motion(handle, motion("{type: oscillate, amplitude: [0.5, 0, 0], frequency: 1.0}"));
+
+        // Quarter period at 60 Hz
+        world.run(15);
+        let table = world.get_body_by_name("table").unwrap();
+        assert!((table.translation().x - 0.5).abs() < 1e-3);
+        assert!((table.translation().y - 1.0).abs() < 1e-6);
+        assert_eq!(Vec3::from_nalgebra(table.translation()).z, 0.0);
+    }
+}
@@ -0,0 +1,207 @@
+//! Live changes to physics and body parameters during a run
+//!
+//! Every change takes effect on the next step and is recorded as a
+//! `ParameterChanged` event, so reports show why metrics shifted mid-run.
+
+use crate::MetricWorld;
+use rapier3d::prelude::*;
+use simuforge_core::{EventKind, SimEvent, Vec3};
+use std::num::NonZeroUsize;
+
+impl MetricWorld {
+    /// Change gravity
+    pub fn set_gravity(&mut self, gravity: Vec3) -> Result<(), String> {
+        if ![gravity.x, gravity.y, gravity.z].iter().all(|g| g.is_finite()) {
+            return Err(format!("Gravity must be finite, got {}", format_vec3(gravity)));
+        }
+        let previous = format_vec3(Vec3::from_nalgebra(&self.gravity));
+        self.gravity = gravity.to_nalgebra();
+        self.record_change("gravity", None, previous, format_vec3(gravity));
+        Ok(())
+    }
+
+    /// Change the fixed timestep
+    pub fn set_timestep(&mut self, timestep: f32) -> Result<(), String> {
+        if !timestep.is_finite() || timestep <= 0.0 {
+            return Err(format!("Timestep must be positive, got {}", timestep));
+        }
+        let previous = self.timestep.to_string();
+        self.timestep = timestep;
+        self.integration_parameters.dt = timestep;
+        self.record_change("timestep", None, previous, timestep.to_string());
+        Ok(())
+    }
+
+    /// Change the number of solver iterations per step
+    pub fn set_solver_iterations(&mut self, iterations: u32) -> Result<(), String> {
+        let iterations = NonZeroUsize::new(iterations as usize)
+            .ok_or_else(|| "Solver iterations must be at least 1".to_string())?;
+        let previous = self.integration_parameters.num_solver_iterations.to_string();
+        self.integration_parameters.num_solver_iterations = iterations;
+        self.record_change("solver_iterations", None, previous, iterations.to_string());
+        Ok(())
+    }
+
+    /// Change friction, restitution and/or density of every collider on a body.
+    /// A density change also changes the body's mass.
+    pub fn set_body_material(
+        &mut self,
+        name: &str,
+        friction: Option<f32>,
+        restitution: Option<f32>,
+        density: Option<f32>,
+    ) -> Result<(), String> {
+        let handle = self.require_body(name)?;
+        if friction.is_some_and(|f| !f.is_finite() || f < 0.0) {
+            return Err("Friction must not be negative".to_string());
+        }
+        if restitution.is_some_and(|r| !(0.0..=1.0).contains(&r)) {
+            return Err("Restitution must be between 0 and 1".to_string());
+        }
+        if density.is_some_and(|d| !d.is_finite() || d <= 0.0) {
+            return Err("Density must be positive".to_string());
+        }
+
+        let colliders = self.rigid_body_set[handle].colliders().to_vec();
+        let Some(first) = colliders.first().and_then(|c| self.collider_set.get(*c)) else {
+            return Err(format!("Body has no collider: {}", name));
+        };
+        let previous = (first.friction(), first.restitution(), first.density());
+
+        for collider_handle in &colliders {
+            let Some(collider) = self.collider_set.get_mut(*collider_handle) else {
+                continue;
+            };
+            if let Some(friction) = friction {
+                collider.set_friction(friction);
+            }
+            if let Some(restitution) = restitution {
+                collider.set_restitution(restitution);
+            }
+            if let Some(density) = density {
+                collider.set_density(density);
+            }
+        }
+
+        if let Some(friction) = friction {
+            self.record_change("friction", Some(name), previous.0.to_string(), friction.to_string());
+        }
+        if let Some(restitution) = restitution {
+            self.record_change("restitution", Some(name), previous.1.to_string(), restitution.to_string());
+        }
+        if let Some(density) = density {
+            self.refresh_mass(handle);
+            self.record_change("density", Some(name), previous.2.to_string(), density.to_string());
+        }
+        Ok(())
+    }
+
+    /// Change a body's total mass by scaling the density of its colliders
+    pub fn set_body_mass(&mut self, name: &str, mass: f32) -> Result<(), String> {
+        let handle = self.require_body(name)?;
+        if !mass.is_finite() || mass <= 0.0 {
+            return Err(format!("Mass must be positive, got {}", mass));
+        }
+
+        let colliders = self.rigid_body_set[handle].colliders().to_vec();
+        let collider_mass: f32 = colliders.iter().filter_map(|c| self.collider_set.get(*c)).map(|c| c.mass()).sum();
+        if collider_mass <= 0.0 {
+            return Err(format!("Body has no collider mass to scale: {}", name));
+        }
+
+        let previous = self.rigid_body_set[handle].mass();
+        let scale = mass / collider_mass;
+        for collider_handle in &colliders {
+            if let Some(collider) = self.collider_set.get_mut(*collider_handle) {
+                collider.set_density(collider.density() * scale);
+            }
+        }
+        self.refresh_mass(handle);
+        self.record_change("mass", Some(name), previous.to_string(), mass.to_string());
+        Ok(())
+    }
+
+    /// Update mass properties right away so metrics recorded before the next
+    /// step already use the new mass
+    fn refresh_mass(&mut self, handle: RigidBodyHandle) {
+        if let Some(body) = self.rigid_body_set.get_mut(handle) {
+            body.recompute_mass_properties_from_colliders(&self.collider_set);
+        }
+    }
+
+    fn record_change(&mut self, parameter: &str, body: Option<&str>, previous: String, value: String) {
+        let kind = EventKind::ParameterChanged {
+            parameter: parameter.to_string(),
+            body: body.map(str::to_string),
+            previous,
+            value,
+        };
+        self.pending_events.push(SimEvent::new(self.current_step, self.current_time, kind));
+    }
+}
+
+fn format_vec3(v: Vec3) -> String {
+    format!("[{}, {}, {}]", v.x, v.y, v.z)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BodyBuilder, MetricWorld};
+    use simuforge_core::{AggregateMetrics, EventKind, PhysicsConfig, Vec3};
+
+    fn world_with_ball() -> MetricWorld {
+        let mut world = MetricWorld::new(&PhysicsConfig::default());
+        let (body, collider, name) = BodyBuilder::new("ball")
+            .position_xyz(0.0, 10.0, 0.0)
+            .sphere(0.5)
+            .build();
+        let handle = world.add_body(body, name);
+        world.add_collider(collider, handle);
+        world
+    }
+
+    #[test]
+    fn test_gravity_change_recorded() {
+        let mut world = world_with_ball();
+        world.run(30);
+        world.set_gravity(Vec3::ZERO).unwrap();
+        world.run(30);
+
+        let vy = world.get_body_by_name("ball").unwrap().linvel().y;
+        assert!((vy + 9.81 * 0.5).abs() < 0.05, "{}", vy);
+
+        let metrics = AggregateMetrics::compute(world.frames());
+        assert_eq!(metrics.parameter_changes.len(), 1);
+        let change = &metrics.parameter_changes[0];
+        assert_eq!(change.step, 30);
+        assert!(matches!(&change.kind, EventKind::ParameterChanged { parameter, value, .. }
+            if parameter == "gravity" && value == "[0, 0, 0]"));
+    }
+
+    #[test]
+    fn test_body_mass_and_material() {
+        let mut world = world_with_ball();
+        world.set_body_mass("ball", 3.0).unwrap();
+        assert!((world.get_body_by_name("ball").unwrap().mass() - 3.0).abs() < 1e-4);
+
+        world.set_body_material("ball", Some(0.9), None, None).unwrap();
+        assert!(world.set_body_material("ball", None, Some(2.0), None).is_err());
+        assert!(world.set_body_mass("missing", 1.0).is_err());
+        assert!(world.set_timestep(0.0).is_err());
+
+        // Non-finite values are refused before they reach the solver or the colliders
+        assert!(world.set_gravity(Vec3::new(0.0, f32::NAN, 0.0)).is_err());
+        assert!(world.set_gravity(Vec3::new(f32::INFINITY, 0.0, 0.0)).is_err());
+        assert!(world.set_timestep(f32::INFINITY).is_err());
+        assert!(world.set_timestep(f32::NAN).is_err());
+        assert!(world.set_body_material("ball", Some(f32::NAN), None, None).is_err());
+        assert!(world.set_body_material("ball", None, Some(f32::NAN), None).is_err());
+        assert!(world.set_body_material("ball", None, None, Some(f32::NAN)).is_err());
+        assert!(world.set_body_material("ball", None, None, Some(f32::INFINITY)).is_err());
+        assert!(world.set_body_mass("ball", f32::INFINITY).is_err());
+        assert!((world.get_body_by_name("ball").unwrap().mass() - 3.0).abs() < 1e-4);
+
+        world.step();
+        assert_eq!(AggregateMetrics::compute(world.frames()).parameter_changes.len(), 2);
+    }
+}
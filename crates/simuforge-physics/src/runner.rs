@@ -0,0 +1,244 @@
+//! Experiment runner shared by the CLI and the browser
+//!
+//! Owns the path from a spec to a report: world and scenario setup, run
+//! length and report finalization. Callers only decide how to step.
+
+use crate::{MetricWorld, Scenario, create_scenario};
+use simuforge_core::{DurationConfig, ExperimentSpec, MetricFrame, ScenarioConfig, SimulationReport};
+use std::convert::Infallible;
+use std::fs;
+use std::path::Path;
+
+/// Deepest chain of included experiments, which also stops include cycles
+const MAX_INCLUDE_DEPTH: usize = 8;
+
+/// Load an experiment specification from a YAML file, resolving composite includes
+pub fn load_experiment(path: &Path) -> Result<ExperimentSpec, String> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read experiment file {}: {}", path.display(), e))?;
+
+    let mut spec: ExperimentSpec = serde_yaml::from_str(&content)
+        .map_err(|e| format!("Failed to parse experiment YAML {}: {}", path.display(), e))?;
+
+    let base_dir = path.parent().unwrap_or(Path::new("."));
+    resolve_includes(&mut spec.spec.scenario, base_dir, 0)?;
+
+    Ok(spec)
+}
+
+/// Replace composite instance includes with the scenarios of the included experiments.
+///
+/// Include paths are relative to the directory of the file that names them.
+fn resolve_includes(scenario: &mut ScenarioConfig, base_dir: &Path, depth: usize) -> Result<(), String> {
+    let ScenarioConfig::Composite { instances, .. } = scenario else {
+        return Ok(());
+    };
+
+    for instance in instances {
+        if let Some(scenario) = &mut instance.scenario {
+            resolve_includes(scenario, base_dir, depth)?;
+            continue;
+        }
+        let Some(include) = &instance.include else {
+            continue;
+        };
+        if depth >= MAX_INCLUDE_DEPTH {
+            return Err(format!("Instance {}: includes nested more than {} deep", instance.name, MAX_INCLUDE_DEPTH));
+        }
+
+        let path = base_dir.join(include);
+        let content = fs::read_to_string(&path)
+            .map_err(|e| format!("Instance {}: failed to read include {}: {}", instance.name, path.display(), e))?;
+        let included: ExperimentSpec = serde_yaml::from_str(&content)
+            .map_err(|e| format!("Instance {}: failed to parse include {}: {}", instance.name, path.display(), e))?;
+
+        let mut included = included.spec.scenario;
+        resolve_includes(&mut included, path.parent().unwrap_or(base_dir), depth + 1)?;
+        instance.scenario = Some(Box::new(included));
+    }
+    Ok(())
+}
+
+/// World and scenario set up from a spec, run for the spec's duration
+pub struct ExperimentRunner {
+    spec: ExperimentSpec,
+    world: MetricWorld,
+    scenario: Box<dyn Scenario>,
+    target_steps: u64,
+}
+
+impl ExperimentRunner {
+    /// Set up the spec's scenario, actions and actuators. The spec is expected
+    /// to be validated already.
+    pub fn new(spec: ExperimentSpec) -> Result<Self, String> {
+        let (world, scenario) = build_world(&spec)?;
+        let target_steps = target_steps(&spec);
+        Ok(Self { spec, world, scenario, target_steps })
+    }
+
+    pub fn spec(&self) -> &ExperimentSpec {
+        &self.spec
+    }
+
+    pub fn world(&self) -> &MetricWorld {
+        &self.world
+    }
+
+    pub fn world_mut(&mut self) -> &mut MetricWorld {
+        &mut self.world
+    }
+
+    pub fn scenario(&self) -> &dyn Scenario {
+        self.scenario.as_ref()
+    }
+
+    /// Steps in a full run
+    pub fn target_steps(&self) -> u64 {
+        self.target_steps
+    }
+
+    pub fn remaining_steps(&self) -> u64 {
+        self.target_steps.saturating_sub(self.world.step_count())
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.world.step_count() >= self.target_steps
+    }
+
+    /// Rebuild the world from the spec, undoing every step and live change
+    pub fn reset(&mut self) -> Result<(), String> {
+        let (world, scenario) = build_world(&self.spec)?;
+        self.world = world;
+        self.scenario = scenario;
+        Ok(())
+    }
+
+    /// Run the remaining steps
+    pub fn run_to_completion(&mut self) {
+        let _ = self.run_observed(|_| Ok::<_, Infallible>(()));
+    }
+
+    /// Step through the remaining steps, handing the world to `observe` before
+    /// every step and once more after the last. Stops at the first error.
+    pub fn run_observed<E>(&mut self, mut observe: impl FnMut(&MetricWorld) -> Result<(), E>) -> Result<(), E> {
+        while !self.is_complete() {
+            observe(&self.world)?;
+            self.world.step();
+        }
+        observe(&self.world)
+    }
+
+    /// Record the final state, unless stepping through `run` already did, and
+    /// build the report. Performance is left for the caller to fill in, since
+    /// it differs between otherwise identical runs.
+    pub fn finish(&mut self) -> SimulationReport {
+        let step = self.world.step_count();
+        if self.world.frames().last().is_none_or(|f| f.step < step) {
+            self.world.record_frame();
+        }
+        self.report()
+    }
+
+    /// Report on the frames recorded so far
+    pub fn report(&self) -> SimulationReport {
+        let frames = self.world.frames();
+        let mut report = SimulationReport::new(self.spec.metadata.name.clone());
+        report.finalize_with_scenario(frames, &self.spec.spec.criteria, self.scenario.compute_metrics(frames));
+        report
+    }
+
+    pub fn frames(&self) -> &[MetricFrame] {
+        self.world.frames()
+    }
+}
+
+fn build_world(spec: &ExperimentSpec) -> Result<(MetricWorld, Box<dyn Scenario>), String> {
+    let mut world = MetricWorld::from_spec(spec);
+    let scenario = create_scenario(&spec.spec.scenario);
+    scenario.setup(&mut world).map_err(|e| format!("Failed to set up scenario: {}", e))?;
+    world.set_actions(spec.spec.actions.clone());
+    world.set_actuators(&spec.spec.actuators)
+        .map_err(|e| format!("Failed to configure actuators: {}", e))?;
+    Ok((world, scenario))
+}
+
+fn target_steps(spec: &ExperimentSpec) -> u64 {
+    match &spec.spec.duration {
+        DurationConfig::Fixed { steps } => *steps,
+        DurationConfig::Time { seconds } => (*seconds / spec.spec.physics.timestep) as u64,
+        DurationConfig::UntilStable { max_steps, .. } => *max_steps,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use simuforge_core::ReportStatus;
+
+    fn benchmark(name: &str) -> ExperimentSpec {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("../../experiments/benchmarks").join(name);
+        load_experiment(&path).unwrap()
+    }
+
+    #[test]
+    fn test_reset_reproduces_report() {
+        let mut runner = ExperimentRunner::new(benchmark("bouncing-ball.yaml")).unwrap();
+        runner.run_to_completion();
+        let first = serde_json::to_value(runner.finish()).unwrap();
+
+        runner.world_mut().set_gravity(simuforge_core::Vec3::ZERO).unwrap();
+        runner.reset().unwrap();
+        assert_eq!(runner.world().step_count(), 0);
+        runner.run_to_completion();
+        assert_eq!(serde_json::to_value(runner.finish()).unwrap(), first);
+        assert!(runner.is_complete());
+    }
+
+    #[test]
+    fn test_setup_errors_are_reported() {
+        let yaml = r#"
+apiVersion: simuforge/v1
+kind: Experiment
+metadata:
+  name: loop
+spec:
+  physics: {}
+  duration: { type: fixed, steps: 10 }
+  scenario:
+    type: custom
+    bodies:
+      - { name: a, shape: { type: sphere, radius: 0.2 }, position: [0, 1, 0] }
+      - { name: b, shape: { type: sphere, radius: 0.2 }, position: [1, 1, 0] }
+    joints:
+      - { name: ab, type: spherical, body_a: a, body_b: b, multibody: true }
+      - { name: ba, type: spherical, body_a: b, body_b: a, multibody: true }
+"#;
+        let spec: ExperimentSpec = serde_yaml::from_str(yaml).unwrap();
+        assert!(spec.validate().is_ok());
+        let error = ExperimentRunner::new(spec).err().unwrap();
+        assert!(error.contains("Joint ba"), "{}", error);
+    }
+
+    #[test]
+    fn test_unresolved_include_fails_setup() {
+        // Parsed without `load_experiment`, as the browser does
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("../../experiments/benchmarks/composite-stacks.yaml");
+        let spec: ExperimentSpec = serde_yaml::from_str(&fs::read_to_string(path).unwrap()).unwrap();
+        let error = ExperimentRunner::new(spec).err().unwrap();
+        assert!(error.contains("Instance left: include box-stack-10.yaml is not resolved"), "{}", error);
+    }
+
+    #[test]
+    fn test_short_run_fails_unmeasured_criterion() {
+        // 30 steps is well short of one swing period, so retention cannot be measured
+        let mut spec = benchmark("newtons-cradle.yaml");
+        spec.spec.duration = DurationConfig::Fixed { steps: 30 };
+        let mut runner = ExperimentRunner::new(spec).unwrap();
+        runner.run_to_completion();
+        let report = runner.finish();
+
+        let retained = &report.criteria_results["energy_retained_per_cycle"];
+        assert!(retained.value.is_nan() && !retained.passed);
+        assert_eq!(report.status, ReportStatus::Failed);
+    }
+}
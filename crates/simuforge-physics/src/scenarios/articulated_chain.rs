@@ -0,0 +1,277 @@
+//! Articulated chain scenario
+
+use crate::{MetricWorld, BodyBuilder, Scenario};
+use rapier3d::prelude::*;
+use simuforge_core::{MetricFrame, Vec3};
+use simuforge_core::metrics::BodyState;
+use std::collections::{BTreeMap, HashMap};
+use super::{get_f32, get_str, get_u32, vertical_gravity};
+
+/// Height of the chain pivots above the origin
+const PIVOT_HEIGHT: f32 = 5.0;
+
+/// Distance between the two chains when both formulations are built
+const CHAIN_SEPARATION: f32 = 1.0;
+
+/// How the chain joints are solved
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum JointFormulation {
+    /// Reduced coordinates; links cannot drift apart, but Rapier adds a small angular damping
+    Multibody,
+    /// Maximal coordinates with joint constraints solved alongside contacts
+    Impulse,
+    /// One chain of each, side by side, for comparison in a single run
+    Both,
+}
+
+impl JointFormulation {
+    fn prefixes(self) -> &'static [&'static str] {
+        match self {
+            JointFormulation::Multibody => &["multibody"],
+            JointFormulation::Impulse => &["impulse"],
+            JointFormulation::Both => &["multibody", "impulse"],
+        }
+    }
+}
+
+/// Scenario: Chain of box links hinged end to end, released from a straight pose
+pub struct ArticulatedChainScenario {
+    pub links: u32,
+    pub link_length: f32,
+    /// Width and depth of each link
+    pub link_width: f32,
+    pub density: f32,
+    /// Release angle of the straight chain below horizontal (rad)
+    pub initial_angle: f32,
+    pub formulation: JointFormulation,
+}
+
+impl Default for ArticulatedChainScenario {
+    fn default() -> Self {
+        Self {
+            links: 8,
+            link_length: 0.5,
+            link_width: 0.1,
+            density: 1.0,
+            initial_angle: 0.0,
+            formulation: JointFormulation::Both,
+        }
+    }
+}
+
+impl ArticulatedChainScenario {
+    pub fn new(links: u32, formulation: JointFormulation) -> Self {
+        Self {
+            links,
+            formulation,
+            ..Default::default()
+        }
+    }
+
+    pub fn from_params(params: &HashMap<String, serde_yaml::Value>) -> Self {
+        let defaults = Self::default();
+        Self {
+            links: get_u32(params, "links", defaults.links).max(1),
+            link_length: get_f32(params, "link_length", defaults.link_length),
+            link_width: get_f32(params, "link_width", defaults.link_width),
+            density: get_f32(params, "density", defaults.density),
+            initial_angle: get_f32(params, "initial_angle", defaults.initial_angle),
+            formulation: match get_str(params, "formulation", "both") {
+                "multibody" => JointFormulation::Multibody,
+                "impulse" => JointFormulation::Impulse,
+                _ => JointFormulation::Both,
+            },
+        }
+    }
+
+    fn link_name(prefix: &str, index: u32) -> String {
+        format!("{}_link_{}", prefix, index)
+    }
+
+    fn pivot(&self, prefix: &str) -> Vec3 {
+        let z = match (self.formulation, prefix) {
+            (JointFormulation::Both, "multibody") => -CHAIN_SEPARATION / 2.0,
+            (JointFormulation::Both, _) => CHAIN_SEPARATION / 2.0,
+            _ => 0.0,
+        };
+        Vec3::new(0.0, PIVOT_HEIGHT, z)
+    }
+
+    fn link_mass(&self) -> f32 {
+        self.density * self.link_length * self.link_width * self.link_width
+    }
+
+    fn build_chain(&self, world: &mut MetricWorld, prefix: &str) {
+        let pivot = self.pivot(prefix);
+        let direction = Vec3::new(self.initial_angle.cos(), -self.initial_angle.sin(), 0.0);
+        let half_length = self.link_length / 2.0;
+        let half_width = self.link_width / 2.0;
+
+        // Fixed root the first link hangs from; it has no collider
+        let (anchor_body, _, anchor_name) = BodyBuilder::new(format!("{}_anchor", prefix))
+            .position(pivot)
+            .fixed()
+            .build();
+        let mut parent = world.add_body(anchor_body, anchor_name);
+        let mut parent_anchor = point![0.0, 0.0, 0.0];
+
+        for i in 0..self.links {
+            let along = self.link_length * (i as f32 + 0.5);
+            let (body, collider, name) = BodyBuilder::new(Self::link_name(prefix, i))
+                .position_xyz(pivot.x + direction.x * along, pivot.y + direction.y * along, pivot.z)
+                .rotation(Vec3::new(0.0, 0.0, 1.0), -self.initial_angle)
+                .box_shape(half_length, half_width, half_width)
+                .dynamic()
+                .density(self.density)
+                .build();
+            let link = world.add_body(body, name);
+            world.add_collider(collider, link);
+
+            // Neighbouring links overlap at the hinge, so their contacts are disabled
+            let joint = RevoluteJointBuilder::new(Vector::z_axis())
+                .local_anchor1(parent_anchor)
+                .local_anchor2(point![-half_length, 0.0, 0.0])
+                .contacts_enabled(false);
+            let joint_name = format!("{}_joint_{}", prefix, i);
+            if prefix == "multibody" {
+                world.add_multibody_joint(joint_name, parent, link, joint);
+            } else {
+                world.add_joint(joint_name, parent, link, joint);
+            }
+
+            parent = link;
+            parent_anchor = point![half_length, 0.0, 0.0];
+        }
+    }
+
+    /// Largest gap between the hinge points of neighbouring links
+    fn max_joint_gap(&self, bodies: &[BodyState], prefix: &str) -> Option<f32> {
+        let half_length = self.link_length / 2.0;
+        let end = |name: &str, offset: f32| {
+            let body = bodies.iter().find(|b| b.name == name)?;
+            let local = Vector::x() * offset;
+            Some(body.transform.position.to_nalgebra() + body.transform.rotation.to_nalgebra() * local)
+        };
+
+        let mut parent_end = self.pivot(prefix).to_nalgebra();
+        let mut max_gap = 0.0f32;
+        for i in 0..self.links {
+            let name = Self::link_name(prefix, i);
+            let start = end(&name, -half_length)?;
+            max_gap = max_gap.max((start - parent_end).norm());
+            parent_end = end(&name, half_length)?;
+        }
+        Some(max_gap)
+    }
+
+    /// Kinetic and potential energy of one chain's links
+    fn chain_energy(&self, bodies: &[BodyState], prefix: &str, gravity: f32) -> f32 {
+        let mass = self.link_mass();
+        let (l, w) = (self.link_length, self.link_width);
+        let inertia = vector![2.0 * w * w, l * l + w * w, l * l + w * w] * (mass / 12.0);
+
+        bodies.iter()
+            .filter(|b| b.name.starts_with(&format!("{}_link_", prefix)))
+            .map(|b| {
+                // Box inertia is diagonal in the link's own frame
+                let omega = b.transform.rotation.to_nalgebra().inverse() * b.angular_velocity.to_nalgebra();
+                0.5 * mass * b.velocity.magnitude_squared()
+                    + 0.5 * omega.component_mul(&omega).dot(&inertia)
+                    + mass * gravity * b.transform.position.y
+            })
+            .sum()
+    }
+}
+
+impl Scenario for ArticulatedChainScenario {
+    fn name(&self) -> &str {
+        "articulated_chain"
+    }
+
+    fn description(&self) -> &str {
+        "Hinged chain built from multibody or impulse joints, compares joint drift and energy"
+    }
+
+    fn setup(&self, world: &mut MetricWorld) -> Result<(), String> {
+        for prefix in self.formulation.prefixes() {
+            self.build_chain(world, prefix);
+        }
+        Ok(())
+    }
+
+    fn compute_metrics(&self, frames: &[MetricFrame]) -> BTreeMap<String, f64> {
+        let mut metrics = BTreeMap::new();
+        let (Some(first), Some(last)) = (frames.first(), frames.last()) else {
+            return metrics;
+        };
+        if last.bodies.is_empty() {
+            return metrics;
+        }
+
+        let mass = self.link_mass();
+        let gravity = vertical_gravity(frames);
+
+        for prefix in self.formulation.prefixes() {
+            let gaps: Vec<f32> = frames.iter().filter_map(|f| self.max_joint_gap(&f.bodies, prefix)).collect();
+            if let Some(max_gap) = gaps.iter().copied().reduce(f32::max) {
+                metrics.insert(format!("{}.max_joint_drift", prefix), max_gap as f64);
+            }
+            if let Some(final_gap) = self.max_joint_gap(&last.bodies, prefix) {
+                metrics.insert(format!("{}.final_joint_drift", prefix), final_gap as f64);
+            }
+
+            let Some(gravity) = gravity else {
+                continue;
+            };
+
+            // Swing energy is measured above the chain hanging straight down at rest
+            let pivot_y = self.pivot(prefix).y;
+            let rest: f32 = (0..self.links)
+                .map(|i| mass * gravity * (pivot_y - self.link_length * (i as f32 + 0.5)))
+                .sum();
+            let swing = |frame: &MetricFrame| self.chain_energy(&frame.bodies, prefix, gravity) - rest;
+
+            let initial = swing(first);
+            if initial <= 1e-9 {
+                continue;
+            }
+            let max_error = frames.iter().map(|f| (swing(f) - initial).abs()).fold(0.0f32, f32::max);
+            metrics.insert(format!("{}.energy_drift_percent", prefix), ((swing(last) - initial) / initial * 100.0) as f64);
+            metrics.insert(format!("{}.max_energy_error_percent", prefix), (max_error / initial * 100.0) as f64);
+        }
+
+        metrics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use simuforge_core::PhysicsConfig;
+
+    #[test]
+    fn test_both_chains_built() {
+        let mut world = MetricWorld::new(&PhysicsConfig::default());
+        let scenario = ArticulatedChainScenario::new(4, JointFormulation::Both);
+        scenario.setup(&mut world).unwrap();
+
+        // Anchor plus four links per chain
+        assert_eq!(world.body_count(), 10);
+        assert!(world.multibody_joint_handle("multibody_joint_3").is_some());
+        assert!(world.joint_handle("impulse_joint_3").is_some());
+        assert!(world.joint_state("multibody_joint_0").is_some());
+    }
+
+    #[test]
+    fn test_multibody_chain_holds_together() {
+        let mut world = MetricWorld::new(&PhysicsConfig::default());
+        let scenario = ArticulatedChainScenario::new(6, JointFormulation::Both);
+        scenario.setup(&mut world).unwrap();
+        world.run(180);
+
+        let metrics = scenario.compute_metrics(world.frames());
+        assert!(metrics["multibody.max_joint_drift"] < 1e-3, "{:?}", metrics);
+        assert!(metrics["impulse.max_joint_drift"] >= metrics["multibody.max_joint_drift"], "{:?}", metrics);
+        assert!(metrics.contains_key("impulse.energy_drift_percent"), "{:?}", metrics);
+    }
+}
@@ -1,9 +1,13 @@
 //! Bouncing ball scenario
 
 use crate::{MetricWorld, BodyBuilder, Scenario};
-use std::collections::HashMap;
+use simuforge_core::MetricFrame;
+use std::collections::{BTreeMap, HashMap};
 use super::get_f32;
 
+/// Bounces lower than this fraction of the drop height are too short to measure reliably
+const MIN_BOUNCE_FRACTION: f32 = 0.02;
+
 /// Scenario: Ball dropped from height, bouncing on ground
 pub struct BouncingBallScenario {
     pub radius: f32,
@@ -35,6 +39,11 @@ impl BouncingBallScenario {
             density: get_f32(params, "density", 1.0),
         }
     }
+
+    /// Apex height of the `n`th bounce above the ground, `h0 * e^(2n)`
+    pub fn expected_bounce_height(&self, n: u32) -> f32 {
+        self.drop_height * self.restitution.powi(2 * n as i32)
+    }
 }
 
 impl Scenario for BouncingBallScenario {
@@ -46,7 +55,7 @@ impl Scenario for BouncingBallScenario {
         "Ball dropped from height, tests restitution and energy conservation"
     }
 
-    fn setup(&self, world: &mut MetricWorld) {
+    fn setup(&self, world: &mut MetricWorld) -> Result<(), String> {
         // Add ground plane
         let (ground_body, ground_collider, ground_name) = BodyBuilder::new("ground")
             .position_xyz(0.0, -0.5, 0.0)
@@ -71,6 +80,53 @@ impl Scenario for BouncingBallScenario {
 
         let ball_handle = world.add_body(ball_body, ball_name);
         world.add_collider(ball_collider, ball_handle);
+        Ok(())
+    }
+
+    fn compute_metrics(&self, frames: &[MetricFrame]) -> BTreeMap<String, f64> {
+        let mut metrics = BTreeMap::new();
+
+        // Height of the ball's underside and its vertical velocity
+        let samples: Vec<(f32, f32)> = frames
+            .iter()
+            .filter_map(|f| {
+                let ball = f.bodies.iter().find(|b| b.name == "ball")?;
+                Some((ball.transform.position.y - self.radius, ball.velocity.y))
+            })
+            .collect();
+
+        // Apexes are where the ball stops rising; the release point is not one
+        let apexes: Vec<f32> = samples
+            .windows(2)
+            .filter(|w| w[0].1 > 0.0 && w[1].1 <= 0.0)
+            .map(|w| w[0].0.max(w[1].0))
+            .take_while(|h| *h > MIN_BOUNCE_FRACTION * self.drop_height)
+            .collect();
+
+        metrics.insert("bounces_measured".to_string(), apexes.len() as f64);
+        if apexes.is_empty() {
+            return metrics;
+        }
+
+        let errors: Vec<f32> = apexes
+            .iter()
+            .enumerate()
+            .map(|(i, h)| {
+                let expected = self.expected_bounce_height(i as u32 + 1);
+                (h - expected).abs() / expected
+            })
+            .collect();
+        let mean_error = errors.iter().sum::<f32>() / errors.len() as f32;
+        let max_error = errors.iter().copied().fold(0.0f32, f32::max);
+
+        metrics.insert("bounce_height_error".to_string(), mean_error as f64);
+        metrics.insert("max_bounce_height_error".to_string(), max_error as f64);
+        metrics.insert("first_bounce_height".to_string(), apexes[0] as f64);
+        if self.drop_height > 0.0 {
+            let restitution = (apexes[0] / self.drop_height).sqrt();
+            metrics.insert("effective_restitution".to_string(), restitution as f64);
+        }
+        metrics
     }
 }
 
@@ -85,8 +141,20 @@ mod tests {
         let mut world = MetricWorld::new(&config);
 
         let scenario = BouncingBallScenario::default();
-        scenario.setup(&mut world);
+        scenario.setup(&mut world).unwrap();
 
         assert_eq!(world.body_count(), 2);
     }
+
+    #[test]
+    fn test_bounce_heights_follow_restitution() {
+        let mut world = MetricWorld::new(&PhysicsConfig::default());
+        let scenario = BouncingBallScenario::default();
+        scenario.setup(&mut world).unwrap();
+        world.run(600);
+
+        let metrics = scenario.compute_metrics(world.frames());
+        assert!(metrics["bounces_measured"] >= 3.0, "{:?}", metrics);
+        assert!(metrics["bounce_height_error"] < 0.1, "{:?}", metrics);
+    }
 }
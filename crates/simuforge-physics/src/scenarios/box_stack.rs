@@ -1,7 +1,6 @@
 //! Box stacking scenario
 
 use crate::{MetricWorld, BodyBuilder, Scenario};
-use rapier3d::prelude::*;
 use std::collections::HashMap;
 use super::{get_f32, get_u32, get_vec3};
 
@@ -64,7 +63,7 @@ impl Scenario for BoxStackScenario {
         "Stack of boxes on a ground plane, tests stability and contact handling"
     }
 
-    fn setup(&self, world: &mut MetricWorld) {
+    fn setup(&self, world: &mut MetricWorld) -> Result<(), String> {
         let half_x = self.box_size[0] / 2.0;
         let half_y = self.box_size[1] / 2.0;
         let half_z = self.box_size[2] / 2.0;
@@ -97,6 +96,7 @@ impl Scenario for BoxStackScenario {
             let handle = world.add_body(body, name);
             world.add_collider(collider, handle);
         }
+        Ok(())
     }
 }
 
@@ -111,7 +111,7 @@ mod tests {
         let mut world = MetricWorld::new(&config);
 
         let scenario = BoxStackScenario::new(5);
-        scenario.setup(&mut world);
+        scenario.setup(&mut world).unwrap();
 
         // Ground + 5 boxes
         assert_eq!(world.body_count(), 6);
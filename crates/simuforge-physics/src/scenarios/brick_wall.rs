@@ -0,0 +1,167 @@
+//! Brick wall scenario
+
+use crate::{MetricWorld, BodyBuilder, Scenario};
+use simuforge_core::MetricFrame;
+use std::collections::{BTreeMap, HashMap};
+use super::{displacement_metrics, get_f32, get_u32, get_vec3};
+
+/// Scenario: Wall of bricks laid in a staggered (running) bond
+pub struct BrickWallScenario {
+    pub rows: u32,
+    /// Number of full bricks per row
+    pub columns: u32,
+    pub brick_size: [f32; 3],
+    /// Gap between neighbouring bricks in a row
+    pub spacing: f32,
+    pub friction: f32,
+    pub restitution: f32,
+    /// Displacement beyond which a brick counts as fallen
+    pub collapse_threshold: f32,
+}
+
+impl Default for BrickWallScenario {
+    fn default() -> Self {
+        Self {
+            rows: 6,
+            columns: 5,
+            brick_size: [1.0, 0.5, 0.5],
+            spacing: 0.0,
+            friction: 0.6,
+            restitution: 0.1,
+            collapse_threshold: 0.25,
+        }
+    }
+}
+
+impl BrickWallScenario {
+    pub fn new(rows: u32, columns: u32) -> Self {
+        Self {
+            rows,
+            columns,
+            ..Default::default()
+        }
+    }
+
+    pub fn from_params(params: &HashMap<String, serde_yaml::Value>) -> Self {
+        let brick_size = get_vec3(params, "brick_size", [1.0, 0.5, 0.5]);
+        Self {
+            rows: get_u32(params, "rows", 6),
+            columns: get_u32(params, "columns", 5),
+            brick_size,
+            spacing: get_f32(params, "spacing", 0.0),
+            friction: get_f32(params, "friction", 0.6),
+            restitution: get_f32(params, "restitution", 0.1),
+            collapse_threshold: get_f32(params, "collapse_threshold", brick_size[1] / 2.0),
+        }
+    }
+
+    /// Brick centers and half lengths along X for one row.
+    ///
+    /// Odd rows are shifted by half a brick, with half bricks closing both ends
+    /// so every row spans the same length.
+    fn row_layout(&self, row: u32) -> Vec<(f32, f32)> {
+        let length = self.brick_size[0];
+        let pitch = length + self.spacing;
+        let start = -(self.columns as f32 * pitch - self.spacing) / 2.0;
+
+        if row.is_multiple_of(2) {
+            return (0..self.columns)
+                .map(|i| (start + i as f32 * pitch + length / 2.0, length / 2.0))
+                .collect();
+        }
+
+        let half = (length - self.spacing) / 2.0;
+        let mut layout = vec![(start + half / 2.0, half / 2.0)];
+        let mut x = start + half + self.spacing;
+        for _ in 1..self.columns {
+            layout.push((x + length / 2.0, length / 2.0));
+            x += pitch;
+        }
+        layout.push((x + half / 2.0, half / 2.0));
+        layout
+    }
+}
+
+impl Scenario for BrickWallScenario {
+    fn name(&self) -> &str {
+        "brick_wall"
+    }
+
+    fn description(&self) -> &str {
+        "Staggered brick wall on a ground plane, tests solver iterations and warm starting"
+    }
+
+    fn setup(&self, world: &mut MetricWorld) -> Result<(), String> {
+        let half_y = self.brick_size[1] / 2.0;
+        let half_z = self.brick_size[2] / 2.0;
+
+        // Add ground plane
+        let (ground_body, ground_collider, ground_name) = BodyBuilder::new("ground")
+            .position_xyz(0.0, -0.5, 0.0)
+            .box_shape(50.0, 0.5, 50.0)
+            .fixed()
+            .friction(self.friction)
+            .restitution(self.restitution)
+            .build();
+
+        let ground_handle = world.add_body(ground_body, ground_name);
+        world.add_collider(ground_collider, ground_handle);
+
+        for row in 0..self.rows {
+            let y = half_y + self.brick_size[1] * row as f32;
+
+            for (i, (x, half_x)) in self.row_layout(row).into_iter().enumerate() {
+                let (body, collider, name) = BodyBuilder::new(format!("brick_{}_{}", row, i))
+                    .position_xyz(x, y, 0.0)
+                    .box_shape(half_x, half_y, half_z)
+                    .dynamic()
+                    .friction(self.friction)
+                    .restitution(self.restitution)
+                    .build();
+
+                let handle = world.add_body(body, name);
+                world.add_collider(collider, handle);
+            }
+        }
+        Ok(())
+    }
+
+    fn compute_metrics(&self, frames: &[MetricFrame]) -> BTreeMap<String, f64> {
+        displacement_metrics(frames, "brick_", self.collapse_threshold)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use simuforge_core::PhysicsConfig;
+
+    #[test]
+    fn test_staggered_rows_span_same_length() {
+        let wall = BrickWallScenario::new(2, 4);
+        let extent = |row| {
+            let layout = wall.row_layout(row);
+            let (first, first_half) = layout[0];
+            let (last, last_half) = layout[layout.len() - 1];
+            (first - first_half, last + last_half)
+        };
+
+        assert_eq!(wall.row_layout(0).len(), 4);
+        assert_eq!(wall.row_layout(1).len(), 5);
+        let (a0, b0) = extent(0);
+        let (a1, b1) = extent(1);
+        assert!((a0 - a1).abs() < 1e-5 && (b0 - b1).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_wall_stands() {
+        let mut world = MetricWorld::new(&PhysicsConfig::default());
+        let scenario = BrickWallScenario::new(4, 3);
+        scenario.setup(&mut world).unwrap();
+        world.run(120);
+
+        let metrics = scenario.compute_metrics(world.frames());
+        assert_eq!(metrics["collapsed"], 0.0, "{:?}", metrics);
+        assert_eq!(metrics["displaced_boxes"], 0.0);
+    }
+}
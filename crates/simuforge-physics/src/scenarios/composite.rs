@@ -0,0 +1,325 @@
+//! Composite scenario built from several scenario instances
+
+use crate::{MetricWorld, Scenario};
+use rapier3d::prelude::*;
+use simuforge_core::events::{EventKind, SimEvent};
+use simuforge_core::metrics::{EnergyMetrics, MomentumMetrics};
+use simuforge_core::generators::{expand_bodies, BodyEntry};
+use simuforge_core::spec::{InstanceGround, JointConfig, INSTANCE_SEPARATOR};
+use simuforge_core::{MetricFrame, Vec3};
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Scenario placed under a name prefix and shifted by an offset
+pub struct ScenarioInstance {
+    pub name: String,
+    pub offset: Vec3,
+    pub ground: InstanceGround,
+    pub scenario: Box<dyn Scenario>,
+}
+
+impl ScenarioInstance {
+    fn prefixed(&self, name: &str) -> String {
+        format!("{}{}{}", self.name, INSTANCE_SEPARATOR, name)
+    }
+
+    fn local<'a>(&self, name: &'a str) -> Option<&'a str> {
+        name.strip_prefix(self.name.as_str())?.strip_prefix(INSTANCE_SEPARATOR)
+    }
+}
+
+/// Stands in for an instance whose include was never loaded, so setup fails instead
+/// of the instance silently going missing
+pub(crate) struct UnresolvedInclude(pub String);
+
+impl Scenario for UnresolvedInclude {
+    fn name(&self) -> &str {
+        "unresolved_include"
+    }
+
+    fn description(&self) -> &str {
+        "Included experiment that was not loaded"
+    }
+
+    fn setup(&self, _world: &mut MetricWorld) -> Result<(), String> {
+        Err(format!("include {} is not resolved; load the experiment from its file", self.0))
+    }
+}
+
+/// Masses of an instance's dynamic bodies, by local name
+type InstanceMasses = HashMap<String, f32>;
+
+/// Scenario: Several scenarios side by side in one world, plus extra custom bodies and joints
+pub struct CompositeScenario {
+    pub instances: Vec<ScenarioInstance>,
+    pub bodies: Vec<BodyEntry>,
+    pub joints: Vec<JointConfig>,
+    /// Recorded during setup so each instance's energy can be measured on its own
+    masses: RefCell<Vec<InstanceMasses>>,
+}
+
+impl CompositeScenario {
+    pub fn new(instances: Vec<ScenarioInstance>, bodies: Vec<BodyEntry>, joints: Vec<JointConfig>) -> Self {
+        Self {
+            instances,
+            bodies,
+            joints,
+            masses: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Set up one instance, then move and rename everything it created
+    fn setup_instance(&self, world: &mut MetricWorld, instance: &ScenarioInstance) -> Result<InstanceMasses, String> {
+        let existing: HashSet<RigidBodyHandle> = world.rigid_body_set.iter().map(|(h, _)| h).collect();
+        let joint_count = world.joints.len();
+        let target_count = world.actuator_targets.len();
+
+        instance.scenario.setup(world).map_err(|e| format!("Instance {}: {}", instance.name, e))?;
+
+        let added: Vec<RigidBodyHandle> = world.rigid_body_set
+            .iter()
+            .map(|(h, _)| h)
+            .filter(|h| !existing.contains(h))
+            .collect();
+
+        let mut masses = HashMap::new();
+        for handle in added {
+            let Some(name) = world.body_names.get(&handle).cloned() else {
+                continue;
+            };
+            if instance.ground == InstanceGround::Shared && name == "ground" {
+                world.remove_body(handle);
+                continue;
+            }
+            if let Some(body) = world.rigid_body_set.get_mut(handle) {
+                let translation = body.translation() + instance.offset.to_nalgebra();
+                body.set_translation(translation, false);
+                if body.is_dynamic() {
+                    masses.insert(name.clone(), body.mass());
+                }
+            }
+            world.body_names.insert(handle, instance.prefixed(&name));
+        }
+
+        // Joints and motor targets are matched by name, so both are renamed together
+        for joint in &mut world.joints[joint_count..] {
+            joint.name = instance.prefixed(&joint.name);
+        }
+        for target in &mut world.actuator_targets[target_count..] {
+            target.joint = instance.prefixed(&target.joint);
+        }
+
+        Ok(masses)
+    }
+
+    /// Body names with an instance prefix must name a body that instance created
+    fn check_instance_bodies(&self, world: &MetricWorld, joint: &JointConfig) -> Result<(), String> {
+        for name in [&joint.body_a, &joint.body_b] {
+            let Some((prefix, local)) = name.split_once(INSTANCE_SEPARATOR) else {
+                continue;
+            };
+            if self.instances.iter().any(|i| i.name == prefix) && world.get_body_by_name(name).is_none() {
+                return Err(format!("Joint {}: instance {} has no body {}", joint.name, prefix, local));
+            }
+        }
+        Ok(())
+    }
+
+    /// Frames as the instance's own scenario would have recorded them on its own:
+    /// local body names and positions, and energy and momentum of its bodies only.
+    /// Contact metrics still cover the whole world.
+    fn instance_frames(&self, frames: &[MetricFrame], instance: &ScenarioInstance, masses: &InstanceMasses) -> Vec<MetricFrame> {
+        frames
+            .iter()
+            .map(|frame| {
+                let mut local = MetricFrame::new(frame.step, frame.time);
+                local.contacts = frame.contacts.clone();
+                local.gravity = frame.gravity;
+                let gravity = frame.gravity.y.abs();
+
+                local.bodies = frame.bodies
+                    .iter()
+                    .filter_map(|body| {
+                        let name = instance.local(&body.name)?;
+                        let mut body = body.clone();
+                        body.name = name.to_string();
+                        body.transform.position = body.transform.position - instance.offset;
+                        Some(body)
+                    })
+                    .collect();
+
+                // Same simplified formulas as the world's own energy and momentum
+                let (mut kinetic, mut potential) = (0.0, 0.0);
+                let (mut linear, mut angular) = (Vec3::ZERO, Vec3::ZERO);
+                for body in &local.bodies {
+                    let Some(&mass) = masses.get(&body.name) else {
+                        continue;
+                    };
+                    kinetic += 0.5 * mass * body.velocity.magnitude_squared()
+                        + 0.5 * mass * 0.4 * body.angular_velocity.magnitude_squared();
+                    potential += mass * gravity * body.transform.position.y;
+                    linear = linear + body.velocity * mass;
+                    angular = angular + body.angular_velocity * (mass * 0.4);
+                }
+                local.energy = EnergyMetrics::new(kinetic, potential);
+                local.momentum = MomentumMetrics::new(linear, angular);
+
+                local.events = frame.events.iter().filter_map(|e| localize_event(e, instance)).collect();
+                local.actuators = frame.actuators
+                    .iter()
+                    .filter_map(|actuator| {
+                        let joint = instance.local(&actuator.joint)?;
+                        let mut actuator = actuator.clone();
+                        actuator.joint = joint.to_string();
+                        Some(actuator)
+                    })
+                    .collect();
+                local
+            })
+            .collect()
+    }
+}
+
+/// Event with the instance prefix stripped, if every body it names belongs to the instance
+fn localize_event(event: &SimEvent, instance: &ScenarioInstance) -> Option<SimEvent> {
+    let local = |name: &str| instance.local(name).map(str::to_string);
+    let kind = match &event.kind {
+        EventKind::CollisionStarted { body_a, body_b } => EventKind::CollisionStarted {
+            body_a: local(body_a)?,
+            body_b: local(body_b)?,
+        },
+        EventKind::CollisionStopped { body_a, body_b } => EventKind::CollisionStopped {
+            body_a: local(body_a)?,
+            body_b: local(body_b)?,
+        },
+        EventKind::SensorEntered { sensor, body } => EventKind::SensorEntered {
+            sensor: local(sensor)?,
+            body: local(body)?,
+        },
+        EventKind::SensorExited { sensor, body } => EventKind::SensorExited {
+            sensor: local(sensor)?,
+            body: local(body)?,
+        },
+        EventKind::ContactForce { body_a, body_b, total_force_magnitude, max_force_magnitude } => EventKind::ContactForce {
+            body_a: local(body_a)?,
+            body_b: local(body_b)?,
+            total_force_magnitude: *total_force_magnitude,
+            max_force_magnitude: *max_force_magnitude,
+        },
+        // Scripted actions and parameter changes belong to the whole experiment
+        EventKind::ActionApplied { .. } | EventKind::ActionFailed { .. } | EventKind::ParameterChanged { .. } => return None,
+    };
+    Some(SimEvent::new(event.step, event.time, kind))
+}
+
+impl Scenario for CompositeScenario {
+    fn name(&self) -> &str {
+        "composite"
+    }
+
+    fn description(&self) -> &str {
+        "Several scenario instances in one world"
+    }
+
+    fn setup(&self, world: &mut MetricWorld) -> Result<(), String> {
+        let masses = self.instances
+            .iter()
+            .map(|instance| self.setup_instance(world, instance))
+            .collect::<Result<_, _>>()?;
+        *self.masses.borrow_mut() = masses;
+
+        for body_config in &expand_bodies(&self.bodies, world.seed()) {
+            world.add_body_from_config(body_config);
+        }
+        for joint_config in &self.joints {
+            self.check_instance_bodies(world, joint_config)?;
+            world.add_joint_from_config(joint_config)?;
+        }
+        Ok(())
+    }
+
+    fn compute_metrics(&self, frames: &[MetricFrame]) -> BTreeMap<String, f64> {
+        let masses = self.masses.borrow();
+        let mut metrics = BTreeMap::new();
+        for (instance, masses) in self.instances.iter().zip(masses.iter()) {
+            let local = self.instance_frames(frames, instance, masses);
+            for (key, value) in instance.scenario.compute_metrics(&local) {
+                metrics.insert(instance.prefixed(&key), value);
+            }
+        }
+        metrics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scenarios::{BouncingBallScenario, BoxStackScenario};
+    use simuforge_core::PhysicsConfig;
+
+    fn stack(name: &str, x: f32) -> ScenarioInstance {
+        ScenarioInstance {
+            name: name.to_string(),
+            offset: Vec3::new(x, 0.0, 0.0),
+            ground: InstanceGround::Own,
+            scenario: Box::new(BoxStackScenario::new(3)),
+        }
+    }
+
+    #[test]
+    fn test_instances_are_prefixed_and_offset() {
+        let mut world = MetricWorld::new(&PhysicsConfig::default());
+        let scenario = CompositeScenario::new(vec![stack("left", -5.0), stack("right", 5.0)], Vec::new(), Vec::new());
+        scenario.setup(&mut world).unwrap();
+
+        let left = world.get_body_by_name("left/box_0").unwrap().translation().x;
+        let right = world.get_body_by_name("right/box_0").unwrap().translation().x;
+        assert!((right - left - 10.0).abs() < 1e-5);
+        assert!(world.get_body_by_name("box_0").is_none());
+    }
+
+    #[test]
+    fn test_shared_ground_and_instance_joint_bodies() {
+        let mut world = MetricWorld::new(&PhysicsConfig::default());
+        let mut right = stack("right", 5.0);
+        right.ground = InstanceGround::Shared;
+        let joint: JointConfig = serde_yaml::from_str("{ name: tie, type: fixed, body_a: left/box_2, body_b: right/box_0 }").unwrap();
+        let scenario = CompositeScenario::new(vec![stack("left", -5.0), right], Vec::new(), vec![joint.clone()]);
+        scenario.setup(&mut world).unwrap();
+
+        assert!(world.get_body_by_name("left/ground").is_some());
+        assert!(world.get_body_by_name("right/ground").is_none());
+
+        let mut world = MetricWorld::new(&PhysicsConfig::default());
+        let missing = JointConfig { body_b: "right/box_9".to_string(), ..joint };
+        let scenario = CompositeScenario::new(vec![stack("left", -5.0), stack("right", 5.0)], Vec::new(), vec![missing]);
+        let error = scenario.setup(&mut world).unwrap_err();
+        assert!(error.contains("instance right has no body box_9"), "{}", error);
+    }
+
+    #[test]
+    fn test_instance_metrics_match_standalone_run() {
+        let ball = || BouncingBallScenario::default();
+
+        let mut alone = MetricWorld::new(&PhysicsConfig::default());
+        ball().setup(&mut alone).unwrap();
+        alone.run(120);
+        let expected = ball().compute_metrics(alone.frames());
+
+        let mut world = MetricWorld::new(&PhysicsConfig::default());
+        let scenario = CompositeScenario::new(
+            vec![ScenarioInstance { name: "ball".to_string(), offset: Vec3::new(20.0, 0.0, 0.0), ground: InstanceGround::Own, scenario: Box::new(ball()) }],
+            Vec::new(),
+            Vec::new(),
+        );
+        scenario.setup(&mut world).unwrap();
+        world.run(120);
+        let metrics = scenario.compute_metrics(world.frames());
+
+        assert!(!expected.is_empty());
+        for (key, value) in expected {
+            let composite = metrics[&format!("ball/{}", key)];
+            assert!((composite - value).abs() < 1e-3 * value.abs().max(1.0), "{}: {} vs {}", key, composite, value);
+        }
+    }
+}
@@ -0,0 +1,211 @@
+//! Domino chain scenario
+
+use crate::{MetricWorld, BodyBuilder, Scenario};
+use rapier3d::prelude::*;
+use simuforge_core::{MetricFrame, Vec3};
+use std::collections::{BTreeMap, HashMap};
+use super::{get_f32, get_u32, get_vec3};
+
+/// Scenario: Row of thin boxes standing on end, the first one pushed over
+pub struct DominoChainScenario {
+    pub count: u32,
+    /// Thickness, height and width of each domino
+    pub domino_size: [f32; 3],
+    /// Distance between neighbouring domino centers along the path
+    pub spacing: f32,
+    /// Radius of the arc the chain is laid along; zero for a straight line
+    pub curve_radius: f32,
+    /// Initial tipping rate of the first domino (rad/s)
+    pub push_angular_velocity: f32,
+    /// Tilt from vertical at which a domino counts as tipped (rad)
+    pub tip_angle: f32,
+    pub friction: f32,
+    pub restitution: f32,
+}
+
+impl Default for DominoChainScenario {
+    fn default() -> Self {
+        Self {
+            count: 20,
+            domino_size: [0.1, 1.0, 0.5],
+            spacing: 0.5,
+            curve_radius: 0.0,
+            push_angular_velocity: 2.0,
+            tip_angle: std::f32::consts::FRAC_PI_4,
+            friction: 0.5,
+            restitution: 0.1,
+        }
+    }
+}
+
+impl DominoChainScenario {
+    pub fn new(count: u32) -> Self {
+        Self {
+            count,
+            ..Default::default()
+        }
+    }
+
+    pub fn from_params(params: &HashMap<String, serde_yaml::Value>) -> Self {
+        let defaults = Self::default();
+        Self {
+            count: get_u32(params, "count", defaults.count),
+            domino_size: get_vec3(params, "domino_size", defaults.domino_size),
+            spacing: get_f32(params, "spacing", defaults.spacing),
+            curve_radius: get_f32(params, "curve_radius", defaults.curve_radius),
+            push_angular_velocity: get_f32(params, "push_angular_velocity", defaults.push_angular_velocity),
+            tip_angle: get_f32(params, "tip_angle", defaults.tip_angle),
+            friction: get_f32(params, "friction", defaults.friction),
+            restitution: get_f32(params, "restitution", defaults.restitution),
+        }
+    }
+
+    fn domino_name(index: u32) -> String {
+        format!("domino_{}", index)
+    }
+
+    /// Ground position and heading angle (about +Y) of a domino along the path
+    fn placement(&self, index: u32) -> (f32, f32, f32) {
+        let distance = index as f32 * self.spacing;
+        if self.curve_radius <= 0.0 {
+            return (distance, 0.0, 0.0);
+        }
+
+        // Arc starts along +X and bends towards +Z
+        let angle = distance / self.curve_radius;
+        (self.curve_radius * angle.sin(), self.curve_radius * (1.0 - angle.cos()), angle)
+    }
+}
+
+impl Scenario for DominoChainScenario {
+    fn name(&self) -> &str {
+        "domino_chain"
+    }
+
+    fn description(&self) -> &str {
+        "Chain of dominoes toppling in sequence, tests friction, restitution and rotational dynamics"
+    }
+
+    fn setup(&self, world: &mut MetricWorld) -> Result<(), String> {
+        let [thickness, height, width] = self.domino_size;
+
+        // Add ground plane
+        let (ground_body, ground_collider, ground_name) = BodyBuilder::new("ground")
+            .position_xyz(0.0, -0.5, 0.0)
+            .box_shape(100.0, 0.5, 100.0)
+            .fixed()
+            .friction(self.friction)
+            .restitution(self.restitution)
+            .build();
+
+        let ground_handle = world.add_body(ground_body, ground_name);
+        world.add_collider(ground_collider, ground_handle);
+
+        for i in 0..self.count {
+            let (x, z, heading) = self.placement(i);
+
+            // A rotation of -heading about +Y turns the local +X face towards the direction of travel
+            let mut builder = BodyBuilder::new(Self::domino_name(i))
+                .position_xyz(x, height / 2.0, z)
+                .rotation(Vec3::new(0.0, 1.0, 0.0), -heading)
+                .box_shape(thickness / 2.0, height / 2.0, width / 2.0)
+                .dynamic()
+                .friction(self.friction)
+                .restitution(self.restitution);
+
+            if i == 0 {
+                // Rotate about the leading bottom edge so the push tips rather than slides the domino
+                let direction = vector![heading.cos(), 0.0, heading.sin()];
+                let axis = Vector::y().cross(&direction);
+                let omega = axis * self.push_angular_velocity;
+                let lever = -direction * (thickness / 2.0) + Vector::y() * (height / 2.0);
+                builder = builder
+                    .angular_velocity(Vec3::from_nalgebra(&omega))
+                    .velocity(Vec3::from_nalgebra(&omega.cross(&lever)));
+            }
+
+            let (body, collider, name) = builder.build();
+            let handle = world.add_body(body, name);
+            world.add_collider(collider, handle);
+        }
+        Ok(())
+    }
+
+    fn compute_metrics(&self, frames: &[MetricFrame]) -> BTreeMap<String, f64> {
+        let mut metrics = BTreeMap::new();
+        if frames.last().is_none_or(|f| f.bodies.is_empty()) {
+            return metrics;
+        }
+
+        // First time each domino's long axis leans past the tip angle
+        let min_up = self.tip_angle.cos();
+        let tip_times: Vec<Option<f32>> = (0..self.count)
+            .map(|i| {
+                let name = Self::domino_name(i);
+                frames.iter().find_map(|f| {
+                    let body = f.bodies.iter().find(|b| b.name == name)?;
+                    let up = body.transform.rotation.to_nalgebra() * Vector::y();
+                    (up.y < min_up).then_some(f.time)
+                })
+            })
+            .collect();
+
+        for (i, time) in tip_times.iter().enumerate() {
+            if let Some(time) = time {
+                metrics.insert(format!("tip_time.{}", Self::domino_name(i as u32)), *time as f64);
+            }
+        }
+
+        // The chain stops at the first domino that never tips
+        let tipped = tip_times.iter().take_while(|t| t.is_some()).count();
+        metrics.insert("dominoes_tipped".to_string(), tipped as f64);
+        metrics.insert("chain_completed".to_string(), if tipped == self.count as usize { 1.0 } else { 0.0 });
+
+        if tipped > 0 {
+            let first = tip_times[0].unwrap_or(0.0);
+            let last = tip_times[tipped - 1].unwrap_or(0.0);
+            metrics.insert("last_tip_time".to_string(), last as f64);
+
+            let distance = (tipped - 1) as f32 * self.spacing;
+            if tipped > 1 && last > first {
+                metrics.insert("propagation_speed".to_string(), (distance / (last - first)) as f64);
+            }
+        }
+
+        metrics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use simuforge_core::PhysicsConfig;
+
+    #[test]
+    fn test_straight_chain_completes() {
+        let mut world = MetricWorld::new(&PhysicsConfig::default());
+        let scenario = DominoChainScenario::new(8);
+        scenario.setup(&mut world).unwrap();
+        world.run(300);
+
+        let metrics = scenario.compute_metrics(world.frames());
+        assert_eq!(metrics["chain_completed"], 1.0, "{:?}", metrics);
+        assert!(metrics["propagation_speed"] > 0.5, "{:?}", metrics);
+        assert!(metrics["tip_time.domino_7"] > metrics["tip_time.domino_0"]);
+    }
+
+    #[test]
+    fn test_curved_placement() {
+        let scenario = DominoChainScenario {
+            curve_radius: 2.0,
+            spacing: std::f32::consts::PI,
+            ..DominoChainScenario::default()
+        };
+
+        // A quarter of the way around a circle of radius 2
+        let (x, z, heading) = scenario.placement(1);
+        assert!((x - 2.0).abs() < 1e-5);
+        assert!((z - 2.0).abs() < 1e-5);
+        assert!((heading - std::f32::consts::PI / 2.0).abs() < 1e-5);
+    }
+}
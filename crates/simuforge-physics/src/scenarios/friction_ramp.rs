@@ -1,9 +1,12 @@
 //! Friction ramp scenario
 
 use crate::{MetricWorld, BodyBuilder, Scenario};
-use simuforge_core::Vec3;
-use std::collections::HashMap;
-use super::get_f32;
+use simuforge_core::{MetricFrame, Vec3};
+use std::collections::{BTreeMap, HashMap};
+use super::{get_f32, get_str, vertical_gravity};
+
+/// Time allowed for the slider to settle onto the ramp before measuring (s)
+const SETTLE_TIME: f32 = 0.1;
 
 /// Scenario: Object sliding down an inclined ramp
 pub struct FrictionRampScenario {
@@ -12,6 +15,8 @@ pub struct FrictionRampScenario {
     pub box_size: f32,
     pub friction: f32,
     pub restitution: f32,
+    /// Start the slider resting on the ramp surface instead of dropping it near the bottom
+    pub start_on_ramp: bool,
 }
 
 impl Default for FrictionRampScenario {
@@ -22,6 +27,7 @@ impl Default for FrictionRampScenario {
             box_size: 1.0,
             friction: 0.3,
             restitution: 0.1,
+            start_on_ramp: false,
         }
     }
 }
@@ -34,8 +40,20 @@ impl FrictionRampScenario {
             box_size: get_f32(params, "box_size", 1.0),
             friction: get_f32(params, "friction", 0.3),
             restitution: get_f32(params, "restitution", 0.1),
+            start_on_ramp: get_str(params, "start", "drop") == "on_ramp",
         }
     }
+
+    /// Unit vector pointing down the ramp surface
+    fn downhill(&self) -> Vec3 {
+        Vec3::new(self.ramp_angle.cos(), -self.ramp_angle.sin(), 0.0)
+    }
+
+    /// Sliding acceleration `g (sin theta - mu cos theta)`, or zero when static friction holds
+    pub fn expected_acceleration(&self, gravity: f32) -> f32 {
+        let (sin, cos) = self.ramp_angle.sin_cos();
+        (gravity * (sin - self.friction * cos)).max(0.0)
+    }
 }
 
 impl Scenario for FrictionRampScenario {
@@ -47,7 +65,7 @@ impl Scenario for FrictionRampScenario {
         "Object sliding down a ramp, tests friction coefficient accuracy"
     }
 
-    fn setup(&self, world: &mut MetricWorld) {
+    fn setup(&self, world: &mut MetricWorld) -> Result<(), String> {
         let ramp_height = (self.ramp_angle.sin() * self.ramp_length) / 2.0;
         let ramp_offset = (self.ramp_angle.cos() * self.ramp_length) / 2.0;
 
@@ -76,13 +94,27 @@ impl Scenario for FrictionRampScenario {
         let floor_handle = world.add_body(floor_body, floor_name);
         world.add_collider(floor_collider, floor_handle);
 
-        // Add sliding box at top of ramp
+        // Add sliding box at top of ramp, or resting on the upper end of the ramp aligned with its surface
         let box_half = self.box_size / 2.0;
-        let start_x = self.ramp_angle.cos() * (self.ramp_length * 0.9);
-        let start_y = self.ramp_angle.sin() * (self.ramp_length * 0.9) + box_half + 0.6;
+        let (start_x, start_y, tilt) = if self.start_on_ramp {
+            let along = self.ramp_length * 0.1 + box_half;
+            let clearance = 0.5 + box_half + 0.01;
+            (
+                along * self.ramp_angle.cos() + clearance * self.ramp_angle.sin(),
+                (self.ramp_length - along) * self.ramp_angle.sin() + clearance * self.ramp_angle.cos(),
+                -self.ramp_angle,
+            )
+        } else {
+            (
+                self.ramp_angle.cos() * (self.ramp_length * 0.9),
+                self.ramp_angle.sin() * (self.ramp_length * 0.9) + box_half + 0.6,
+                0.0,
+            )
+        };
 
         let (box_body, box_collider, box_name) = BodyBuilder::new("slider")
             .position_xyz(start_x, start_y, 0.0)
+            .rotation(Vec3::new(0.0, 0.0, 1.0), tilt)
             .box_shape(box_half, box_half, box_half)
             .dynamic()
             .friction(self.friction)
@@ -91,6 +123,41 @@ impl Scenario for FrictionRampScenario {
 
         let box_handle = world.add_body(box_body, box_name);
         world.add_collider(box_collider, box_handle);
+        Ok(())
+    }
+
+    fn compute_metrics(&self, frames: &[MetricFrame]) -> BTreeMap<String, f64> {
+        let mut metrics = BTreeMap::new();
+        let Some(gravity) = vertical_gravity(frames) else {
+            return metrics;
+        };
+
+        // Speed along the ramp while the slider is still fully on it
+        let downhill = self.downhill();
+        let bottom_x = self.ramp_angle.cos() * self.ramp_length;
+        let samples: Vec<(f32, f32)> = frames
+            .iter()
+            .filter(|f| f.time >= SETTLE_TIME)
+            .map_while(|f| {
+                let slider = f.bodies.iter().find(|b| b.name == "slider")?;
+                let on_ramp = slider.transform.position.x + self.box_size < bottom_x;
+                on_ramp.then(|| (f.time, slider.velocity.dot(&downhill)))
+            })
+            .collect();
+
+        let (Some(&(t0, v0)), Some(&(t1, v1))) = (samples.first(), samples.last()) else {
+            return metrics;
+        };
+        if t1 - t0 < SETTLE_TIME {
+            return metrics;
+        }
+
+        let measured = (v1 - v0) / (t1 - t0);
+        let expected = self.expected_acceleration(gravity);
+        metrics.insert("ramp_acceleration".to_string(), measured as f64);
+        metrics.insert("expected_ramp_acceleration".to_string(), expected as f64);
+        metrics.insert("ramp_acceleration_error".to_string(), (measured - expected).abs() as f64);
+        metrics
     }
 }
 
@@ -105,8 +172,22 @@ mod tests {
         let mut world = MetricWorld::new(&config);
 
         let scenario = FrictionRampScenario::default();
-        scenario.setup(&mut world);
+        scenario.setup(&mut world).unwrap();
 
         assert_eq!(world.body_count(), 3);
     }
+
+    #[test]
+    fn test_sliding_acceleration_matches_reference() {
+        let mut world = MetricWorld::new(&PhysicsConfig::default());
+        let scenario = FrictionRampScenario {
+            start_on_ramp: true,
+            ..Default::default()
+        };
+        scenario.setup(&mut world).unwrap();
+        world.run(180);
+
+        let metrics = scenario.compute_metrics(world.frames());
+        assert!(metrics["ramp_acceleration_error"] < 0.1, "{:?}", metrics);
+    }
 }
@@ -0,0 +1,341 @@
+//! Granular pile scenario
+
+use crate::{MetricWorld, BodyBuilder, Scenario};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use simuforge_core::{MetricFrame, Vec3};
+use std::collections::{BTreeMap, HashMap};
+use std::f32::consts::FRAC_PI_4;
+use super::{get_f32, get_str, get_u32, get_u64};
+
+/// Grain speed below which the pile counts as settled (m/s)
+const SETTLED_SPEED: f32 = 0.1;
+
+/// Fraction of grains used for pile statistics; the rest are treated as stragglers
+const BULK_FRACTION: f32 = 0.95;
+
+/// Value below which `BULK_FRACTION` of the samples fall
+fn bulk_percentile(mut values: Vec<f32>) -> Option<f32> {
+    values.sort_by(f32::total_cmp);
+    let index = ((values.len() as f32 * BULK_FRACTION) as usize).min(values.len().checked_sub(1)?);
+    Some(values[index])
+}
+
+/// Grain shapes to drop
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GrainShape {
+    Sphere,
+    /// Spheres, cubes and capsules chosen at random
+    Mixed,
+}
+
+/// Vessel the grains are poured into
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Container {
+    /// Funnel above open ground, so the grains form a free-standing cone
+    Hopper,
+    /// Closed bin with vertical walls
+    Bin,
+}
+
+/// Scenario: Hundreds to thousands of grains poured through a hopper or into a bin
+pub struct GranularPileScenario {
+    pub count: u32,
+    pub grain_radius: f32,
+    pub shape: GrainShape,
+    pub container: Container,
+    /// Half width of the hopper mouth or bin
+    pub container_half_width: f32,
+    /// Half width of the hopper outlet
+    pub outlet_half_width: f32,
+    /// Height of the hopper outlet above the ground
+    pub hopper_height: f32,
+    pub friction: f32,
+    pub restitution: f32,
+    /// Angular damping on grains, standing in for rolling resistance so spheres come to rest
+    pub rolling_damping: f32,
+    /// Overrides the physics seed for grain placement
+    pub seed: Option<u64>,
+}
+
+impl Default for GranularPileScenario {
+    fn default() -> Self {
+        Self {
+            count: 500,
+            grain_radius: 0.1,
+            shape: GrainShape::Sphere,
+            container: Container::Hopper,
+            container_half_width: 1.5,
+            outlet_half_width: 0.45,
+            hopper_height: 1.0,
+            friction: 0.9,
+            restitution: 0.1,
+            rolling_damping: 10.0,
+            seed: None,
+        }
+    }
+}
+
+impl GranularPileScenario {
+    pub fn new(count: u32) -> Self {
+        Self {
+            count,
+            ..Default::default()
+        }
+    }
+
+    pub fn from_params(params: &HashMap<String, serde_yaml::Value>) -> Self {
+        let defaults = Self::default();
+        Self {
+            count: get_u32(params, "count", defaults.count),
+            grain_radius: get_f32(params, "grain_radius", defaults.grain_radius),
+            shape: match get_str(params, "shape", "sphere") {
+                "mixed" => GrainShape::Mixed,
+                _ => GrainShape::Sphere,
+            },
+            container: match get_str(params, "container", "hopper") {
+                "bin" | "box" => Container::Bin,
+                _ => Container::Hopper,
+            },
+            container_half_width: get_f32(params, "container_half_width", defaults.container_half_width),
+            outlet_half_width: get_f32(params, "outlet_half_width", defaults.outlet_half_width),
+            hopper_height: get_f32(params, "hopper_height", defaults.hopper_height),
+            friction: get_f32(params, "friction", defaults.friction),
+            restitution: get_f32(params, "restitution", defaults.restitution),
+            rolling_damping: get_f32(params, "rolling_damping", defaults.rolling_damping),
+            seed: get_u64(params, "seed"),
+        }
+    }
+
+    fn grain_name(index: u32) -> String {
+        format!("grain_{}", index)
+    }
+
+    /// Lowest height grains are spawned at
+    fn spawn_floor(&self) -> f32 {
+        match self.container {
+            Container::Hopper => self.hopper_height + (self.container_half_width - self.outlet_half_width) + self.grain_radius,
+            Container::Bin => self.grain_radius * 2.0,
+        }
+    }
+
+    fn add_fixed_box(&self, world: &mut MetricWorld, name: &str, position: Vec3, half: Vec3, rotation: Option<(Vec3, f32)>) {
+        let mut builder = BodyBuilder::new(name)
+            .position(position)
+            .box_shape(half.x, half.y, half.z)
+            .fixed()
+            .friction(self.friction)
+            .restitution(self.restitution);
+        if let Some((axis, angle)) = rotation {
+            builder = builder.rotation(axis, angle);
+        }
+        let (body, collider, name) = builder.build();
+        let handle = world.add_body(body, name);
+        world.add_collider(collider, handle);
+    }
+
+    /// Four 45 degree walls narrowing from the mouth down to the outlet
+    fn add_hopper(&self, world: &mut MetricWorld) {
+        let thickness = 0.05;
+        let run = self.container_half_width - self.outlet_half_width;
+        let half_length = run * std::f32::consts::SQRT_2 / 2.0;
+        let mid = self.outlet_half_width + run / 2.0;
+        let y = self.hopper_height + run / 2.0;
+        let z_axis = Vec3::new(0.0, 0.0, 1.0);
+        let x_axis = Vec3::new(1.0, 0.0, 0.0);
+
+        // Walls span the full mouth sideways, so neighbouring walls close the corners
+        let across = self.container_half_width;
+        self.add_fixed_box(world, "hopper_px", Vec3::new(mid, y, 0.0), Vec3::new(half_length, thickness, across), Some((z_axis, FRAC_PI_4)));
+        self.add_fixed_box(world, "hopper_nx", Vec3::new(-mid, y, 0.0), Vec3::new(half_length, thickness, across), Some((z_axis, -FRAC_PI_4)));
+        self.add_fixed_box(world, "hopper_pz", Vec3::new(0.0, y, mid), Vec3::new(across, thickness, half_length), Some((x_axis, -FRAC_PI_4)));
+        self.add_fixed_box(world, "hopper_nz", Vec3::new(0.0, y, -mid), Vec3::new(across, thickness, half_length), Some((x_axis, FRAC_PI_4)));
+    }
+
+    /// Vertical walls tall enough to hold every grain
+    fn add_bin(&self, world: &mut MetricWorld) {
+        let thickness = 0.05;
+        let w = self.container_half_width;
+        let height = self.spawn_floor() + self.spawn_layers() as f32 * self.grain_radius * 2.2;
+        let half_y = height / 2.0;
+        self.add_fixed_box(world, "bin_px", Vec3::new(w + thickness, half_y, 0.0), Vec3::new(thickness, half_y, w), None);
+        self.add_fixed_box(world, "bin_nx", Vec3::new(-w - thickness, half_y, 0.0), Vec3::new(thickness, half_y, w), None);
+        self.add_fixed_box(world, "bin_pz", Vec3::new(0.0, half_y, w + thickness), Vec3::new(w, half_y, thickness), None);
+        self.add_fixed_box(world, "bin_nz", Vec3::new(0.0, half_y, -w - thickness), Vec3::new(w, half_y, thickness), None);
+    }
+
+    /// Grains per side of one spawn layer
+    fn spawn_columns(&self) -> u32 {
+        let pitch = self.grain_radius * 2.2;
+        (((self.container_half_width - self.grain_radius) * 2.0 / pitch) as u32).max(1)
+    }
+
+    fn spawn_layers(&self) -> u32 {
+        let per_layer = self.spawn_columns().pow(2);
+        self.count.div_ceil(per_layer)
+    }
+
+    fn add_grains(&self, world: &mut MetricWorld) {
+        let mut rng = ChaCha8Rng::seed_from_u64(self.seed.or(world.seed()).unwrap_or(0));
+        let r = self.grain_radius;
+        let pitch = r * 2.2;
+        let columns = self.spawn_columns();
+        let origin = -((columns - 1) as f32) * pitch / 2.0;
+        let jitter = (pitch - 2.0 * r) / 2.0;
+
+        for i in 0..self.count {
+            let layer = i / (columns * columns);
+            let cell = i % (columns * columns);
+            let x = origin + (cell % columns) as f32 * pitch + rng.gen_range(-jitter..=jitter);
+            let z = origin + (cell / columns) as f32 * pitch + rng.gen_range(-jitter..=jitter);
+            let y = self.spawn_floor() + layer as f32 * pitch;
+
+            let builder = BodyBuilder::new(Self::grain_name(i))
+                .position_xyz(x, y, z)
+                .dynamic()
+                .friction(self.friction)
+                .restitution(self.restitution);
+            let builder = match (self.shape, rng.gen_range(0..3)) {
+                (GrainShape::Mixed, 1) => builder
+                    .box_shape(r * 0.8, r * 0.8, r * 0.8)
+                    .rotation(Vec3::new(rng.gen(), rng.gen(), rng.gen()), rng.gen_range(0.0..std::f32::consts::TAU)),
+                (GrainShape::Mixed, 2) => builder
+                    .capsule(r * 0.5, r * 0.5)
+                    .rotation(Vec3::new(rng.gen(), rng.gen(), rng.gen()), rng.gen_range(0.0..std::f32::consts::TAU)),
+                _ => builder.sphere(r),
+            };
+
+            let (mut body, collider, name) = builder.build();
+            body.set_angular_damping(self.rolling_damping);
+            let handle = world.add_body(body, name);
+            world.add_collider(collider, handle);
+        }
+    }
+}
+
+impl Scenario for GranularPileScenario {
+    fn name(&self) -> &str {
+        "granular_pile"
+    }
+
+    fn description(&self) -> &str {
+        "Grains poured through a hopper or into a bin, tests large body counts and settling"
+    }
+
+    fn setup(&self, world: &mut MetricWorld) -> Result<(), String> {
+        // Add ground plane
+        let (ground_body, ground_collider, ground_name) = BodyBuilder::new("ground")
+            .position_xyz(0.0, -0.5, 0.0)
+            .box_shape(50.0, 0.5, 50.0)
+            .fixed()
+            .friction(self.friction)
+            .restitution(self.restitution)
+            .build();
+
+        let ground_handle = world.add_body(ground_body, ground_name);
+        world.add_collider(ground_collider, ground_handle);
+
+        match self.container {
+            Container::Hopper => self.add_hopper(world),
+            Container::Bin => self.add_bin(world),
+        }
+        self.add_grains(world);
+        Ok(())
+    }
+
+    fn compute_metrics(&self, frames: &[MetricFrame]) -> BTreeMap<String, f64> {
+        let mut metrics = BTreeMap::new();
+        let Some(last) = frames.last() else {
+            return metrics;
+        };
+
+        let is_grain = |name: &str| name.starts_with("grain_");
+        let bulk_speed = |frame: &MetricFrame| {
+            let speeds = frame.bodies
+                .iter()
+                .filter(|b| is_grain(&b.name))
+                .map(|b| b.velocity.magnitude())
+                .collect();
+            bulk_percentile(speeds).unwrap_or(0.0)
+        };
+
+        // Settled from the frame after the last one where the bulk was still moving,
+        // if the run ends settled
+        if bulk_speed(last) < SETTLED_SPEED {
+            let settled_from = frames
+                .iter()
+                .rposition(|f| bulk_speed(f) >= SETTLED_SPEED)
+                .map(|i| i + 1)
+                .unwrap_or(0);
+            metrics.insert("settling_time".to_string(), frames[settled_from].time as f64);
+            metrics.insert("settled".to_string(), 1.0);
+        } else {
+            metrics.insert("settled".to_string(), 0.0);
+        }
+
+        let grains: Vec<Vec3> = last.bodies
+            .iter()
+            .filter(|b| is_grain(&b.name))
+            .map(|b| b.transform.position)
+            .collect();
+        if grains.is_empty() {
+            return metrics;
+        }
+
+        let height = grains.iter().map(|p| p.y + self.grain_radius).fold(0.0f32, f32::max);
+        metrics.insert("pile_height".to_string(), height as f64);
+
+        // Free-standing cone under the hopper: slope from the peak down to the
+        // outermost grains still touching the ground, ignoring stragglers
+        if self.container == Container::Hopper {
+            let ground_radii = grains
+                .iter()
+                .filter(|p| p.y < self.grain_radius * 2.0)
+                .map(|p| (p.x * p.x + p.z * p.z).sqrt())
+                .collect();
+
+            if let Some(base) = bulk_percentile(ground_radii) {
+                if base > 0.0 {
+                    let angle = ((height - self.grain_radius) / base).atan().to_degrees();
+                    metrics.insert("angle_of_repose".to_string(), angle as f64);
+                    metrics.insert("pile_base_radius".to_string(), base as f64);
+                }
+            }
+        }
+
+        metrics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use simuforge_core::PhysicsConfig;
+
+    #[test]
+    fn test_seeded_placement_is_reproducible() {
+        let positions = |seed| {
+            let mut world = MetricWorld::new(&PhysicsConfig { seed: Some(seed), ..Default::default() });
+            let scenario = GranularPileScenario { shape: GrainShape::Mixed, ..GranularPileScenario::new(40) };
+            scenario.setup(&mut world).unwrap();
+            world.get_body_by_name("grain_17").map(|b| *b.translation()).unwrap()
+        };
+
+        assert_eq!(positions(7), positions(7));
+        assert_ne!(positions(7), positions(8));
+    }
+
+    #[test]
+    fn test_hopper_pours_a_pile() {
+        let mut world = MetricWorld::new(&PhysicsConfig::default());
+        let scenario = GranularPileScenario::new(60);
+        scenario.setup(&mut world).unwrap();
+        // Ground, four hopper walls and the grains
+        assert_eq!(world.body_count(), 65);
+
+        world.run(240);
+        let metrics = scenario.compute_metrics(world.frames());
+        assert!(metrics["pile_height"] < scenario.hopper_height as f64, "{:?}", metrics);
+        assert!(metrics.contains_key("angle_of_repose"), "{:?}", metrics);
+    }
+}
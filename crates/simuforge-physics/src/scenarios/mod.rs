@@ -4,21 +4,46 @@ mod box_stack;
 mod rolling;
 mod bouncing;
 mod friction_ramp;
+mod newtons_cradle;
+mod pyramid;
+mod brick_wall;
+mod domino_chain;
+mod pendulum;
+mod granular;
+mod articulated_chain;
+mod vehicle;
+mod composite;
 
-use crate::{MetricWorld, BodyBuilder};
-use simuforge_core::{PhysicsConfig, spec::ScenarioConfig};
-use std::collections::HashMap;
+use crate::MetricWorld;
+use simuforge_core::{MetricFrame, spec::ScenarioConfig};
+use simuforge_core::generators::{expand_bodies, BodyEntry};
+use std::collections::{BTreeMap, HashMap};
 
 pub use box_stack::BoxStackScenario;
 pub use rolling::RollingSphereScenario;
 pub use bouncing::BouncingBallScenario;
 pub use friction_ramp::FrictionRampScenario;
+pub use newtons_cradle::NewtonsCradleScenario;
+pub use pyramid::BoxPyramidScenario;
+pub use brick_wall::BrickWallScenario;
+pub use domino_chain::DominoChainScenario;
+pub use pendulum::{DoublePendulumScenario, SimplePendulumScenario};
+pub use granular::{Container, GrainShape, GranularPileScenario};
+pub use articulated_chain::{ArticulatedChainScenario, JointFormulation};
+pub use vehicle::{Terrain, VehicleScenario};
+pub use composite::{CompositeScenario, ScenarioInstance};
+use composite::UnresolvedInclude;
 
 /// Trait for scenario implementations
 pub trait Scenario {
     fn name(&self) -> &str;
     fn description(&self) -> &str;
-    fn setup(&self, world: &mut MetricWorld);
+    fn setup(&self, world: &mut MetricWorld) -> Result<(), String>;
+
+    /// Scenario-specific metrics computed from the recorded frames
+    fn compute_metrics(&self, _frames: &[MetricFrame]) -> BTreeMap<String, f64> {
+        BTreeMap::new()
+    }
 }
 
 /// Create a scenario from configuration
@@ -30,18 +55,44 @@ pub fn create_scenario(config: &ScenarioConfig) -> Box<dyn Scenario> {
                 "rolling_sphere" | "rolling" => Box::new(RollingSphereScenario::from_params(params)),
                 "bouncing_ball" | "bouncing" => Box::new(BouncingBallScenario::from_params(params)),
                 "friction_ramp" | "ramp" => Box::new(FrictionRampScenario::from_params(params)),
+                "newtons_cradle" | "cradle" => Box::new(NewtonsCradleScenario::from_params(params)),
+                "box_pyramid" | "pyramid" => Box::new(BoxPyramidScenario::from_params(params)),
+                "brick_wall" | "wall" => Box::new(BrickWallScenario::from_params(params)),
+                "domino_chain" | "dominoes" => Box::new(DominoChainScenario::from_params(params)),
+                "simple_pendulum" | "pendulum" => Box::new(SimplePendulumScenario::from_params(params)),
+                "double_pendulum" => Box::new(DoublePendulumScenario::from_params(params)),
+                "granular_pile" | "hopper" => Box::new(GranularPileScenario::from_params(params)),
+                "articulated_chain" => Box::new(ArticulatedChainScenario::from_params(params)),
+                "vehicle" => Box::new(VehicleScenario::from_params(params)),
                 _ => panic!("Unknown scenario: {}", name),
             }
         }
-        ScenarioConfig::Custom { bodies } => {
-            Box::new(CustomScenario { bodies: bodies.clone() })
+        ScenarioConfig::Custom { bodies, joints } => {
+            Box::new(CustomScenario { bodies: bodies.clone(), joints: joints.clone() })
+        }
+        ScenarioConfig::Composite { instances, bodies, joints } => {
+            // Includes are resolved when the experiment is loaded; an unresolved one fails setup
+            let instances = instances
+                .iter()
+                .map(|instance| ScenarioInstance {
+                    name: instance.name.clone(),
+                    offset: instance.offset,
+                    ground: instance.ground,
+                    scenario: match (instance.scenario.as_deref(), &instance.include) {
+                        (Some(scenario), _) => create_scenario(scenario),
+                        (None, include) => Box::new(UnresolvedInclude(include.clone().unwrap_or_default())),
+                    },
+                })
+                .collect();
+            Box::new(CompositeScenario::new(instances, bodies.clone(), joints.clone()))
         }
     }
 }
 
 /// Custom scenario from body configurations
 struct CustomScenario {
-    bodies: Vec<simuforge_core::spec::BodyConfig>,
+    bodies: Vec<BodyEntry>,
+    joints: Vec<simuforge_core::spec::JointConfig>,
 }
 
 impl Scenario for CustomScenario {
@@ -53,12 +104,14 @@ impl Scenario for CustomScenario {
         "Custom user-defined scenario"
     }
 
-    fn setup(&self, world: &mut MetricWorld) {
-        for body_config in &self.bodies {
-            let (body, collider, name) = BodyBuilder::from_config(body_config).build();
-            let handle = world.add_body(body, name);
-            world.add_collider(collider, handle);
+    fn setup(&self, world: &mut MetricWorld) -> Result<(), String> {
+        for body_config in &expand_bodies(&self.bodies, world.seed()) {
+            world.add_body_from_config(body_config);
         }
+        for joint_config in &self.joints {
+            world.add_joint_from_config(joint_config)?;
+        }
+        Ok(())
     }
 }
 
@@ -82,6 +135,80 @@ pub(crate) fn get_u32(params: &HashMap<String, serde_yaml::Value>, key: &str, de
     default
 }
 
+/// Downward gravity magnitude a run used, read from its recorded frames.
+///
+/// Analytic predictions assume gravity along -Y that stays the same for the
+/// whole run, so runs with tilted or changing gravity get `None`.
+pub(crate) fn vertical_gravity(frames: &[MetricFrame]) -> Option<f32> {
+    let gravity = frames.first()?.gravity;
+    let vertical = gravity.x.abs() < 1e-6 && gravity.z.abs() < 1e-6 && gravity.y < 0.0;
+    (vertical && frames.iter().all(|f| f.gravity == gravity)).then_some(-gravity.y)
+}
+
+/// Displacement and collapse metrics for bodies whose names start with `prefix`.
+///
+/// A structure counts as collapsed once any of its bodies has moved further
+/// than `collapse_threshold` from where it started.
+pub(crate) fn displacement_metrics(
+    frames: &[MetricFrame],
+    prefix: &str,
+    collapse_threshold: f32,
+) -> BTreeMap<String, f64> {
+    let mut metrics = BTreeMap::new();
+    let Some(first) = frames.first() else {
+        return metrics;
+    };
+
+    let initial: HashMap<&str, simuforge_core::Vec3> = first.bodies
+        .iter()
+        .filter(|b| b.name.starts_with(prefix))
+        .map(|b| (b.name.as_str(), b.transform.position))
+        .collect();
+    if initial.is_empty() {
+        return metrics;
+    }
+
+    let displacements = |frame: &MetricFrame| -> Vec<f32> {
+        frame.bodies
+            .iter()
+            .filter_map(|b| initial.get(b.name.as_str()).map(|p0| (b.transform.position - *p0).magnitude()))
+            .collect()
+    };
+
+    let mut max_displacement = 0.0f32;
+    let mut collapse_time = None;
+    for frame in frames {
+        let frame_max = displacements(frame).into_iter().fold(0.0f32, f32::max);
+        max_displacement = max_displacement.max(frame_max);
+        if collapse_time.is_none() && frame_max > collapse_threshold {
+            collapse_time = Some(frame.time);
+        }
+    }
+
+    let displaced = frames
+        .last()
+        .map(|f| displacements(f).into_iter().filter(|d| *d > collapse_threshold).count())
+        .unwrap_or(0);
+
+    metrics.insert("max_box_displacement".to_string(), max_displacement as f64);
+    metrics.insert("displaced_boxes".to_string(), displaced as f64);
+    metrics.insert("collapsed".to_string(), if collapse_time.is_some() { 1.0 } else { 0.0 });
+    if let Some(time) = collapse_time {
+        metrics.insert("collapse_time".to_string(), time as f64);
+    }
+    metrics
+}
+
+/// Helper to extract string from YAML value
+pub(crate) fn get_str<'a>(params: &'a HashMap<String, serde_yaml::Value>, key: &str, default: &'a str) -> &'a str {
+    params.get(key).and_then(|v| v.as_str()).unwrap_or(default)
+}
+
+/// Helper to extract u64 from YAML value
+pub(crate) fn get_u64(params: &HashMap<String, serde_yaml::Value>, key: &str) -> Option<u64> {
+    params.get(key).and_then(|v| v.as_u64())
+}
+
 /// Helper to extract Vec3 from YAML value
 pub(crate) fn get_vec3(params: &HashMap<String, serde_yaml::Value>, key: &str, default: [f32; 3]) -> [f32; 3] {
     if let Some(value) = params.get(key) {
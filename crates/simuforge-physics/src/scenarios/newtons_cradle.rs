@@ -0,0 +1,250 @@
+//! Newton's cradle scenario
+
+use crate::{MetricWorld, BodyBuilder, Scenario};
+use rapier3d::prelude::*;
+use simuforge_core::{MetricFrame, Vec3, metrics::BodyState};
+use std::collections::{BTreeMap, HashMap};
+use std::f32::consts::{PI, TAU};
+use super::{get_f32, get_u32, vertical_gravity};
+
+/// Height of the resting ball centers above the origin
+const REST_HEIGHT: f32 = 1.0;
+
+/// Fraction of the release height a ball must rise to count as swinging out
+const SWING_OUT_FRACTION: f32 = 0.25;
+
+/// Scenario: Row of spheres hanging on pendulum joints, with the first balls raised and released
+pub struct NewtonsCradleScenario {
+    pub count: u32,
+    pub radius: f32,
+    /// Distance between neighbouring ball surfaces at rest
+    pub gap: f32,
+    pub string_length: f32,
+    pub restitution: f32,
+    /// Number of balls pulled back before release
+    pub raised: u32,
+    /// Release angle of the raised balls (rad)
+    pub raise_angle: f32,
+    pub density: f32,
+}
+
+impl Default for NewtonsCradleScenario {
+    fn default() -> Self {
+        Self {
+            count: 5,
+            radius: 0.25,
+            gap: 0.005,
+            string_length: 2.0,
+            restitution: 1.0,
+            raised: 1,
+            raise_angle: 0.5,
+            density: 1.0,
+        }
+    }
+}
+
+impl NewtonsCradleScenario {
+    pub fn from_params(params: &HashMap<String, serde_yaml::Value>) -> Self {
+        let count = get_u32(params, "count", 5).max(2);
+        Self {
+            count,
+            radius: get_f32(params, "radius", 0.25),
+            gap: get_f32(params, "gap", 0.005),
+            string_length: get_f32(params, "string_length", 2.0),
+            restitution: get_f32(params, "restitution", 1.0),
+            raised: get_u32(params, "raised", 1).clamp(1, count - 1),
+            raise_angle: get_f32(params, "raise_angle", 0.5),
+            density: get_f32(params, "density", 1.0),
+        }
+    }
+
+    fn ball_name(index: u32) -> String {
+        format!("ball_{}", index)
+    }
+
+    /// Rest position of a ball along the X axis, with the row centered on the origin
+    fn rest_x(&self, index: u32) -> f32 {
+        let spacing = 2.0 * self.radius + self.gap;
+        (index as f32 - (self.count - 1) as f32 / 2.0) * spacing
+    }
+
+    fn ball_mass(&self) -> f32 {
+        self.density * 4.0 / 3.0 * PI * self.radius.powi(3)
+    }
+
+    /// Mechanical energy of the balls, including their spin about their centers
+    fn ball_energy(&self, bodies: &[BodyState], gravity: f32) -> f32 {
+        let mass = self.ball_mass();
+        let inertia = 0.4 * mass * self.radius * self.radius;
+        bodies.iter()
+            .filter(|b| b.name.starts_with("ball_"))
+            .map(|b| {
+                0.5 * mass * b.velocity.magnitude_squared()
+                    + 0.5 * inertia * b.angular_velocity.magnitude_squared()
+                    + mass * gravity * b.transform.position.y
+            })
+            .sum()
+    }
+}
+
+impl Scenario for NewtonsCradleScenario {
+    fn name(&self) -> &str {
+        "newtons_cradle"
+    }
+
+    fn description(&self) -> &str {
+        "Row of pendulum spheres, tests sequential impulse propagation and restitution"
+    }
+
+    fn setup(&self, world: &mut MetricWorld) -> Result<(), String> {
+        // Fixed frame the pendulum joints hang from; it has no collider
+        let (frame_body, _, frame_name) = BodyBuilder::new("frame").fixed().build();
+        let frame_handle = world.add_body(frame_body, frame_name);
+
+        let pivot_y = REST_HEIGHT + self.string_length;
+
+        for i in 0..self.count {
+            let pivot_x = self.rest_x(i);
+            let angle = if i < self.raised { self.raise_angle } else { 0.0 };
+
+            // Raised balls swing back towards -X; rotating them keeps the joint anchor at the pivot
+            let (ball_body, ball_collider, ball_name) = BodyBuilder::new(Self::ball_name(i))
+                .position_xyz(
+                    pivot_x - self.string_length * angle.sin(),
+                    pivot_y - self.string_length * angle.cos(),
+                    0.0,
+                )
+                .rotation(Vec3::new(0.0, 0.0, 1.0), -angle)
+                .sphere(self.radius)
+                .dynamic()
+                .friction(0.0)
+                .restitution(self.restitution)
+                .density(self.density)
+                .build();
+
+            let ball_handle = world.add_body(ball_body, ball_name);
+            world.add_collider(ball_collider, ball_handle);
+
+            let joint = RevoluteJointBuilder::new(Vector::z_axis())
+                .local_anchor1(point![pivot_x, pivot_y, 0.0])
+                .local_anchor2(point![0.0, self.string_length, 0.0]);
+            world.add_joint(format!("string_{}", i), frame_handle, ball_handle, joint);
+        }
+        Ok(())
+    }
+
+    fn compute_metrics(&self, frames: &[MetricFrame]) -> BTreeMap<String, f64> {
+        let mut metrics = BTreeMap::new();
+        let Some(last) = frames.last() else {
+            return metrics;
+        };
+        if last.bodies.is_empty() {
+            return metrics;
+        }
+
+        let names: Vec<String> = (0..self.count).map(Self::ball_name).collect();
+        let far_name = &names[names.len() - 1];
+        let mass = self.ball_mass();
+
+        // Peak speed of the raised balls before the far ball starts moving is the incoming speed
+        let far_moves_at = frames
+            .iter()
+            .position(|f| f.bodies.iter().any(|b| &b.name == far_name && b.velocity.magnitude() > 1e-3))
+            .unwrap_or(frames.len());
+        let incoming_speed = frames[..far_moves_at]
+            .iter()
+            .flat_map(|f| f.bodies.iter())
+            .filter(|b| names[..self.raised as usize].contains(&b.name))
+            .map(|b| b.velocity.magnitude())
+            .fold(0.0f32, f32::max);
+
+        let far_speed = frames
+            .iter()
+            .flat_map(|f| f.bodies.iter())
+            .filter(|b| &b.name == far_name)
+            .map(|b| b.velocity.magnitude())
+            .fold(0.0f32, f32::max);
+
+        metrics.insert("far_ball_momentum".to_string(), (mass * far_speed) as f64);
+        let transfer = if incoming_speed > 1e-6 { far_speed / incoming_speed } else { 0.0 };
+        metrics.insert("momentum_transfer_ratio".to_string(), transfer as f64);
+
+        let first = &frames[0];
+        let Some(gravity) = vertical_gravity(frames) else {
+            return metrics;
+        };
+        let period = TAU * (self.string_length / gravity).sqrt();
+
+        // Balls that were at rest and swing out on the far side during the first cycle
+        let release_rise = self.string_length * (1.0 - self.raise_angle.cos());
+        let swung_out = (self.raised..self.count)
+            .filter(|&i| {
+                frames.iter().take_while(|f| f.time <= period).any(|f| {
+                    f.bodies.iter().any(|b| {
+                        b.name == names[i as usize]
+                            && b.transform.position.x > self.rest_x(i)
+                            && b.transform.position.y - REST_HEIGHT > SWING_OUT_FRACTION * release_rise
+                    })
+                })
+            })
+            .count();
+        metrics.insert("balls_swung_out".to_string(), swung_out as f64);
+
+        // Swing energy is the ball energy above that of all balls hanging at rest
+        let rest_energy = mass * gravity * REST_HEIGHT * self.count as f32;
+        let initial_swing = self.ball_energy(&first.bodies, gravity) - rest_energy;
+        let final_swing = self.ball_energy(&last.bodies, gravity) - rest_energy;
+        let cycles = last.time / period;
+        if cycles >= 1.0 && initial_swing > 0.0 {
+            let retained = (final_swing.max(0.0) / initial_swing).powf(1.0 / cycles);
+            metrics.insert("energy_retained_per_cycle".to_string(), retained as f64);
+        }
+
+        metrics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use simuforge_core::PhysicsConfig;
+
+    #[test]
+    fn test_cradle_setup() {
+        let mut world = MetricWorld::new(&PhysicsConfig::default());
+        let scenario = NewtonsCradleScenario::default();
+        scenario.setup(&mut world).unwrap();
+
+        // Frame plus five balls, each hanging on its own joint
+        assert_eq!(world.body_count(), 6);
+        assert_eq!(world.joint_names().count(), 5);
+    }
+
+    #[test]
+    fn test_ball_energy_uses_sphere_inertia() {
+        let scenario = NewtonsCradleScenario::default();
+        let mut world = MetricWorld::new(&PhysicsConfig::default());
+        scenario.setup(&mut world).unwrap();
+        let mut ball = world.current_frame().bodies.into_iter().find(|b| b.name == "ball_1").unwrap();
+        ball.velocity = Vec3::ZERO;
+        ball.angular_velocity = Vec3::new(0.0, 0.0, 2.0);
+
+        let mass = scenario.ball_mass();
+        let spin = 0.5 * 0.4 * mass * scenario.radius.powi(2) * 4.0;
+        let energy = scenario.ball_energy(&[ball], 9.81) - mass * 9.81 * REST_HEIGHT;
+        assert!((energy - spin).abs() < 1e-5, "{} vs {}", energy, spin);
+    }
+
+    #[test]
+    fn test_single_ball_transfers_momentum() {
+        let mut world = MetricWorld::new(&PhysicsConfig::default());
+        let scenario = NewtonsCradleScenario::default();
+        scenario.setup(&mut world).unwrap();
+        world.run(240);
+
+        let metrics = scenario.compute_metrics(world.frames());
+        assert!(metrics["momentum_transfer_ratio"] > 0.8, "{:?}", metrics);
+        assert_eq!(metrics["balls_swung_out"], 1.0, "{:?}", metrics);
+        assert!(metrics["energy_retained_per_cycle"] > 0.5, "{:?}", metrics);
+    }
+}
@@ -0,0 +1,370 @@
+//! Simple and double pendulum scenarios with analytic references
+
+use crate::{MetricWorld, BodyBuilder, Scenario};
+use rapier3d::prelude::*;
+use simuforge_core::{MetricFrame, Vec3, metrics::BodyState};
+use std::collections::{BTreeMap, HashMap};
+use std::f32::consts::{PI, TAU};
+use super::{get_f32, vertical_gravity};
+
+/// Height of the pivot above the origin
+const PIVOT_HEIGHT: f32 = 5.0;
+
+/// Scenario: Sphere bob hanging from a fixed pivot on a revolute joint
+pub struct SimplePendulumScenario {
+    pub length: f32,
+    pub bob_radius: f32,
+    /// Release angle from vertical (rad)
+    pub initial_angle: f32,
+    pub density: f32,
+}
+
+impl Default for SimplePendulumScenario {
+    fn default() -> Self {
+        Self {
+            length: 2.0,
+            bob_radius: 0.1,
+            initial_angle: 0.3,
+            density: 1.0,
+        }
+    }
+}
+
+impl SimplePendulumScenario {
+    pub fn from_params(params: &HashMap<String, serde_yaml::Value>) -> Self {
+        let defaults = Self::default();
+        Self {
+            length: get_f32(params, "length", defaults.length),
+            bob_radius: get_f32(params, "bob_radius", defaults.bob_radius),
+            initial_angle: get_f32(params, "initial_angle", defaults.initial_angle),
+            density: get_f32(params, "density", defaults.density),
+        }
+    }
+
+    /// Length of the ideal point-mass pendulum with the same period.
+    ///
+    /// The bob is a solid sphere rigidly attached to the joint, so it is a
+    /// physical pendulum with inertia `m * (L^2 + 2/5 r^2)` about the pivot.
+    pub fn equivalent_length(&self) -> f32 {
+        self.length + 0.4 * self.bob_radius * self.bob_radius / self.length
+    }
+
+    /// Small-angle period `2 pi sqrt(L / g)`
+    pub fn small_angle_period(&self, gravity: f32) -> f32 {
+        TAU * (self.equivalent_length() / gravity).sqrt()
+    }
+
+    /// Exact period `4 sqrt(L / g) K(sin(theta0 / 2))`
+    pub fn exact_period(&self, gravity: f32) -> f32 {
+        let k = (self.initial_angle / 2.0).sin();
+        4.0 * (self.equivalent_length() / gravity).sqrt() * complete_elliptic_k(k)
+    }
+}
+
+impl Scenario for SimplePendulumScenario {
+    fn name(&self) -> &str {
+        "simple_pendulum"
+    }
+
+    fn description(&self) -> &str {
+        "Pendulum on a revolute joint, compared against the analytic period"
+    }
+
+    fn setup(&self, world: &mut MetricWorld) -> Result<(), String> {
+        let pivot = add_pivot(world);
+        let bob = add_bob(world, "bob", pivot_point(), self.length, self.initial_angle, self.bob_radius, self.density);
+        world.add_joint("hinge", pivot, bob, hinge(pivot_point(), self.length));
+        Ok(())
+    }
+
+    fn compute_metrics(&self, frames: &[MetricFrame]) -> BTreeMap<String, f64> {
+        let mut metrics = BTreeMap::new();
+        let mass = sphere_mass(self.bob_radius, self.density);
+        let Some(gravity) = vertical_gravity(frames) else {
+            return metrics;
+        };
+
+        // Swing angle from the bob's offset below the pivot
+        let angles: Vec<(f32, f32)> = frames
+            .iter()
+            .filter_map(|f| {
+                let bob = f.bodies.iter().find(|b| b.name == "bob")?;
+                let offset = bob.transform.position - Vec3::from_nalgebra(&pivot_point().coords);
+                Some((f.time, offset.x.atan2(-offset.y)))
+            })
+            .collect();
+
+        if let Some(period) = measured_period(&angles) {
+            let small = self.small_angle_period(gravity);
+            let exact = self.exact_period(gravity);
+            metrics.insert("measured_period".to_string(), period as f64);
+            metrics.insert("small_angle_period".to_string(), small as f64);
+            metrics.insert("exact_period".to_string(), exact as f64);
+            metrics.insert("period_error_small_angle".to_string(), ((period - small).abs() / small) as f64);
+            metrics.insert("period_error_exact".to_string(), ((period - exact).abs() / exact) as f64);
+        }
+
+        let bobs = [Bob { name: "bob", mass, radius: self.bob_radius, depth: self.length }];
+        insert_energy_metrics(&mut metrics, frames, &bobs, gravity);
+        metrics
+    }
+}
+
+/// Scenario: Two sphere bobs chained on revolute joints
+pub struct DoublePendulumScenario {
+    pub length1: f32,
+    pub length2: f32,
+    pub bob_radius: f32,
+    /// Release angles of the upper and lower links from vertical (rad)
+    pub angle1: f32,
+    pub angle2: f32,
+    pub density: f32,
+}
+
+impl Default for DoublePendulumScenario {
+    fn default() -> Self {
+        Self {
+            length1: 1.0,
+            length2: 1.0,
+            bob_radius: 0.1,
+            angle1: 1.5,
+            angle2: 1.0,
+            density: 1.0,
+        }
+    }
+}
+
+impl DoublePendulumScenario {
+    pub fn from_params(params: &HashMap<String, serde_yaml::Value>) -> Self {
+        let defaults = Self::default();
+        Self {
+            length1: get_f32(params, "length1", defaults.length1),
+            length2: get_f32(params, "length2", defaults.length2),
+            bob_radius: get_f32(params, "bob_radius", defaults.bob_radius),
+            angle1: get_f32(params, "angle1", defaults.angle1),
+            angle2: get_f32(params, "angle2", defaults.angle2),
+            density: get_f32(params, "density", defaults.density),
+        }
+    }
+}
+
+impl Scenario for DoublePendulumScenario {
+    fn name(&self) -> &str {
+        "double_pendulum"
+    }
+
+    fn description(&self) -> &str {
+        "Chaotic double pendulum on revolute joints, tests long-run energy conservation"
+    }
+
+    fn setup(&self, world: &mut MetricWorld) -> Result<(), String> {
+        let pivot = add_pivot(world);
+        let upper = add_bob(world, "bob_1", pivot_point(), self.length1, self.angle1, self.bob_radius, self.density);
+        world.add_joint("hinge_1", pivot, upper, hinge(pivot_point(), self.length1));
+
+        // The lower link hangs from the upper bob's center
+        let elbow = pivot_point() + vector![self.length1 * self.angle1.sin(), -self.length1 * self.angle1.cos(), 0.0];
+        let lower = add_bob(world, "bob_2", elbow, self.length2, self.angle2, self.bob_radius, self.density);
+        world.add_joint("hinge_2", upper, lower, hinge(Point::origin(), self.length2));
+        Ok(())
+    }
+
+    fn compute_metrics(&self, frames: &[MetricFrame]) -> BTreeMap<String, f64> {
+        let mut metrics = BTreeMap::new();
+        let mass = sphere_mass(self.bob_radius, self.density);
+        let Some(gravity) = vertical_gravity(frames) else {
+            return metrics;
+        };
+
+        let bobs = [
+            Bob { name: "bob_1", mass, radius: self.bob_radius, depth: self.length1 },
+            Bob { name: "bob_2", mass, radius: self.bob_radius, depth: self.length1 + self.length2 },
+        ];
+        insert_energy_metrics(&mut metrics, frames, &bobs, gravity);
+        metrics
+    }
+}
+
+fn pivot_point() -> Point<f32> {
+    point![0.0, PIVOT_HEIGHT, 0.0]
+}
+
+/// Fixed pivot body without a collider
+fn add_pivot(world: &mut MetricWorld) -> RigidBodyHandle {
+    let (body, _, name) = BodyBuilder::new("pivot")
+        .position_xyz(0.0, 0.0, 0.0)
+        .fixed()
+        .build();
+    world.add_body(body, name)
+}
+
+/// Bob hanging `length` below `anchor`, swung out by `angle` about +Z
+fn add_bob(
+    world: &mut MetricWorld,
+    name: &str,
+    anchor: Point<f32>,
+    length: f32,
+    angle: f32,
+    radius: f32,
+    density: f32,
+) -> RigidBodyHandle {
+    // Rotating the bob with the link keeps its joint anchor at (0, length, 0) locally
+    let (body, collider, name) = BodyBuilder::new(name)
+        .position_xyz(anchor.x + length * angle.sin(), anchor.y - length * angle.cos(), anchor.z)
+        .rotation(Vec3::new(0.0, 0.0, 1.0), angle)
+        .sphere(radius)
+        .dynamic()
+        .friction(0.0)
+        .density(density)
+        .build();
+    let handle = world.add_body(body, name);
+    world.add_collider(collider, handle);
+    handle
+}
+
+/// Massless link of `length` from `anchor` on the parent to the bob's center
+fn hinge(anchor: Point<f32>, length: f32) -> RevoluteJointBuilder {
+    RevoluteJointBuilder::new(Vector::z_axis())
+        .local_anchor1(anchor)
+        .local_anchor2(point![0.0, length, 0.0])
+        .contacts_enabled(false)
+}
+
+fn sphere_mass(radius: f32, density: f32) -> f32 {
+    density * 4.0 / 3.0 * PI * radius.powi(3)
+}
+
+/// Sphere bob at the end of a pendulum link
+struct Bob {
+    name: &'static str,
+    mass: f32,
+    radius: f32,
+    /// Distance below the pivot when the whole chain hangs straight down
+    depth: f32,
+}
+
+/// Mechanical energy of the bobs, including their spin about their centers
+fn mechanical_energy(bodies: &[BodyState], bobs: &[Bob], gravity: f32) -> f32 {
+    bobs.iter()
+        .filter_map(|bob| {
+            let body = bodies.iter().find(|b| b.name == bob.name)?;
+            let inertia = 0.4 * bob.mass * bob.radius * bob.radius;
+            Some(
+                0.5 * bob.mass * body.velocity.magnitude_squared()
+                    + 0.5 * inertia * body.angular_velocity.magnitude_squared()
+                    + bob.mass * gravity * body.transform.position.y,
+            )
+        })
+        .sum()
+}
+
+/// Energy drift at the end of the run and the largest deviation during it,
+/// both relative to the swing energy above the chain hanging at rest
+fn insert_energy_metrics(metrics: &mut BTreeMap<String, f64>, frames: &[MetricFrame], bobs: &[Bob], gravity: f32) {
+    let (Some(first), Some(last)) = (frames.first(), frames.last()) else {
+        return;
+    };
+
+    let rest: f32 = bobs.iter().map(|b| b.mass * gravity * (PIVOT_HEIGHT - b.depth)).sum();
+    let swing = |frame: &MetricFrame| mechanical_energy(&frame.bodies, bobs, gravity) - rest;
+
+    let initial = swing(first);
+    if initial <= 1e-9 {
+        return;
+    }
+
+    let max_error = frames
+        .iter()
+        .map(|f| (swing(f) - initial).abs())
+        .fold(0.0f32, f32::max);
+
+    metrics.insert("mechanical_energy_drift_percent".to_string(), ((swing(last) - initial) / initial * 100.0) as f64);
+    metrics.insert("max_energy_error_percent".to_string(), (max_error / initial * 100.0) as f64);
+}
+
+/// Mean period from successive crossings of zero angle in the same direction
+fn measured_period(angles: &[(f32, f32)]) -> Option<f32> {
+    let crossings: Vec<f32> = angles
+        .windows(2)
+        .filter(|w| w[0].1 > 0.0 && w[1].1 <= 0.0)
+        .map(|w| {
+            let (t0, a0) = w[0];
+            let (t1, a1) = w[1];
+            t0 + (t1 - t0) * a0 / (a0 - a1)
+        })
+        .collect();
+
+    if crossings.len() < 2 {
+        return None;
+    }
+    Some((crossings[crossings.len() - 1] - crossings[0]) / (crossings.len() - 1) as f32)
+}
+
+/// Complete elliptic integral of the first kind K(k), via the arithmetic-geometric mean
+fn complete_elliptic_k(k: f32) -> f32 {
+    let (mut a, mut b) = (1.0f64, (1.0 - (k as f64) * (k as f64)).sqrt());
+    while (a - b).abs() > 1e-12 {
+        (a, b) = ((a + b) / 2.0, (a * b).sqrt());
+    }
+    (std::f64::consts::PI / (2.0 * a)) as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use simuforge_core::PhysicsConfig;
+
+    #[test]
+    fn test_elliptic_period_reference() {
+        // K(0) = pi / 2, and a 90 degree swing is about 18% slower than the small-angle period
+        assert!((complete_elliptic_k(0.0) - PI / 2.0).abs() < 1e-6);
+        let pendulum = SimplePendulumScenario {
+            initial_angle: PI / 2.0,
+            ..Default::default()
+        };
+        let ratio = pendulum.exact_period(9.81) / pendulum.small_angle_period(9.81);
+        assert!((ratio - 1.18034).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_simple_pendulum_matches_reference() {
+        let mut world = MetricWorld::new(&PhysicsConfig::default());
+        let scenario = SimplePendulumScenario::default();
+        scenario.setup(&mut world).unwrap();
+        world.run(600);
+
+        let metrics = scenario.compute_metrics(world.frames());
+        assert!(metrics["period_error_exact"] < 0.01, "{:?}", metrics);
+        assert!(metrics["mechanical_energy_drift_percent"].abs() < 5.0, "{:?}", metrics);
+    }
+
+    #[test]
+    fn test_predictions_follow_recorded_gravity() {
+        let scenario = SimplePendulumScenario::default();
+        let mut world = MetricWorld::new(&PhysicsConfig::default());
+        world.set_gravity(Vec3::new(0.0, -1.62, 0.0)).unwrap();
+        scenario.setup(&mut world).unwrap();
+        world.run(600);
+        let metrics = scenario.compute_metrics(world.frames());
+        let expected = scenario.exact_period(1.62) as f64;
+        assert!((metrics["exact_period"] - expected).abs() < 1e-6, "{:?}", metrics);
+
+        // Gravity changed mid-run has no single analytic reference
+        world.set_gravity(Vec3::new(0.0, -9.81, 0.0)).unwrap();
+        world.run(10);
+        let metrics = scenario.compute_metrics(world.frames());
+        assert!(!metrics.contains_key("exact_period"), "{:?}", metrics);
+    }
+
+    #[test]
+    fn test_double_pendulum_setup() {
+        let mut world = MetricWorld::new(&PhysicsConfig::default());
+        let scenario = DoublePendulumScenario::default();
+        scenario.setup(&mut world).unwrap();
+        world.run(60);
+
+        assert_eq!(world.joint_names().count(), 2);
+        let metrics = scenario.compute_metrics(world.frames());
+        assert!(metrics.contains_key("max_energy_error_percent"));
+    }
+}
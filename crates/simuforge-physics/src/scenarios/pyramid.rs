@@ -0,0 +1,162 @@
+//! Box pyramid scenario
+
+use crate::{MetricWorld, BodyBuilder, Scenario};
+use simuforge_core::MetricFrame;
+use std::collections::{BTreeMap, HashMap};
+use super::{displacement_metrics, get_f32, get_u32, get_vec3};
+
+/// Scenario: Pyramid of boxes, each row one box narrower than the row below
+pub struct BoxPyramidScenario {
+    /// Number of boxes in the bottom row
+    pub base_width: u32,
+    pub box_size: [f32; 3],
+    /// Horizontal shift of each row relative to the row below, as a fraction of box width
+    pub brick_offset: f32,
+    /// Gap between neighbouring boxes in a row
+    pub spacing: f32,
+    pub friction: f32,
+    pub restitution: f32,
+    /// Displacement beyond which a box counts as fallen
+    pub collapse_threshold: f32,
+}
+
+impl Default for BoxPyramidScenario {
+    fn default() -> Self {
+        Self {
+            base_width: 5,
+            box_size: [1.0, 1.0, 1.0],
+            brick_offset: 0.5,
+            spacing: 0.0,
+            friction: 0.5,
+            restitution: 0.1,
+            collapse_threshold: 0.5,
+        }
+    }
+}
+
+impl BoxPyramidScenario {
+    pub fn new(base_width: u32) -> Self {
+        Self {
+            base_width,
+            ..Default::default()
+        }
+    }
+
+    pub fn from_params(params: &HashMap<String, serde_yaml::Value>) -> Self {
+        let box_size = get_vec3(params, "box_size", [1.0, 1.0, 1.0]);
+        Self {
+            base_width: get_u32(params, "base_width", 5),
+            box_size,
+            brick_offset: get_f32(params, "brick_offset", 0.5),
+            spacing: get_f32(params, "spacing", 0.0),
+            friction: get_f32(params, "friction", 0.5),
+            restitution: get_f32(params, "restitution", 0.1),
+            collapse_threshold: get_f32(params, "collapse_threshold", box_size[1] / 2.0),
+        }
+    }
+
+    /// Total number of boxes in the pyramid
+    pub fn box_count(&self) -> u32 {
+        self.base_width * (self.base_width + 1) / 2
+    }
+}
+
+impl Scenario for BoxPyramidScenario {
+    fn name(&self) -> &str {
+        "box_pyramid"
+    }
+
+    fn description(&self) -> &str {
+        "Pyramid of boxes on a ground plane, tests solver iterations and warm starting"
+    }
+
+    fn setup(&self, world: &mut MetricWorld) -> Result<(), String> {
+        let half_x = self.box_size[0] / 2.0;
+        let half_y = self.box_size[1] / 2.0;
+        let half_z = self.box_size[2] / 2.0;
+        let pitch = self.box_size[0] + self.spacing;
+
+        // Add ground plane
+        let (ground_body, ground_collider, ground_name) = BodyBuilder::new("ground")
+            .position_xyz(0.0, -0.5, 0.0)
+            .box_shape(50.0, 0.5, 50.0)
+            .fixed()
+            .friction(self.friction)
+            .restitution(self.restitution)
+            .build();
+
+        let ground_handle = world.add_body(ground_body, ground_name);
+        world.add_collider(ground_collider, ground_handle);
+
+        // Bottom row is centered on the origin
+        let base_x = -(self.base_width.saturating_sub(1) as f32) * pitch / 2.0;
+
+        for row in 0..self.base_width {
+            let y = half_y + self.box_size[1] * row as f32;
+            let row_x = base_x + row as f32 * self.brick_offset * pitch;
+
+            for i in 0..self.base_width - row {
+                let (body, collider, name) = BodyBuilder::new(format!("box_{}_{}", row, i))
+                    .position_xyz(row_x + i as f32 * pitch, y, 0.0)
+                    .box_shape(half_x, half_y, half_z)
+                    .dynamic()
+                    .friction(self.friction)
+                    .restitution(self.restitution)
+                    .build();
+
+                let handle = world.add_body(body, name);
+                world.add_collider(collider, handle);
+            }
+        }
+        Ok(())
+    }
+
+    fn compute_metrics(&self, frames: &[MetricFrame]) -> BTreeMap<String, f64> {
+        displacement_metrics(frames, "box_", self.collapse_threshold)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use simuforge_core::PhysicsConfig;
+
+    #[test]
+    fn test_pyramid_setup() {
+        let mut world = MetricWorld::new(&PhysicsConfig::default());
+        let scenario = BoxPyramidScenario::new(4);
+        scenario.setup(&mut world).unwrap();
+
+        // Ground + 4 + 3 + 2 + 1 boxes
+        assert_eq!(world.body_count(), 11);
+    }
+
+    #[test]
+    fn test_pyramid_stands() {
+        let mut world = MetricWorld::new(&PhysicsConfig::default());
+        let scenario = BoxPyramidScenario::new(4);
+        scenario.setup(&mut world).unwrap();
+        world.run(120);
+
+        let metrics = scenario.compute_metrics(world.frames());
+        assert_eq!(metrics["collapsed"], 0.0, "{:?}", metrics);
+        assert!(metrics["max_box_displacement"] < 0.1);
+    }
+
+    #[test]
+    fn test_gapped_pyramid_collapses() {
+        let mut world = MetricWorld::new(&PhysicsConfig::default());
+        // Upper boxes sit over gaps as wide as a box and drop through
+        let scenario = BoxPyramidScenario {
+            spacing: 1.0,
+            ..BoxPyramidScenario::new(3)
+        };
+        scenario.setup(&mut world).unwrap();
+        world.run(120);
+
+        let metrics = scenario.compute_metrics(world.frames());
+        assert_eq!(metrics["collapsed"], 1.0, "{:?}", metrics);
+        assert!(metrics["displaced_boxes"] >= 1.0);
+        assert!(metrics.contains_key("collapse_time"));
+    }
+}
@@ -1,8 +1,12 @@
 //! Rolling sphere scenario
 
 use crate::{MetricWorld, BodyBuilder, Scenario};
-use std::collections::HashMap;
-use super::{get_f32, get_vec3};
+use simuforge_core::{MetricFrame, Vec3};
+use std::collections::{BTreeMap, HashMap};
+use super::{get_f32, get_vec3, vertical_gravity};
+
+/// Contact-point slip speed, relative to the launch speed, below which the sphere is rolling
+const ROLLING_SLIP_FRACTION: f32 = 0.01;
 
 /// Scenario: Sphere rolling on a flat surface
 pub struct RollingSphereScenario {
@@ -35,6 +39,21 @@ impl RollingSphereScenario {
             density: get_f32(params, "density", 1.0),
         }
     }
+
+    fn launch_speed(&self) -> f32 {
+        let [x, _, z] = self.initial_velocity;
+        (x * x + z * z).sqrt()
+    }
+
+    /// Time for kinetic friction to bring a sliding sphere into rolling, `2 v0 / (7 mu g)`
+    pub fn expected_roll_transition_time(&self, gravity: f32) -> f32 {
+        2.0 * self.launch_speed() / (7.0 * self.friction * gravity)
+    }
+
+    /// Rolling speed once slipping stops, `5/7 v0`
+    pub fn expected_rolling_speed(&self) -> f32 {
+        5.0 / 7.0 * self.launch_speed()
+    }
 }
 
 impl Scenario for RollingSphereScenario {
@@ -46,7 +65,7 @@ impl Scenario for RollingSphereScenario {
         "Sphere rolling on a flat surface, tests friction and angular momentum"
     }
 
-    fn setup(&self, world: &mut MetricWorld) {
+    fn setup(&self, world: &mut MetricWorld) -> Result<(), String> {
         // Add ground plane
         let (ground_body, ground_collider, ground_name) = BodyBuilder::new("ground")
             .position_xyz(0.0, -0.5, 0.0)
@@ -76,6 +95,40 @@ impl Scenario for RollingSphereScenario {
 
         let sphere_handle = world.add_body(sphere_body, sphere_name);
         world.add_collider(sphere_collider, sphere_handle);
+        Ok(())
+    }
+
+    fn compute_metrics(&self, frames: &[MetricFrame]) -> BTreeMap<String, f64> {
+        let mut metrics = BTreeMap::new();
+        let Some(gravity) = vertical_gravity(frames) else {
+            return metrics;
+        };
+        let launch_speed = self.launch_speed();
+        if launch_speed <= 0.0 || self.friction <= 0.0 {
+            return metrics;
+        }
+
+        // Velocity of the material point touching the ground: v + w x (0, -r, 0)
+        let contact_offset = Vec3::new(0.0, -self.radius, 0.0);
+        let rolling = frames.iter().find_map(|f| {
+            let sphere = f.bodies.iter().find(|b| b.name == "sphere")?;
+            let slip = sphere.velocity + sphere.angular_velocity.cross(&contact_offset);
+            let horizontal = Vec3::new(slip.x, 0.0, slip.z).magnitude();
+            (horizontal < ROLLING_SLIP_FRACTION * launch_speed).then_some((f.time, sphere.velocity))
+        });
+
+        let Some((time, velocity)) = rolling else {
+            return metrics;
+        };
+
+        let expected_time = self.expected_roll_transition_time(gravity);
+        let expected_speed = self.expected_rolling_speed();
+        let speed = Vec3::new(velocity.x, 0.0, velocity.z).magnitude();
+        metrics.insert("roll_transition_time".to_string(), time as f64);
+        metrics.insert("expected_roll_transition_time".to_string(), expected_time as f64);
+        metrics.insert("roll_transition_time_error".to_string(), (time - expected_time).abs() as f64);
+        metrics.insert("rolling_speed_error".to_string(), ((speed - expected_speed).abs() / expected_speed) as f64);
+        metrics
     }
 }
 
@@ -90,8 +143,21 @@ mod tests {
         let mut world = MetricWorld::new(&config);
 
         let scenario = RollingSphereScenario::default();
-        scenario.setup(&mut world);
+        scenario.setup(&mut world).unwrap();
 
         assert_eq!(world.body_count(), 2);
     }
+
+    #[test]
+    fn test_roll_transition_matches_reference() {
+        let mut world = MetricWorld::new(&PhysicsConfig::default());
+        let scenario = RollingSphereScenario::default();
+        scenario.setup(&mut world).unwrap();
+        world.run(60);
+
+        // Allow two frames of timing resolution
+        let metrics = scenario.compute_metrics(world.frames());
+        assert!(metrics["roll_transition_time_error"] < 1.0 / 30.0, "{:?}", metrics);
+        assert!(metrics["rolling_speed_error"] < 0.02, "{:?}", metrics);
+    }
 }
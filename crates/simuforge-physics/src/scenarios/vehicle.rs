@@ -0,0 +1,324 @@
+//! Vehicle on terrain scenario
+
+use crate::{MetricWorld, BodyBuilder, MotorCommand, Scenario};
+use rapier3d::prelude::*;
+use simuforge_core::MetricFrame;
+use simuforge_core::metrics::BodyState;
+use std::collections::{BTreeMap, HashMap};
+use std::f32::consts::{FRAC_PI_2, TAU};
+use super::{get_f32, get_str, get_u32, get_vec3};
+
+/// Flat run-up before the terrain starts, so the vehicle settles on its wheels first
+const RUN_UP: f32 = 3.0;
+
+/// Speeds below which a wheel is too slow for a meaningful slip ratio (m/s)
+const MIN_SLIP_SPEED: f32 = 0.1;
+
+/// Wheel corners: name suffix, sign along the chassis length and sign across it
+const WHEELS: [(&str, f32, f32); 4] = [("fl", 1.0, -1.0), ("fr", 1.0, 1.0), ("rl", -1.0, -1.0), ("rr", -1.0, 1.0)];
+
+/// Ground the vehicle drives over
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Terrain {
+    Flat,
+    /// Half-buried cylinders lying across the track
+    Bumps,
+    /// Rolling sinusoidal heightfield
+    Heightfield,
+}
+
+/// Scenario: Four-wheeled vehicle with motor-driven axles driving along +X
+pub struct VehicleScenario {
+    /// Length, height and width of the chassis
+    pub chassis_size: [f32; 3],
+    pub chassis_density: f32,
+    pub wheel_radius: f32,
+    pub wheel_width: f32,
+    pub wheel_density: f32,
+    /// Target ground speed of the wheels (m/s)
+    pub drive_speed: f32,
+    /// Gain of the axle velocity motors
+    pub motor_gain: f32,
+    pub max_torque: f32,
+    pub friction: f32,
+    pub terrain: Terrain,
+    /// Bump radius, or heightfield amplitude
+    pub bump_height: f32,
+    /// Distance between bumps, or heightfield wavelength
+    pub bump_spacing: f32,
+    pub bump_count: u32,
+}
+
+impl Default for VehicleScenario {
+    fn default() -> Self {
+        Self {
+            chassis_size: [2.0, 0.3, 1.0],
+            chassis_density: 100.0,
+            wheel_radius: 0.35,
+            wheel_width: 0.2,
+            wheel_density: 100.0,
+            drive_speed: 3.0,
+            motor_gain: 50.0,
+            max_torque: 200.0,
+            friction: 1.0,
+            terrain: Terrain::Bumps,
+            bump_height: 0.1,
+            bump_spacing: 2.0,
+            bump_count: 10,
+        }
+    }
+}
+
+impl VehicleScenario {
+    pub fn new(terrain: Terrain) -> Self {
+        Self {
+            terrain,
+            ..Default::default()
+        }
+    }
+
+    pub fn from_params(params: &HashMap<String, serde_yaml::Value>) -> Self {
+        let defaults = Self::default();
+        Self {
+            chassis_size: get_vec3(params, "chassis_size", defaults.chassis_size),
+            chassis_density: get_f32(params, "chassis_density", defaults.chassis_density),
+            wheel_radius: get_f32(params, "wheel_radius", defaults.wheel_radius),
+            wheel_width: get_f32(params, "wheel_width", defaults.wheel_width),
+            wheel_density: get_f32(params, "wheel_density", defaults.wheel_density),
+            drive_speed: get_f32(params, "drive_speed", defaults.drive_speed),
+            motor_gain: get_f32(params, "motor_gain", defaults.motor_gain),
+            max_torque: get_f32(params, "max_torque", defaults.max_torque),
+            friction: get_f32(params, "friction", defaults.friction),
+            terrain: match get_str(params, "terrain", "bumps") {
+                "flat" => Terrain::Flat,
+                "heightfield" => Terrain::Heightfield,
+                _ => Terrain::Bumps,
+            },
+            bump_height: get_f32(params, "bump_height", defaults.bump_height),
+            bump_spacing: get_f32(params, "bump_spacing", defaults.bump_spacing),
+            bump_count: get_u32(params, "bump_count", defaults.bump_count),
+        }
+    }
+
+    fn wheel_name(suffix: &str) -> String {
+        format!("wheel_{}", suffix)
+    }
+
+    /// Length of track covered by the terrain features
+    fn course_length(&self) -> f32 {
+        self.bump_spacing * self.bump_count.max(1) as f32
+    }
+
+    fn add_ground(&self, world: &mut MetricWorld) {
+        let length = RUN_UP + self.course_length() + 20.0;
+        let (ground_body, ground_collider, ground_name) = BodyBuilder::new("ground")
+            .position_xyz(length / 2.0 - RUN_UP - 5.0, -0.5, 0.0)
+            .box_shape(length / 2.0, 0.5, 10.0)
+            .fixed()
+            .friction(self.friction)
+            .build();
+
+        let ground_handle = world.add_body(ground_body, ground_name);
+        world.add_collider(ground_collider, ground_handle);
+    }
+
+    /// Cylinders across the track, centered on the ground surface
+    fn add_bumps(&self, world: &mut MetricWorld) {
+        let half_across = self.chassis_size[2] / 2.0 + self.wheel_width + 1.0;
+        for i in 0..self.bump_count {
+            let (body, mut collider, name) = BodyBuilder::new(format!("bump_{}", i))
+                .position_xyz(RUN_UP + i as f32 * self.bump_spacing, 0.0, 0.0)
+                .cylinder(half_across, self.bump_height)
+                .fixed()
+                .friction(self.friction)
+                .build();
+            // Cylinders are built along +Y; lay this one across the track
+            collider.set_position(Isometry::rotation(vector![FRAC_PI_2, 0.0, 0.0]));
+            let handle = world.add_body(body, name);
+            world.add_collider(collider, handle);
+        }
+    }
+
+    /// Rolling heightfield starting flat at the end of the run-up
+    fn add_heightfield(&self, world: &mut MetricWorld) {
+        let length = self.course_length();
+        let width = 20.0;
+        let columns = ((length / 0.1).ceil() as usize).max(1);
+        let rows = 4;
+        let heights = nalgebra::DMatrix::from_fn(rows + 1, columns + 1, |_, j| {
+            let x = j as f32 * length / columns as f32;
+            // Starts at a trough so the surface meets the ground without a step
+            self.bump_height * (1.0 - (TAU * x / self.bump_spacing).cos()) / 2.0
+        });
+
+        let (body, _, _) = BodyBuilder::new("terrain")
+            .position_xyz(RUN_UP + length / 2.0, 0.0, 0.0)
+            .fixed()
+            .build();
+        let handle = world.add_body(body, "terrain".to_string());
+        let collider = ColliderBuilder::heightfield(heights, vector![length, 1.0, width])
+            .friction(self.friction)
+            .build();
+        world.add_collider(collider, handle);
+    }
+
+    fn add_vehicle(&self, world: &mut MetricWorld) -> Result<(), String> {
+        let [length, height, width] = self.chassis_size;
+        let axle_y = self.wheel_radius + 0.02;
+
+        let (chassis_body, chassis_collider, chassis_name) = BodyBuilder::new("chassis")
+            .position_xyz(0.0, axle_y + height / 2.0, 0.0)
+            .box_shape(length / 2.0, height / 2.0, width / 2.0)
+            .dynamic()
+            .density(self.chassis_density)
+            .friction(self.friction)
+            .build();
+        let chassis = world.add_body(chassis_body, chassis_name);
+        world.add_collider(chassis_collider, chassis);
+
+        let wheel_x = length / 2.0 * 0.75;
+        let wheel_z = width / 2.0 + self.wheel_width / 2.0 + 0.05;
+        for (suffix, along, across) in WHEELS {
+            let (body, mut collider, name) = BodyBuilder::new(Self::wheel_name(suffix))
+                .position_xyz(along * wheel_x, axle_y, across * wheel_z)
+                .cylinder(self.wheel_width / 2.0, self.wheel_radius)
+                .dynamic()
+                .density(self.wheel_density)
+                .friction(self.friction)
+                .restitution(0.0)
+                .build();
+            // Keep the wheel body aligned with the chassis so the axle axis is +Z in both frames
+            collider.set_position(Isometry::rotation(vector![FRAC_PI_2, 0.0, 0.0]));
+            let wheel = world.add_body(body, name);
+            world.add_collider(collider, wheel);
+
+            let axle = RevoluteJointBuilder::new(Vector::z_axis())
+                .local_anchor1(point![along * wheel_x, -height / 2.0, across * wheel_z])
+                .local_anchor2(point![0.0, 0.0, 0.0])
+                .contacts_enabled(false);
+            let joint_name = format!("axle_{}", suffix);
+            world.add_joint(joint_name.clone(), chassis, wheel, axle);
+
+            // Rolling towards +X turns the wheels clockwise about +Z
+            let mut drive = MotorCommand::velocity(joint_name, -self.drive_speed / self.wheel_radius, self.motor_gain);
+            drive.max_force = Some(self.max_torque);
+            world.apply_motor_command(&drive).map_err(|e| format!("Wheel {}: {}", suffix, e))?;
+        }
+        Ok(())
+    }
+
+    /// Slip ratio of one wheel: difference between rim speed and ground speed,
+    /// relative to the larger of the two
+    fn slip_ratio(&self, chassis: &BodyState, wheel: &BodyState) -> Option<f32> {
+        let rotation = chassis.transform.rotation.to_nalgebra();
+        let forward = rotation * Vector::x();
+        let axle = rotation * Vector::z();
+
+        let rim_speed = -wheel.angular_velocity.to_nalgebra().dot(&axle) * self.wheel_radius;
+        let ground_speed = wheel.velocity.to_nalgebra().dot(&forward);
+        let scale = rim_speed.abs().max(ground_speed.abs());
+        (scale > MIN_SLIP_SPEED).then(|| (rim_speed - ground_speed).abs() / scale)
+    }
+}
+
+impl Scenario for VehicleScenario {
+    fn name(&self) -> &str {
+        "vehicle"
+    }
+
+    fn description(&self) -> &str {
+        "Motor-driven four-wheeled vehicle on uneven ground, tests joints, motors and friction together"
+    }
+
+    fn setup(&self, world: &mut MetricWorld) -> Result<(), String> {
+        self.add_ground(world);
+        match self.terrain {
+            Terrain::Flat => {}
+            Terrain::Bumps => self.add_bumps(world),
+            Terrain::Heightfield => self.add_heightfield(world),
+        }
+        self.add_vehicle(world)
+    }
+
+    fn compute_metrics(&self, frames: &[MetricFrame]) -> BTreeMap<String, f64> {
+        let mut metrics = BTreeMap::new();
+        let chassis = |frame: &'_ MetricFrame| frame.bodies.iter().find(|b| b.name == "chassis").cloned();
+        let (Some(start), Some(end)) = (frames.first().and_then(chassis), frames.last().and_then(chassis)) else {
+            return metrics;
+        };
+
+        let distance = end.transform.position.x - start.transform.position.x;
+        metrics.insert("distance_traveled".to_string(), distance as f64);
+        if let Some(last) = frames.last() {
+            if last.time > 0.0 {
+                metrics.insert("average_speed".to_string(), (distance / last.time) as f64);
+            }
+        }
+
+        let mut max_roll = 0.0f32;
+        let mut max_pitch = 0.0f32;
+        let mut rolled_over = false;
+        let mut slips = Vec::new();
+        for frame in frames {
+            let Some(chassis) = frame.bodies.iter().find(|b| b.name == "chassis") else {
+                continue;
+            };
+            let rotation = chassis.transform.rotation.to_nalgebra();
+            let pitch = (rotation * Vector::x()).y.clamp(-1.0, 1.0).asin();
+            let roll = (rotation * Vector::z()).y.clamp(-1.0, 1.0).asin();
+            max_pitch = max_pitch.max(pitch.abs());
+            max_roll = max_roll.max(roll.abs());
+            rolled_over |= (rotation * Vector::y()).y < 0.0;
+
+            for (suffix, _, _) in WHEELS {
+                let name = Self::wheel_name(suffix);
+                if let Some(slip) = frame.bodies.iter().find(|b| b.name == name).and_then(|w| self.slip_ratio(chassis, w)) {
+                    slips.push(slip);
+                }
+            }
+        }
+
+        metrics.insert("max_roll_degrees".to_string(), max_roll.to_degrees() as f64);
+        metrics.insert("max_pitch_degrees".to_string(), max_pitch.to_degrees() as f64);
+        metrics.insert("rolled_over".to_string(), if rolled_over { 1.0 } else { 0.0 });
+        if !slips.is_empty() {
+            let mean = slips.iter().sum::<f32>() / slips.len() as f32;
+            metrics.insert("mean_slip_ratio".to_string(), mean as f64);
+            metrics.insert("max_slip_ratio".to_string(), slips.iter().copied().fold(0.0f32, f32::max) as f64);
+        }
+
+        metrics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use simuforge_core::PhysicsConfig;
+
+    #[test]
+    fn test_vehicle_drives_forward_on_flat_ground() {
+        let mut world = MetricWorld::new(&PhysicsConfig::default());
+        let scenario = VehicleScenario::new(Terrain::Flat);
+        scenario.setup(&mut world).unwrap();
+        assert_eq!(world.joint_names().count(), 4);
+
+        world.run(180);
+        let metrics = scenario.compute_metrics(world.frames());
+        assert!(metrics["distance_traveled"] > 4.0, "{:?}", metrics);
+        assert!(metrics["mean_slip_ratio"] < 0.2, "{:?}", metrics);
+        assert_eq!(metrics["rolled_over"], 0.0);
+    }
+
+    #[test]
+    fn test_bumps_pitch_the_chassis() {
+        let mut world = MetricWorld::new(&PhysicsConfig::default());
+        let scenario = VehicleScenario { bump_height: 0.15, ..VehicleScenario::new(Terrain::Bumps) };
+        scenario.setup(&mut world).unwrap();
+
+        world.run(300);
+        let metrics = scenario.compute_metrics(world.frames());
+        assert!(metrics["max_pitch_degrees"] > 1.0, "{:?}", metrics);
+        assert!(metrics["distance_traveled"] > RUN_UP as f64, "{:?}", metrics);
+    }
+}
@@ -0,0 +1,192 @@
+//! Collider geometry export for renderers
+//!
+//! Describes every body's colliders from the live world, so renderers do not
+//! have to guess shapes from the spec or from body names.
+
+use crate::MetricWorld;
+use nalgebra::UnitQuaternion;
+use rapier3d::prelude::*;
+use serde::Serialize;
+use simuforge_core::{Transform, Vec3};
+
+/// Segments around the circumference when tessellating round shapes
+const ROUND_SUBDIVISIONS: u32 = 24;
+
+/// Every body and its collider geometry
+#[derive(Debug, Clone, Serialize)]
+pub struct SceneDescription {
+    pub bodies: Vec<SceneBody>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SceneBody {
+    pub id: u64,
+    pub name: String,
+    /// `dynamic`, `fixed` or `kinematic`
+    pub body_type: &'static str,
+    pub transform: Transform,
+    pub colliders: Vec<SceneCollider>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SceneCollider {
+    pub shape: ShapeDescription,
+    /// Pose relative to the body
+    pub offset: Transform,
+    pub sensor: bool,
+}
+
+/// Collider shape in its own frame. Round shapes are centered on the origin,
+/// with capsules, cylinders and cones along the Y axis.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ShapeDescription {
+    Box { half_extents: Vec3 },
+    Sphere { radius: f32 },
+    Capsule { half_height: f32, radius: f32 },
+    Cylinder { half_height: f32, radius: f32 },
+    Cone { half_height: f32, radius: f32 },
+    /// Triangles of heightfields, triangle meshes and convex hulls
+    Mesh { vertices: Vec<[f32; 3]>, indices: Vec<[u32; 3]> },
+}
+
+impl ShapeDescription {
+    /// Triangulate the shape, tessellating round shapes
+    pub fn triangles(&self) -> (Vec<[f32; 3]>, Vec<[u32; 3]>) {
+        let (points, indices) = match self {
+            Self::Box { half_extents } => Cuboid::new(half_extents.to_nalgebra()).to_trimesh(),
+            Self::Sphere { radius } => Ball::new(*radius).to_trimesh(ROUND_SUBDIVISIONS, ROUND_SUBDIVISIONS / 2),
+            Self::Capsule { half_height, radius } => {
+                Capsule::new_y(*half_height, *radius).to_trimesh(ROUND_SUBDIVISIONS, ROUND_SUBDIVISIONS / 4)
+            }
+            Self::Cylinder { half_height, radius } => Cylinder::new(*half_height, *radius).to_trimesh(ROUND_SUBDIVISIONS),
+            Self::Cone { half_height, radius } => Cone::new(*half_height, *radius).to_trimesh(ROUND_SUBDIVISIONS),
+            Self::Mesh { vertices, indices } => return (vertices.clone(), indices.clone()),
+        };
+        (points.iter().map(|p| [p.x, p.y, p.z]).collect(), indices)
+    }
+}
+
+impl MetricWorld {
+    /// Describe every body's colliders, in body id order
+    pub fn scene_description(&self) -> SceneDescription {
+        let bodies = self.body_order
+            .iter()
+            .filter_map(|handle| {
+                let body = self.rigid_body_set.get(*handle)?;
+                let mut colliders = Vec::new();
+                for collider in body.colliders().iter().filter_map(|c| self.collider_set.get(*c)) {
+                    let offset = collider.position_wrt_parent().copied().unwrap_or_else(Isometry::identity);
+                    describe_shape(collider.shape(), offset, collider.is_sensor(), &mut colliders);
+                }
+                Some(SceneBody {
+                    id: *self.body_ids.get(handle)?,
+                    name: self.body_names.get(handle)?.clone(),
+                    body_type: match body.body_type() {
+                        RigidBodyType::Dynamic => "dynamic",
+                        RigidBodyType::Fixed => "fixed",
+                        RigidBodyType::KinematicPositionBased | RigidBodyType::KinematicVelocityBased => "kinematic",
+                    },
+                    transform: Transform::from_isometry(body.position()),
+                    colliders,
+                })
+            })
+            .collect();
+        SceneDescription { bodies }
+    }
+}
+
+/// Push the description of a shape, flattening compounds into their parts.
+/// Shapes without a volume to draw, like half-spaces, are left out.
+fn describe_shape(shape: &dyn Shape, offset: Isometry<f32>, sensor: bool, out: &mut Vec<SceneCollider>) {
+    let mut offset = offset;
+    let mesh = |(points, indices): (Vec<Point<f32>>, Vec<[u32; 3]>)| ShapeDescription::Mesh {
+        vertices: points.iter().map(|p| [p.x, p.y, p.z]).collect(),
+        indices,
+    };
+
+    let description = match shape.as_typed_shape() {
+        TypedShape::Ball(ball) => ShapeDescription::Sphere { radius: ball.radius },
+        TypedShape::Cuboid(cuboid) => ShapeDescription::Box { half_extents: Vec3::from_nalgebra(&cuboid.half_extents) },
+        TypedShape::RoundCuboid(cuboid) => ShapeDescription::Box {
+            half_extents: Vec3::from_nalgebra(&cuboid.inner_shape.half_extents.add_scalar(cuboid.border_radius)),
+        },
+        TypedShape::Capsule(capsule) => {
+            // Capsules may lie along any segment; describe them along Y and turn the offset instead
+            let axis = capsule.segment.b - capsule.segment.a;
+            let rotation = UnitQuaternion::rotation_between(&Vector::y(), &axis).unwrap_or_else(|| {
+                UnitQuaternion::from_axis_angle(&Vector::x_axis(), std::f32::consts::PI)
+            });
+            offset *= Isometry::from_parts(capsule.segment.a.coords.lerp(&capsule.segment.b.coords, 0.5).into(), rotation);
+            ShapeDescription::Capsule { half_height: axis.norm() / 2.0, radius: capsule.radius }
+        }
+        TypedShape::Cylinder(cylinder) => ShapeDescription::Cylinder { half_height: cylinder.half_height, radius: cylinder.radius },
+        TypedShape::Cone(cone) => ShapeDescription::Cone { half_height: cone.half_height, radius: cone.radius },
+        TypedShape::HeightField(heightfield) => mesh(heightfield.to_trimesh()),
+        TypedShape::ConvexPolyhedron(polyhedron) => mesh(polyhedron.to_trimesh()),
+        TypedShape::TriMesh(trimesh) => mesh((trimesh.vertices().to_vec(), trimesh.indices().to_vec())),
+        TypedShape::Compound(compound) => {
+            for (part_offset, part) in compound.shapes() {
+                describe_shape(part.as_ref(), offset * part_offset, sensor, out);
+            }
+            return;
+        }
+        _ => return,
+    };
+
+    out.push(SceneCollider {
+        shape: description,
+        offset: Transform::from_isometry(&offset),
+        sensor,
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scenarios::create_scenario;
+    use crate::BodyBuilder;
+    use simuforge_core::{PhysicsConfig, ScenarioConfig};
+
+    #[test]
+    fn test_describes_builtin_shapes() {
+        let mut world = MetricWorld::new(&PhysicsConfig::default());
+        let (body, collider, name) = BodyBuilder::new("ball")
+            .position_xyz(1.0, 2.0, 3.0)
+            .sphere(0.25)
+            .build();
+        let handle = world.add_body(body, name);
+        world.add_collider(collider, handle);
+
+        let scene = world.scene_description();
+        let ball = scene.bodies.iter().find(|b| b.name == "ball").unwrap();
+        assert_eq!(ball.body_type, "dynamic");
+        assert_eq!(ball.transform.position, Vec3::new(1.0, 2.0, 3.0));
+        assert!(matches!(ball.colliders[0].shape, ShapeDescription::Sphere { radius } if radius == 0.25));
+
+        let (vertices, indices) = ball.colliders[0].shape.triangles();
+        assert!(!indices.is_empty());
+        assert!(vertices.iter().all(|v| (Vec3::new(v[0], v[1], v[2]).magnitude() - 0.25).abs() < 1e-4));
+    }
+
+    #[test]
+    fn test_heightfield_exported_as_mesh() {
+        let config: ScenarioConfig = serde_yaml::from_str("type: builtin\nname: vehicle\nparams: { terrain: heightfield }").unwrap();
+        let mut world = MetricWorld::new(&PhysicsConfig::default());
+        create_scenario(&config).setup(&mut world).unwrap();
+
+        let scene = world.scene_description();
+        assert_eq!(scene.bodies.len(), world.body_count());
+        let meshes: Vec<_> = scene.bodies
+            .iter()
+            .flat_map(|b| &b.colliders)
+            .filter_map(|c| match &c.shape {
+                ShapeDescription::Mesh { vertices, indices } => Some((vertices.len(), indices)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(meshes.len(), 1);
+        let (count, indices) = &meshes[0];
+        assert!(indices.iter().flatten().all(|&i| (i as usize) < *count));
+    }
+}
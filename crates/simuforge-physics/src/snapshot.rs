@@ -0,0 +1,504 @@
+//! World snapshots for rewinding and seeking
+//!
+//! A snapshot holds a full copy of the physics state. Restoring it and
+//! stepping forward reproduces the original run exactly, so a history of
+//! periodic snapshots is enough to seek to any past step.
+
+use crate::actions::ActiveForce;
+use crate::control::{ActuatorTarget, Controller};
+use crate::interaction::DragSpring;
+use crate::joints::NamedJoint;
+use crate::motion::KinematicMotion;
+use crate::MetricWorld;
+use rapier3d::prelude::*;
+use simuforge_core::spec::ActionConfig;
+use simuforge_core::{SimEvent, Vec3};
+use std::collections::HashMap;
+use std::mem::size_of;
+
+/// Default number of steps between snapshots in a [`SnapshotHistory`]
+pub const DEFAULT_SNAPSHOT_INTERVAL: u64 = 60;
+
+/// Default memory budget of a [`SnapshotHistory`], in bytes
+pub const DEFAULT_SNAPSHOT_MEMORY_CAP: usize = 64 * 1024 * 1024;
+
+/// Complete simulation state at one step
+pub struct WorldSnapshot {
+    step: u64,
+    time: f32,
+    frame_count: usize,
+    gravity: Vector<f32>,
+    integration_parameters: IntegrationParameters,
+    timestep: f32,
+    rigid_body_set: RigidBodySet,
+    collider_set: ColliderSet,
+    island_manager: IslandManager,
+    broad_phase: DefaultBroadPhase,
+    narrow_phase: NarrowPhase,
+    impulse_joint_set: ImpulseJointSet,
+    multibody_joint_set: MultibodyJointSet,
+    ccd_solver: CCDSolver,
+    query_pipeline: QueryPipeline,
+    body_names: HashMap<RigidBodyHandle, String>,
+    body_ids: HashMap<RigidBodyHandle, u64>,
+    next_body_id: u64,
+    body_order: Vec<RigidBodyHandle>,
+    body_layout_version: u64,
+    pending_events: Vec<SimEvent>,
+    actions: Vec<ActionConfig>,
+    active_forces: Vec<ActiveForce>,
+    motions: Vec<KinematicMotion>,
+    drag: Option<DragSpring>,
+    joints: Vec<NamedJoint>,
+    actuator_targets: Vec<ActuatorTarget>,
+    controllers: Vec<Box<dyn Controller>>,
+}
+
+impl WorldSnapshot {
+    /// Step the snapshot was taken at
+    pub fn step(&self) -> u64 {
+        self.step
+    }
+
+    /// Rough memory footprint, used to enforce history memory caps
+    pub fn estimated_bytes(&self) -> usize {
+        let bodies = self.rigid_body_set.len() * (size_of::<RigidBody>() + size_of::<(RigidBodyHandle, String, u64)>());
+        let colliders = self.collider_set.len() * size_of::<Collider>();
+        let contacts: usize = self.narrow_phase
+            .contact_pairs()
+            .map(|pair| size_of::<ContactPair>() + pair.manifolds.len() * size_of::<ContactManifold>())
+            .sum();
+        let joints = (self.impulse_joint_set.len() + self.joints.len()) * size_of::<ImpulseJoint>();
+        // The broad phase and islands hold roughly one entry per collider and body
+        size_of::<Self>() + bodies + 2 * colliders + contacts + joints
+    }
+
+    fn copy_controllers(controllers: &[Box<dyn Controller>]) -> Result<Vec<Box<dyn Controller>>, String> {
+        controllers
+            .iter()
+            .map(|c| c.snapshot().ok_or_else(|| format!("Controller cannot be snapshotted: {}", c.name())))
+            .collect()
+    }
+}
+
+impl MetricWorld {
+    /// Copy the full simulation state. Fails if a controller cannot copy its state.
+    pub fn snapshot(&self) -> Result<WorldSnapshot, String> {
+        Ok(WorldSnapshot {
+            step: self.current_step,
+            time: self.current_time,
+            frame_count: self.frames.len(),
+            gravity: self.gravity,
+            integration_parameters: self.integration_parameters,
+            timestep: self.timestep,
+            rigid_body_set: self.rigid_body_set.clone(),
+            collider_set: self.collider_set.clone(),
+            island_manager: self.island_manager.clone(),
+            broad_phase: self.broad_phase.clone(),
+            narrow_phase: self.narrow_phase.clone(),
+            impulse_joint_set: self.impulse_joint_set.clone(),
+            multibody_joint_set: self.multibody_joint_set.clone(),
+            ccd_solver: self.ccd_solver.clone(),
+            query_pipeline: self.query_pipeline.clone(),
+            body_names: self.body_names.clone(),
+            body_ids: self.body_ids.clone(),
+            next_body_id: self.next_body_id,
+            body_order: self.body_order.clone(),
+            body_layout_version: self.body_layout_version,
+            pending_events: self.pending_events.clone(),
+            actions: self.actions.clone(),
+            active_forces: self.active_forces.clone(),
+            motions: self.motions.clone(),
+            drag: self.drag.clone(),
+            joints: self.joints.clone(),
+            actuator_targets: self.actuator_targets.clone(),
+            controllers: WorldSnapshot::copy_controllers(&self.controllers)?,
+        })
+    }
+
+    /// Return to a snapshot taken from this world, dropping frames recorded after it
+    pub fn restore(&mut self, snapshot: &WorldSnapshot) -> Result<(), String> {
+        self.controllers = WorldSnapshot::copy_controllers(&snapshot.controllers)?;
+        self.current_step = snapshot.step;
+        self.current_time = snapshot.time;
+        self.frames.truncate(snapshot.frame_count);
+        self.gravity = snapshot.gravity;
+        self.integration_parameters = snapshot.integration_parameters;
+        self.timestep = snapshot.timestep;
+        self.rigid_body_set = snapshot.rigid_body_set.clone();
+        self.collider_set = snapshot.collider_set.clone();
+        self.island_manager = snapshot.island_manager.clone();
+        self.broad_phase = snapshot.broad_phase.clone();
+        self.narrow_phase = snapshot.narrow_phase.clone();
+        self.impulse_joint_set = snapshot.impulse_joint_set.clone();
+        self.multibody_joint_set = snapshot.multibody_joint_set.clone();
+        self.ccd_solver = snapshot.ccd_solver.clone();
+        self.query_pipeline = snapshot.query_pipeline.clone();
+        self.body_names = snapshot.body_names.clone();
+        self.body_ids = snapshot.body_ids.clone();
+        self.next_body_id = snapshot.next_body_id;
+        self.body_order = snapshot.body_order.clone();
+        self.body_layout_version = snapshot.body_layout_version;
+        self.pending_events = snapshot.pending_events.clone();
+        self.actions = snapshot.actions.clone();
+        self.active_forces = snapshot.active_forces.clone();
+        self.motions = snapshot.motions.clone();
+        self.drag = snapshot.drag.clone();
+        self.joints = snapshot.joints.clone();
+        self.actuator_targets = snapshot.actuator_targets.clone();
+        // Solver scratch space is not part of the state
+        self.physics_pipeline = PhysicsPipeline::new();
+        Ok(())
+    }
+}
+
+/// Live change made to a world between steps, logged so seeking can replay it
+#[derive(Debug, Clone, PartialEq)]
+pub enum WorldInput {
+    StartDrag { body_id: u64, point: Vec3, stiffness: f32, damping: Option<f32> },
+    MoveDrag { target: Vec3 },
+    EndDrag,
+    Impulse { body_id: u64, impulse: Vec3, point: Vec3 },
+    Gravity { gravity: Vec3 },
+    Timestep { timestep: f32 },
+    SolverIterations { iterations: u32 },
+    BodyMaterial {
+        body: String,
+        friction: Option<f32>,
+        restitution: Option<f32>,
+        density: Option<f32>,
+    },
+    BodyMass { body: String, mass: f32 },
+}
+
+impl WorldInput {
+    /// Make the change this input describes, as the live call did
+    pub fn apply(&self, world: &mut MetricWorld) -> Result<(), String> {
+        match self {
+            WorldInput::StartDrag { body_id, point, stiffness, damping } => {
+                world.start_drag(*body_id, *point, *stiffness, *damping)
+            }
+            WorldInput::MoveDrag { target } => {
+                world.move_drag(*target);
+                Ok(())
+            }
+            WorldInput::EndDrag => {
+                world.end_drag();
+                Ok(())
+            }
+            WorldInput::Impulse { body_id, impulse, point } => {
+                world.apply_impulse_at(*body_id, *impulse, *point)
+            }
+            WorldInput::Gravity { gravity } => world.set_gravity(*gravity),
+            WorldInput::Timestep { timestep } => world.set_timestep(*timestep),
+            WorldInput::SolverIterations { iterations } => world.set_solver_iterations(*iterations),
+            WorldInput::BodyMaterial { body, friction, restitution, density } => {
+                world.set_body_material(body, *friction, *restitution, *density)
+            }
+            WorldInput::BodyMass { body, mass } => world.set_body_mass(body, *mass),
+        }
+    }
+}
+
+/// Snapshot in a history, taken before any input logged at its step
+struct HistoryEntry {
+    snapshot: WorldSnapshot,
+    /// Number of logged inputs the snapshot already reflects
+    inputs: usize,
+    /// Exempt from thinning
+    pinned: bool,
+}
+
+/// Periodic snapshots of one run plus a log of live inputs, for seeking back
+/// to earlier steps. Seeking restores a snapshot and replays the inputs logged
+/// after it while re-simulating.
+///
+/// When the memory cap is exceeded every other snapshot is dropped and the
+/// interval doubled, so the history keeps covering the whole run more sparsely.
+/// The snapshot taken when a drag starts is pinned, so seeking into a drag
+/// does not replay it from far back.
+pub struct SnapshotHistory {
+    interval: u64,
+    memory_cap: usize,
+    entries: Vec<HistoryEntry>,
+    /// Inputs in the order they were applied, with the step they were applied at
+    inputs: Vec<(u64, WorldInput)>,
+    /// Number of logged inputs the world currently reflects
+    applied: usize,
+}
+
+impl Default for SnapshotHistory {
+    fn default() -> Self {
+        Self::new(DEFAULT_SNAPSHOT_INTERVAL, DEFAULT_SNAPSHOT_MEMORY_CAP)
+    }
+}
+
+impl SnapshotHistory {
+    pub fn new(interval: u64, memory_cap: usize) -> Self {
+        Self {
+            interval: interval.max(1),
+            memory_cap,
+            entries: Vec::new(),
+            inputs: Vec::new(),
+            applied: 0,
+        }
+    }
+
+    pub fn interval(&self) -> u64 {
+        self.interval
+    }
+
+    pub fn set_interval(&mut self, interval: u64) {
+        self.interval = interval.max(1);
+    }
+
+    pub fn set_memory_cap(&mut self, memory_cap: usize) {
+        self.memory_cap = memory_cap;
+        self.enforce_cap();
+    }
+
+    /// Number of snapshots held
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Estimated memory held by all snapshots; the input log is a few words per step
+    pub fn memory_usage(&self) -> usize {
+        self.entries.iter().map(|e| e.snapshot.estimated_bytes()).sum()
+    }
+
+    /// Forget every snapshot and input, e.g. when the run starts over
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.inputs.clear();
+        self.applied = 0;
+    }
+
+    /// Snapshot the world unconditionally, replacing any snapshot at the same step
+    pub fn capture(&mut self, world: &MetricWorld) -> Result<(), String> {
+        self.insert(world.snapshot()?, false);
+        Ok(())
+    }
+
+    /// Snapshot the world if it sits on the interval and past the latest snapshot
+    pub fn record(&mut self, world: &MetricWorld) -> Result<(), String> {
+        let step = world.step_count();
+        let is_new = self.entries.last().is_none_or(|e| e.snapshot.step < step);
+        if step.is_multiple_of(self.interval) && is_new {
+            self.capture(world)?;
+        }
+        Ok(())
+    }
+
+    /// Apply a live input to the world and log it. Snapshots and inputs after
+    /// the current step are forgotten, since the run now diverges from them.
+    pub fn apply(&mut self, world: &mut MetricWorld, input: WorldInput) -> Result<(), String> {
+        let step = world.step_count();
+        let before = match input {
+            WorldInput::StartDrag { .. } => Some(world.snapshot()?),
+            _ => None,
+        };
+        input.apply(world)?;
+
+        self.truncate_after(step);
+        if let Some(snapshot) = before {
+            self.insert(snapshot, true);
+        }
+        // Only the last drag target within a step matters
+        let is_move = matches!(input, WorldInput::MoveDrag { .. });
+        match self.inputs.last_mut() {
+            Some((at, last @ WorldInput::MoveDrag { .. })) if *at == step && is_move => *last = input,
+            _ => self.inputs.push((step, input)),
+        }
+        self.applied = self.inputs.len();
+        Ok(())
+    }
+
+    /// Forget snapshots and inputs after `step`, e.g. once the run has diverged from them
+    pub fn truncate_after(&mut self, step: u64) {
+        self.entries.retain(|e| e.snapshot.step <= step);
+        self.inputs.retain(|(at, _)| *at <= step);
+        self.applied = self.applied.min(self.inputs.len());
+    }
+
+    /// Move the world to `step`, restoring the nearest earlier snapshot when that
+    /// is closer than the current state, then simulating forward and replaying
+    /// logged inputs. Steps beyond the latest snapshot are recorded along the way.
+    pub fn seek(&mut self, world: &mut MetricWorld, step: u64) -> Result<(), String> {
+        let current = world.step_count();
+        let nearest = self.entries.iter().rev().find(|e| e.snapshot.step <= step);
+        match nearest {
+            Some(entry) if step < current || entry.snapshot.step > current => {
+                world.restore(&entry.snapshot)?;
+                self.applied = entry.inputs;
+            }
+            None if step < current => return Err(format!("No snapshot at or before step {}", step)),
+            _ => {}
+        }
+
+        loop {
+            let now = world.step_count();
+            while let Some((_, input)) = self.inputs.get(self.applied).filter(|(at, _)| *at == now) {
+                input.apply(world)?;
+                self.applied += 1;
+            }
+            if world.step_count() >= step {
+                return Ok(());
+            }
+            world.step();
+            self.record(world)?;
+        }
+    }
+
+    fn insert(&mut self, snapshot: WorldSnapshot, pinned: bool) {
+        self.entries.retain(|e| e.snapshot.step != snapshot.step);
+        let index = self.entries.partition_point(|e| e.snapshot.step < snapshot.step);
+        self.entries.insert(index, HistoryEntry { snapshot, inputs: self.applied, pinned });
+        self.enforce_cap();
+    }
+
+    fn enforce_cap(&mut self) {
+        while self.entries.len() > 1 && self.memory_usage() > self.memory_cap {
+            // Keep the first and last snapshots, pinned ones, and every other unpinned one between them
+            let count = self.entries.len();
+            let last_step = self.entries[count - 1].snapshot.step;
+            let mut unpinned = 0;
+            self.entries.retain(|e| {
+                if e.pinned || e.snapshot.step == last_step {
+                    return true;
+                }
+                unpinned += 1;
+                unpinned % 2 == 1
+            });
+            if self.entries.len() == count {
+                break;
+            }
+            self.interval *= 2;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scenarios::BoxStackScenario;
+    use crate::Scenario;
+    use simuforge_core::PhysicsConfig;
+
+    fn stack_world() -> MetricWorld {
+        let mut world = MetricWorld::new(&PhysicsConfig::default());
+        BoxStackScenario::new(5).setup(&mut world).unwrap();
+        world
+    }
+
+    fn positions(world: &MetricWorld) -> Vec<f32> {
+        let mut out = vec![0.0; world.body_count() * crate::TRANSFORM_STRIDE];
+        world.write_body_transforms(&mut out);
+        out
+    }
+
+    #[test]
+    fn test_restore_replays_exactly() {
+        let mut world = stack_world();
+        world.run(40);
+        let snapshot = world.snapshot().unwrap();
+        world.run(80);
+        let expected = positions(&world);
+        let frames = world.frames().len();
+
+        world.restore(&snapshot).unwrap();
+        assert_eq!(world.step_count(), 40);
+        world.run(80);
+        assert_eq!(positions(&world), expected);
+        assert_eq!(world.frames().len(), frames);
+    }
+
+    #[test]
+    fn test_seek_back_and_forward() {
+        let mut world = stack_world();
+        let mut history = SnapshotHistory::new(25, usize::MAX);
+        history.capture(&world).unwrap();
+        for _ in 0..100 {
+            world.step();
+            history.record(&world).unwrap();
+        }
+        assert_eq!(history.len(), 5);
+        let at_100 = positions(&world);
+
+        history.seek(&mut world, 60).unwrap();
+        assert_eq!(world.step_count(), 60);
+        history.seek(&mut world, 100).unwrap();
+        assert_eq!(positions(&world), at_100);
+
+        // A tight cap thins the history but keeps both ends
+        history.set_memory_cap(history.memory_usage() / 2);
+        assert!(history.len() < 5 && history.interval() > 25);
+        history.seek(&mut world, 0).unwrap();
+        assert_eq!(world.step_count(), 0);
+    }
+
+    #[test]
+    fn test_seek_across_intervention_after_cap_squeeze() {
+        let mut world = stack_world();
+        let mut history = SnapshotHistory::new(10, usize::MAX);
+        history.capture(&world).unwrap();
+        for _ in 0..55 {
+            world.step();
+            history.record(&world).unwrap();
+        }
+        // Live change that only the input log lets a replay repeat
+        let gravity = WorldInput::Gravity { gravity: Vec3::new(4.0, -9.81, 0.0) };
+        history.apply(&mut world, gravity).unwrap();
+        for _ in 0..25 {
+            world.step();
+            history.record(&world).unwrap();
+        }
+        let at_80 = positions(&world);
+        for _ in 0..20 {
+            world.step();
+            history.record(&world).unwrap();
+        }
+
+        history.set_memory_cap(0);
+        assert!(history.len() < 12);
+        history.seek(&mut world, 20).unwrap();
+        history.seek(&mut world, 80).unwrap();
+        assert_eq!(positions(&world), at_80);
+    }
+
+    #[test]
+    fn test_long_drag_stays_within_memory_cap() {
+        let mut world = stack_world();
+        let snapshot_bytes = world.snapshot().unwrap().estimated_bytes();
+        let cap = snapshot_bytes * 6;
+        let mut history = SnapshotHistory::new(10, cap);
+        history.capture(&world).unwrap();
+        let (body_id, _) = world.body_list().into_iter().find(|(_, name)| *name == "box_4").unwrap();
+
+        let start = WorldInput::StartDrag {
+            body_id,
+            point: Vec3::new(0.0, 4.5, 0.0),
+            stiffness: crate::DEFAULT_DRAG_STIFFNESS,
+            damping: None,
+        };
+        history.apply(&mut world, start).unwrap();
+        let mut at_150 = Vec::new();
+        for i in 0..400 {
+            let target = Vec3::new((i as f32 * 0.05).sin(), 5.0, 0.0);
+            history.apply(&mut world, WorldInput::MoveDrag { target }).unwrap();
+            world.step();
+            history.record(&world).unwrap();
+            assert!(history.memory_usage() <= cap, "history grew to {} bytes", history.memory_usage());
+            if world.step_count() == 150 {
+                at_150 = positions(&world);
+            }
+        }
+
+        history.seek(&mut world, 150).unwrap();
+        assert_eq!(positions(&world), at_150);
+    }
+}
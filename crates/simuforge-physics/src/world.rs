@@ -1,12 +1,49 @@
 //! MetricWorld - Rapier wrapper with metric collection
 
+use rapier3d::crossbeam::channel::{unbounded, Receiver};
 use rapier3d::prelude::*;
 use simuforge_core::{
-    Vec3, Transform, MetricFrame, EnergyMetrics, MomentumMetrics, ContactMetrics,
-    metrics::BodyState, PhysicsConfig, ExperimentSpec,
+    Vec3, Transform, MetricFrame, EnergyMetrics, MomentumMetrics, ContactMetrics, ContactPairForce,
+    metrics::{BodyState, SLIP_RATIO_THRESHOLD}, PhysicsConfig, ExperimentSpec, SimEvent, EventKind,
 };
 use std::collections::HashMap;
 use std::num::NonZeroUsize;
+use crate::actions::ActiveForce;
+use crate::interaction::DragSpring;
+use crate::control::{ActuatorTarget, Controller};
+use crate::joints::NamedJoint;
+use crate::motion::KinematicMotion;
+
+/// Floats per body written by [`MetricWorld::write_body_transforms`]:
+/// position x, y, z followed by rotation quaternion x, y, z, w
+pub const TRANSFORM_STRIDE: usize = 7;
+
+/// Blend transforms written by [`MetricWorld::write_body_transforms`] one step
+/// apart: `current` is replaced by the pose `alpha` of the way from `previous`.
+/// Positions are lerped and rotations nlerped along the shorter arc.
+pub fn interpolate_transforms(previous: &[f32], current: &mut [f32], alpha: f32) {
+    let previous = previous.as_chunks::<TRANSFORM_STRIDE>().0;
+    let current = current.as_chunks_mut::<TRANSFORM_STRIDE>().0;
+    for (from, to) in previous.iter().zip(current) {
+        for i in 0..3 {
+            to[i] = from[i] + (to[i] - from[i]) * alpha;
+        }
+        let dot: f32 = (3..7).map(|i| from[i] * to[i]).sum();
+        let sign = if dot < 0.0 { -1.0 } else { 1.0 };
+        let mut rotation = [0.0; 4];
+        for (i, r) in rotation.iter_mut().enumerate() {
+            *r = from[i + 3] + (sign * to[i + 3] - from[i + 3]) * alpha;
+        }
+        let norm = rotation.iter().map(|r| r * r).sum::<f32>().sqrt();
+        if norm > f32::EPSILON {
+            for (i, r) in rotation.iter().enumerate() {
+                to[i + 3] = r / norm;
+            }
+        }
+    }
+}
+use crate::BodyBuilder;
+use simuforge_core::spec::{ActionConfig, BodyConfig};
 
 /// Physics world wrapper that collects metrics each step
 pub struct MetricWorld {
@@ -27,16 +64,43 @@ pub struct MetricWorld {
     // Simulation state
     pub current_step: u64,
     pub current_time: f32,
-    timestep: f32,
+    pub(crate) timestep: f32,
+    seed: Option<u64>,
 
     // Body tracking
-    body_names: HashMap<RigidBodyHandle, String>,
-    body_ids: HashMap<RigidBodyHandle, u64>,
-    next_body_id: u64,
+    pub(crate) body_names: HashMap<RigidBodyHandle, String>,
+    pub(crate) body_ids: HashMap<RigidBodyHandle, u64>,
+    pub(crate) next_body_id: u64,
+    /// Live bodies in ascending id order
+    pub(crate) body_order: Vec<RigidBodyHandle>,
+    /// Bumped whenever a body is added or removed
+    pub(crate) body_layout_version: u64,
 
     // Metric collection
-    frames: Vec<MetricFrame>,
+    pub(crate) frames: Vec<MetricFrame>,
     collect_body_states: bool,
+
+    // Event recording
+    event_collector: ChannelEventCollector,
+    collision_events: Receiver<CollisionEvent>,
+    contact_force_events: Receiver<ContactForceEvent>,
+    pub(crate) pending_events: Vec<SimEvent>,
+    contact_force_threshold: Option<f32>,
+
+    // Scripted actions
+    pub(crate) actions: Vec<ActionConfig>,
+    pub(crate) active_forces: Vec<ActiveForce>,
+
+    // Kinematic trajectories
+    pub(crate) motions: Vec<KinematicMotion>,
+
+    // Interactive dragging
+    pub(crate) drag: Option<DragSpring>,
+
+    // Joints and actuation
+    pub(crate) joints: Vec<NamedJoint>,
+    pub(crate) actuator_targets: Vec<ActuatorTarget>,
+    pub(crate) controllers: Vec<Box<dyn Controller>>,
 }
 
 impl MetricWorld {
@@ -44,10 +108,15 @@ impl MetricWorld {
     pub fn new(config: &PhysicsConfig) -> Self {
         let gravity = vector![config.gravity.x, config.gravity.y, config.gravity.z];
 
-        let mut integration_parameters = IntegrationParameters::default();
-        integration_parameters.dt = config.timestep;
-        integration_parameters.num_solver_iterations = NonZeroUsize::new(config.solver_iterations as usize)
-            .unwrap_or(NonZeroUsize::new(8).unwrap());
+        let integration_parameters = IntegrationParameters {
+            dt: config.timestep,
+            num_solver_iterations: NonZeroUsize::new(config.solver_iterations as usize)
+                .unwrap_or(NonZeroUsize::new(8).unwrap()),
+            ..Default::default()
+        };
+
+        let (collision_send, collision_events) = unbounded();
+        let (contact_force_send, contact_force_events) = unbounded();
 
         Self {
             rigid_body_set: RigidBodySet::new(),
@@ -65,17 +134,39 @@ impl MetricWorld {
             current_step: 0,
             current_time: 0.0,
             timestep: config.timestep,
+            seed: config.seed,
             body_names: HashMap::new(),
             body_ids: HashMap::new(),
             next_body_id: 0,
+            body_order: Vec::new(),
+            body_layout_version: 0,
             frames: Vec::new(),
             collect_body_states: true,
+            event_collector: ChannelEventCollector::new(collision_send, contact_force_send),
+            collision_events,
+            contact_force_events,
+            pending_events: Vec::new(),
+            contact_force_threshold: None,
+            actions: Vec::new(),
+            active_forces: Vec::new(),
+            motions: Vec::new(),
+            drag: None,
+            joints: Vec::new(),
+            actuator_targets: Vec::new(),
+            controllers: Vec::new(),
         }
     }
 
     /// Create world from experiment specification
     pub fn from_spec(spec: &ExperimentSpec) -> Self {
-        Self::new(&spec.spec.physics)
+        let mut world = Self::new(&spec.spec.physics);
+        world.set_contact_force_threshold(spec.spec.metrics.contact_force_threshold);
+        world
+    }
+
+    /// Random seed from the physics configuration, for scenarios that generate bodies
+    pub fn seed(&self) -> Option<u64> {
+        self.seed
     }
 
     /// Set whether to collect full body states each frame
@@ -83,6 +174,24 @@ impl MetricWorld {
         self.collect_body_states = collect;
     }
 
+    /// Record contact force events above the given threshold (`None` disables them)
+    pub fn set_contact_force_threshold(&mut self, threshold: Option<f32>) {
+        self.contact_force_threshold = threshold;
+        let events = self.active_events();
+        for (_handle, collider) in self.collider_set.iter_mut() {
+            collider.set_active_events(events);
+            collider.set_contact_force_event_threshold(threshold.unwrap_or(0.0));
+        }
+    }
+
+    fn active_events(&self) -> ActiveEvents {
+        if self.contact_force_threshold.is_some() {
+            ActiveEvents::COLLISION_EVENTS | ActiveEvents::CONTACT_FORCE_EVENTS
+        } else {
+            ActiveEvents::COLLISION_EVENTS
+        }
+    }
+
     /// Add a rigid body to the world
     pub fn add_body(&mut self, body: RigidBody, name: String) -> RigidBodyHandle {
         let handle = self.rigid_body_set.insert(body);
@@ -90,20 +199,75 @@ impl MetricWorld {
         self.next_body_id += 1;
         self.body_names.insert(handle, name);
         self.body_ids.insert(handle, id);
+        // Ids only ever increase, so pushing keeps the order sorted
+        self.body_order.push(handle);
+        self.body_layout_version += 1;
         handle
     }
 
     /// Add a collider to a body
-    pub fn add_collider(&mut self, collider: Collider, parent: RigidBodyHandle) -> ColliderHandle {
+    pub fn add_collider(&mut self, mut collider: Collider, parent: RigidBodyHandle) -> ColliderHandle {
+        collider.set_active_events(self.active_events());
+        collider.set_contact_force_event_threshold(self.contact_force_threshold.unwrap_or(0.0));
         self.collider_set.insert_with_parent(collider, parent, &mut self.rigid_body_set)
     }
 
+    /// Add a body described by a spec configuration, including its collider and motion
+    pub fn add_body_from_config(&mut self, config: &BodyConfig) -> RigidBodyHandle {
+        let (body, collider, name) = BodyBuilder::from_config(config).build();
+        let handle = self.add_body(body, name);
+        self.add_collider(collider, handle);
+        if let Some(motion) = &config.motion {
+            self.set_body_motion(handle, motion.clone());
+        }
+        handle
+    }
+
+    /// Remove a body together with its colliders and joints
+    pub fn remove_body(&mut self, handle: RigidBodyHandle) -> Option<RigidBody> {
+        self.body_names.remove(&handle);
+        self.body_ids.remove(&handle);
+        self.body_order.retain(|h| *h != handle);
+        self.body_layout_version += 1;
+        self.active_forces.retain(|f| f.handle != handle);
+        self.motions.retain(|m| m.handle != handle);
+        if self.drag.as_ref().is_some_and(|d| d.handle == handle) {
+            self.drag = None;
+        }
+        let removed = self.rigid_body_set.remove(
+            handle,
+            &mut self.island_manager,
+            &mut self.collider_set,
+            &mut self.impulse_joint_set,
+            &mut self.multibody_joint_set,
+            true,
+        );
+
+        let joints: Vec<_> = std::mem::take(&mut self.joints)
+            .into_iter()
+            .filter(|j| self.joint_exists(j.handle))
+            .collect();
+        self.joints = joints;
+        let joints = &self.joints;
+        self.actuator_targets.retain(|t| joints.iter().any(|j| j.name == t.joint));
+        removed
+    }
+
     /// Step the simulation forward
     pub fn step(&mut self) {
+        // Scripted actions take effect before this step's state is recorded
+        self.run_due_actions();
+        self.apply_active_forces();
+        self.apply_drag();
+        self.run_controllers();
+
         // Collect pre-step metrics
         let frame = self.collect_metrics();
+        self.pending_events.clear();
         self.frames.push(frame);
 
+        self.update_kinematic_motions();
+
         // Step physics
         self.physics_pipeline.step(
             &self.gravity,
@@ -118,11 +282,73 @@ impl MetricWorld {
             &mut self.ccd_solver,
             Some(&mut self.query_pipeline),
             &(),
-            &(),
+            &self.event_collector,
         );
 
         self.current_step += 1;
         self.current_time += self.timestep;
+
+        self.drain_events();
+    }
+
+    /// Convert queued Rapier events into named simulation events.
+    ///
+    /// Events are stamped with the post-step time and attached to the next
+    /// collected frame.
+    fn drain_events(&mut self) {
+        while let Ok(event) = self.collision_events.try_recv() {
+            let (Some(name_a), Some(name_b)) = (
+                self.collider_body_name(event.collider1()),
+                self.collider_body_name(event.collider2()),
+            ) else {
+                continue;
+            };
+
+            let kind = if event.sensor() {
+                let sensor_first = self.collider_set
+                    .get(event.collider1())
+                    .map(|c| c.is_sensor())
+                    .unwrap_or(false);
+                let (sensor, body) = if sensor_first { (name_a, name_b) } else { (name_b, name_a) };
+                if event.started() {
+                    EventKind::SensorEntered { sensor, body }
+                } else {
+                    EventKind::SensorExited { sensor, body }
+                }
+            } else if event.started() {
+                EventKind::CollisionStarted { body_a: name_a, body_b: name_b }
+            } else {
+                EventKind::CollisionStopped { body_a: name_a, body_b: name_b }
+            };
+
+            self.pending_events.push(SimEvent::new(self.current_step, self.current_time, kind));
+        }
+
+        while let Ok(event) = self.contact_force_events.try_recv() {
+            let (Some(body_a), Some(body_b)) = (
+                self.collider_body_name(event.collider1),
+                self.collider_body_name(event.collider2),
+            ) else {
+                continue;
+            };
+
+            self.pending_events.push(SimEvent::new(
+                self.current_step,
+                self.current_time,
+                EventKind::ContactForce {
+                    body_a,
+                    body_b,
+                    total_force_magnitude: event.total_force_magnitude,
+                    max_force_magnitude: event.max_force_magnitude,
+                },
+            ));
+        }
+    }
+
+    /// Name of the body a collider is attached to
+    pub(crate) fn collider_body_name(&self, collider: ColliderHandle) -> Option<String> {
+        let parent = self.collider_set.get(collider)?.parent()?;
+        self.body_names.get(&parent).cloned()
     }
 
     /// Run simulation for specified number of steps
@@ -130,8 +356,13 @@ impl MetricWorld {
         for _ in 0..steps {
             self.step();
         }
-        // Collect final frame
+        self.record_frame();
+    }
+
+    /// Collect a frame of the current state, as `run` does after its last step
+    pub fn record_frame(&mut self) {
         let frame = self.collect_metrics();
+        self.pending_events.clear();
         self.frames.push(frame);
     }
 
@@ -145,6 +376,11 @@ impl MetricWorld {
         self.collect_metrics()
     }
 
+    /// Iterate over all events recorded in collected frames
+    pub fn events(&self) -> impl Iterator<Item = &SimEvent> {
+        self.frames.iter().flat_map(|f| f.events.iter())
+    }
+
     /// Clear collected frames
     pub fn clear_frames(&mut self) {
         self.frames.clear();
@@ -163,11 +399,18 @@ impl MetricWorld {
         // Contact metrics
         frame.contacts = self.compute_contacts();
 
+        frame.gravity = Vec3::from_nalgebra(&self.gravity);
+
         // Body states
         if self.collect_body_states {
             frame.bodies = self.collect_body_states();
         }
 
+        // Events raised by the step that led to this state
+        frame.events = self.pending_events.clone();
+
+        frame.actuators = self.collect_actuator_states();
+
         frame
     }
 
@@ -176,7 +419,7 @@ impl MetricWorld {
         let mut kinetic = 0.0f32;
         let mut potential = 0.0f32;
 
-        for (handle, body) in self.rigid_body_set.iter() {
+        for (_handle, body) in self.rigid_body_set.iter() {
             if body.is_dynamic() {
                 let mass = body.mass();
                 let vel = body.linvel();
@@ -223,7 +466,7 @@ impl MetricWorld {
         )
     }
 
-    /// Compute contact metrics
+    /// Compute contact metrics, including the impulses applied by the last step
     fn compute_contacts(&self) -> ContactMetrics {
         let mut metrics = ContactMetrics::default();
 
@@ -231,15 +474,52 @@ impl MetricWorld {
             if pair.has_any_active_contact {
                 metrics.contact_count += 1;
 
+                let mut pair_normal_impulse = 0.0f32;
+                let mut pair_tangent_impulse = 0.0f32;
+
                 for manifold in pair.manifolds.iter() {
+                    // Every solver contact of a manifold shares the combined friction coefficient
+                    let friction = manifold.data.solver_contacts.first().map(|c| c.friction);
+
                     for point in manifold.points.iter() {
                         let penetration = -point.dist;
                         if penetration > 0.0 {
                             metrics.max_penetration = metrics.max_penetration.max(penetration);
                             metrics.total_penetration += penetration;
                         }
+
+                        let normal = point.data.impulse.abs();
+                        let tangent = point.data.tangent_impulse.norm();
+                        pair_normal_impulse += normal;
+                        pair_tangent_impulse += tangent;
+                        metrics.max_normal_impulse = metrics.max_normal_impulse.max(normal);
+                        metrics.max_tangent_impulse = metrics.max_tangent_impulse.max(tangent);
+
+                        if let Some(mu) = friction {
+                            if mu > 0.0 && normal > 1e-6 {
+                                let ratio = tangent / (mu * normal);
+                                metrics.max_friction_ratio = metrics.max_friction_ratio.max(ratio);
+                                if ratio >= SLIP_RATIO_THRESHOLD {
+                                    metrics.slipping_contacts += 1;
+                                }
+                            }
+                        }
                     }
                 }
+
+                metrics.total_normal_impulse += pair_normal_impulse;
+
+                if let (Some(body_a), Some(body_b)) = (
+                    self.collider_body_name(pair.collider1),
+                    self.collider_body_name(pair.collider2),
+                ) {
+                    metrics.pairs.push(ContactPairForce {
+                        body_a,
+                        body_b,
+                        normal_force: pair_normal_impulse / self.timestep,
+                        tangent_force: pair_tangent_impulse / self.timestep,
+                    });
+                }
             }
         }
 
@@ -266,20 +546,60 @@ impl MetricWorld {
             .collect()
     }
 
-    /// Get body by name
-    pub fn get_body_by_name(&self, name: &str) -> Option<&RigidBody> {
+    /// Ids and names of all bodies, in the order used by [`Self::write_body_transforms`]
+    pub fn body_list(&self) -> Vec<(u64, &str)> {
+        self.body_order
+            .iter()
+            .filter_map(|handle| Some((*self.body_ids.get(handle)?, self.body_names.get(handle)?.as_str())))
+            .collect()
+    }
+
+    /// Counter that changes whenever bodies are added or removed, so cached
+    /// body lists can be refreshed
+    pub fn body_layout_version(&self) -> u64 {
+        self.body_layout_version
+    }
+
+    /// Write each body's position and rotation into `out`, [`TRANSFORM_STRIDE`]
+    /// floats per body in [`Self::body_list`] order, without allocating.
+    /// Returns the number of bodies written; stops early if `out` is too short.
+    pub fn write_body_transforms(&self, out: &mut [f32]) -> usize {
+        let bodies = self.body_order.iter().filter_map(|handle| self.rigid_body_set.get(*handle));
+        let mut written = 0;
+        for (body, chunk) in bodies.zip(out.as_chunks_mut::<TRANSFORM_STRIDE>().0) {
+            let position = body.position();
+            let (t, r) = (position.translation.vector, position.rotation);
+            chunk.copy_from_slice(&[t.x, t.y, t.z, r.i, r.j, r.k, r.w]);
+            written += 1;
+        }
+        written
+    }
+
+    /// Get body handle by name
+    pub fn handle_by_name(&self, name: &str) -> Option<RigidBodyHandle> {
         self.body_names
             .iter()
             .find(|(_, n)| *n == name)
-            .and_then(|(handle, _)| self.rigid_body_set.get(*handle))
+            .map(|(handle, _)| *handle)
+    }
+
+    /// Get body handle by id
+    pub fn handle_by_id(&self, id: u64) -> Option<RigidBodyHandle> {
+        self.body_ids
+            .iter()
+            .find(|(_, i)| **i == id)
+            .map(|(handle, _)| *handle)
+    }
+
+    /// Get body by name
+    pub fn get_body_by_name(&self, name: &str) -> Option<&RigidBody> {
+        self.handle_by_name(name)
+            .and_then(|handle| self.rigid_body_set.get(handle))
     }
 
     /// Get mutable body by name
     pub fn get_body_by_name_mut(&mut self, name: &str) -> Option<&mut RigidBody> {
-        let handle = self.body_names
-            .iter()
-            .find(|(_, n)| *n == name)
-            .map(|(handle, _)| *handle)?;
+        let handle = self.handle_by_name(name)?;
         self.rigid_body_set.get_mut(handle)
     }
 
@@ -288,6 +608,11 @@ impl MetricWorld {
         self.rigid_body_set.len()
     }
 
+    /// Get the fixed timestep
+    pub fn timestep(&self) -> f32 {
+        self.timestep
+    }
+
     /// Get current simulation time
     pub fn time(&self) -> f32 {
         self.current_time
@@ -324,6 +649,41 @@ mod tests {
         assert_eq!(world.body_count(), 1);
     }
 
+    #[test]
+    fn test_body_transforms_follow_id_order() {
+        let mut world = MetricWorld::new(&PhysicsConfig::default());
+        let names = ["a", "b", "c"];
+        let handles: Vec<_> = names
+            .iter()
+            .enumerate()
+            .map(|(i, name)| {
+                let body = RigidBodyBuilder::fixed().translation(vector![i as f32, 0.0, 0.0]).build();
+                world.add_body(body, name.to_string())
+            })
+            .collect();
+        world.remove_body(handles[1]);
+
+        assert_eq!(world.body_list(), vec![(0, "a"), (2, "c")]);
+        let mut out = [0.0; 3 * TRANSFORM_STRIDE];
+        assert_eq!(world.write_body_transforms(&mut out), 2);
+        assert_eq!(out[TRANSFORM_STRIDE], 2.0);
+        assert_eq!(out[2 * TRANSFORM_STRIDE - 1], 1.0);
+        assert_eq!(world.body_layout_version(), 4);
+    }
+
+    #[test]
+    fn test_interpolate_transforms() {
+        let s = std::f32::consts::FRAC_1_SQRT_2;
+        let previous = [0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 1.0];
+        // Quarter turn about Y, given with a negated quaternion
+        let mut current = [2.0, 4.0, 0.0, 0.0, -s, 0.0, -s];
+        interpolate_transforms(&previous, &mut current, 0.5);
+
+        assert_eq!(&current[..3], &[1.0, 2.0, 0.0]);
+        let eighth_turn = (std::f32::consts::PI / 8.0).sin();
+        assert!((current[4] - eighth_turn).abs() < 1e-5, "{:?}", current);
+    }
+
     #[test]
     fn test_step_simulation() {
         let config = PhysicsConfig::default();
@@ -341,4 +701,74 @@ mod tests {
         assert_eq!(world.step_count(), 1);
         assert_eq!(world.frames().len(), 1);
     }
+
+    #[test]
+    fn test_collision_events_recorded() {
+        let config = PhysicsConfig::default();
+        let mut world = MetricWorld::new(&config);
+
+        let ground = world.add_body(RigidBodyBuilder::fixed().build(), "ground".to_string());
+        world.add_collider(ColliderBuilder::cuboid(5.0, 0.5, 5.0).build(), ground);
+
+        let ball = world.add_body(
+            RigidBodyBuilder::dynamic().translation(vector![0.0, 2.0, 0.0]).build(),
+            "ball".to_string(),
+        );
+        world.add_collider(ColliderBuilder::ball(0.5).restitution(0.0).build(), ball);
+
+        world.run(120);
+
+        let started = world.events().filter(|e| matches!(
+            &e.kind,
+            EventKind::CollisionStarted { body_a, body_b }
+                if (body_a == "ball" && body_b == "ground") || (body_a == "ground" && body_b == "ball")
+        )).count();
+        assert_eq!(started, 1);
+    }
+
+    #[test]
+    fn test_contact_force_events_above_threshold() {
+        let config = PhysicsConfig::default();
+        let mut world = MetricWorld::new(&config);
+        world.set_contact_force_threshold(Some(1.0));
+
+        let ground = world.add_body(RigidBodyBuilder::fixed().build(), "ground".to_string());
+        world.add_collider(ColliderBuilder::cuboid(5.0, 0.5, 5.0).build(), ground);
+
+        let ball = world.add_body(
+            RigidBodyBuilder::dynamic().translation(vector![0.0, 2.0, 0.0]).build(),
+            "ball".to_string(),
+        );
+        world.add_collider(ColliderBuilder::ball(0.5).build(), ball);
+
+        world.run(120);
+
+        assert!(world.events().any(|e| matches!(
+            e.kind,
+            EventKind::ContactForce { max_force_magnitude, .. } if max_force_magnitude > 1.0
+        )));
+    }
+
+    #[test]
+    fn test_resting_contact_force_matches_weight() {
+        let config = PhysicsConfig::default();
+        let mut world = MetricWorld::new(&config);
+
+        let ground = world.add_body(RigidBodyBuilder::fixed().build(), "ground".to_string());
+        world.add_collider(ColliderBuilder::cuboid(5.0, 0.5, 5.0).build(), ground);
+
+        let block = world.add_body(
+            RigidBodyBuilder::dynamic().translation(vector![0.0, 1.0, 0.0]).build(),
+            "block".to_string(),
+        );
+        world.add_collider(ColliderBuilder::cuboid(0.5, 0.5, 0.5).density(1.0).build(), block);
+
+        world.run(120);
+
+        let weight = world.rigid_body_set[block].mass() * 9.81;
+        let frame = world.frames().last().unwrap();
+        let pair = frame.contacts.pairs.iter().find(|p| p.body_a == "block" || p.body_b == "block").unwrap();
+        assert!((pair.normal_force - weight).abs() / weight < 0.15);
+        assert_eq!(frame.contacts.slipping_contacts, 0);
+    }
 }
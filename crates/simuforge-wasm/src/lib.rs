@@ -1,18 +1,13 @@
 //! SimuForge WASM - WebAssembly bindings for browser-based physics simulation
 
 use wasm_bindgen::prelude::*;
-use simuforge_core::{ExperimentSpec, MetricFrame, SimulationReport, Transform};
-use simuforge_physics::{MetricWorld, create_scenario};
+use simuforge_core::{ContactMetrics, EnergyMetrics, ExperimentSpec, MetricFrame, MomentumMetrics, SimulationReport, Vec3};
+use simuforge_physics::{
+    ExperimentRunner, SnapshotHistory, WorldInput, DEFAULT_DRAG_STIFFNESS, TRANSFORM_STRIDE, interpolate_transforms,
+};
 
-#[wasm_bindgen]
-extern "C" {
-    #[wasm_bindgen(js_namespace = console)]
-    fn log(s: &str);
-}
-
-macro_rules! console_log {
-    ($($t:tt)*) => (log(&format_args!($($t)*).to_string()))
-}
+/// Default cap on the fixed steps one `advance` call may run
+const DEFAULT_MAX_SUBSTEPS: u32 = 8;
 
 /// Initialize panic hook for better error messages
 #[wasm_bindgen(start)]
@@ -24,9 +19,17 @@ pub fn init() {
 /// Physics simulation instance for browser use
 #[wasm_bindgen]
 pub struct Simulation {
-    world: MetricWorld,
-    spec: ExperimentSpec,
-    target_steps: u64,
+    runner: ExperimentRunner,
+    /// Reused by `update_body_transforms` so rendering does not allocate
+    transform_buffer: Vec<f32>,
+    /// Real time not yet simulated by `advance`
+    accumulator: f32,
+    max_substeps: u32,
+    /// Transforms one step before the current state, for interpolation
+    previous_transforms: Vec<f32>,
+    previous_layout_version: u64,
+    /// Snapshots and live inputs for seeking back to earlier steps
+    history: SnapshotHistory,
 }
 
 #[wasm_bindgen]
@@ -40,43 +43,99 @@ impl Simulation {
         spec.validate()
             .map_err(|errors| JsError::new(&format!("Invalid spec: {}", errors.join(", "))))?;
 
-        let mut world = MetricWorld::from_spec(&spec);
-
-        let scenario = create_scenario(&spec.spec.scenario);
-        scenario.setup(&mut world);
-
-        let target_steps = match &spec.spec.duration {
-            simuforge_core::spec::DurationConfig::Fixed { steps } => *steps,
-            simuforge_core::spec::DurationConfig::Time { seconds } => {
-                (*seconds / spec.spec.physics.timestep) as u64
-            }
-            simuforge_core::spec::DurationConfig::UntilStable { max_steps, .. } => *max_steps,
-        };
+        let runner = ExperimentRunner::new(spec)
+            .map_err(|e| JsError::new(&format!("Failed to set up scenario: {}", e)))?;
 
+        let transform_buffer = vec![0.0; runner.world().body_count() * TRANSFORM_STRIDE];
+        let mut history = SnapshotHistory::default();
+        history.capture(runner.world()).map_err(|e| JsError::new(&e))?;
         Ok(Simulation {
-            world,
-            spec,
-            target_steps,
+            runner,
+            transform_buffer,
+            accumulator: 0.0,
+            max_substeps: DEFAULT_MAX_SUBSTEPS,
+            previous_transforms: Vec::new(),
+            previous_layout_version: 0,
+            history,
         })
     }
 
     /// Step the simulation forward by one frame
     pub fn step(&mut self) -> JsValue {
-        self.world.step();
-        let frame = self.world.current_frame();
+        self.step_live(1);
+        let frame = self.runner.world().current_frame();
         serde_wasm_bindgen::to_value(&frame).unwrap_or(JsValue::NULL)
     }
 
+    /// Step the simulation forward by `n` frames and return a summary of the last one
+    pub fn step_n(&mut self, n: u32) -> JsValue {
+        self.step_live(n as u64);
+        self.get_summary()
+    }
+
+    /// Advance by `real_dt` seconds of wall time, running as many fixed steps
+    /// as have accumulated, up to `max_substeps`. Time beyond the cap is dropped
+    /// so a slow frame cannot snowball. Returns the number of steps run.
+    pub fn advance(&mut self, real_dt: f32) -> u32 {
+        let timestep = self.runner.world().timestep();
+        let remaining = self.runner.remaining_steps();
+        self.accumulator += real_dt.max(0.0);
+
+        let wanted = (self.accumulator / timestep) as u64;
+        let steps = wanted.min(self.max_substeps as u64).min(remaining);
+        if steps > 0 {
+            self.step_live(steps - 1);
+            self.previous_transforms.resize(self.runner.world().body_count() * TRANSFORM_STRIDE, 0.0);
+            self.runner.world().write_body_transforms(&mut self.previous_transforms);
+            self.previous_layout_version = self.runner.world().body_layout_version();
+            self.step_live(1);
+        }
+
+        self.accumulator -= steps as f32 * timestep;
+        if wanted > steps {
+            self.accumulator %= timestep;
+        }
+        if steps == remaining {
+            self.accumulator = 0.0;
+        }
+        steps as u32
+    }
+
+    /// Cap on the fixed steps one `advance` call may run
+    pub fn set_max_substeps(&mut self, max_substeps: u32) {
+        self.max_substeps = max_substeps.max(1);
+    }
+
+    /// Fraction of a step accumulated by `advance` but not yet simulated,
+    /// for blending between the previous and current state
+    pub fn interpolation_alpha(&self) -> f32 {
+        (self.accumulator / self.runner.world().timestep()).clamp(0.0, 1.0)
+    }
+
+    /// Get step, time, energy, momentum and contact totals of the latest frame,
+    /// without body states or contact pairs
+    pub fn get_summary(&self) -> JsValue {
+        let current;
+        let frame = match self.runner.world().frames().last() {
+            Some(frame) => frame,
+            None => {
+                current = self.runner.world().current_frame();
+                &current
+            }
+        };
+        serde_wasm_bindgen::to_value(&FrameSummary::from(frame)).unwrap_or(JsValue::NULL)
+    }
+
     /// Get current metric frame
     pub fn get_frame(&self) -> JsValue {
-        let frame = self.world.current_frame();
+        let frame = self.runner.world().current_frame();
         serde_wasm_bindgen::to_value(&frame).unwrap_or(JsValue::NULL)
     }
 
     /// Get transforms of all bodies for rendering
     pub fn get_body_transforms(&self) -> JsValue {
         // Use current_frame which reads directly from physics world
-        let frame = self.world.current_frame();
+        let frame = self.runner.world().current_frame();
         let transforms: Vec<BodyTransform> = frame.bodies.iter().map(|b| BodyTransform {
             id: b.id,
             name: b.name.clone(),
@@ -92,51 +151,295 @@ impl Simulation {
         serde_wasm_bindgen::to_value(&transforms).unwrap_or(JsValue::NULL)
     }
 
-    /// Run simulation to completion and return report
-    pub fn run_to_completion(&mut self) -> JsValue {
-        let remaining = self.target_steps.saturating_sub(self.world.step_count());
-        self.world.run(remaining);
+    /// Get every body's type, pose and collider shapes with their offsets;
+    /// heightfields and triangle meshes come with vertex and index buffers
+    pub fn get_scene_description(&self) -> JsValue {
+        serde_wasm_bindgen::to_value(&self.runner.world().scene_description()).unwrap_or(JsValue::NULL)
+    }
+
+    /// Get contact points and normals, collider AABBs, joint anchors and axes,
+    /// and centers of mass with sleeping state, for a debug overlay
+    pub fn get_debug_geometry(&self) -> JsValue {
+        serde_wasm_bindgen::to_value(&self.runner.world().debug_geometry()).unwrap_or(JsValue::NULL)
+    }
+
+    /// Get ids and names of all bodies, in the order of `update_body_transforms`.
+    /// Only changes when `body_layout_version` does.
+    pub fn get_body_list(&self) -> JsValue {
+        let bodies: Vec<BodyInfo> = self.runner.world().body_list()
+            .into_iter()
+            .map(|(id, name)| BodyInfo { id, name: name.to_string() })
+            .collect();
+        serde_wasm_bindgen::to_value(&bodies).unwrap_or(JsValue::NULL)
+    }
 
-        let frames = self.world.frames();
-        let mut report = SimulationReport::new(self.spec.metadata.name.clone());
-        report.finalize(frames, &self.spec.spec.criteria);
+    /// Counter that changes when bodies are spawned or removed
+    pub fn body_layout_version(&self) -> u64 {
+        self.runner.world().body_layout_version()
+    }
+
+    /// Number of floats per body in `update_body_transforms`
+    pub fn transform_stride(&self) -> usize {
+        TRANSFORM_STRIDE
+    }
+
+    /// Write all body positions and rotations into a buffer owned by the
+    /// simulation and return a view of it, `transform_stride()` floats per body
+    /// (x, y, z, qx, qy, qz, qw) in `get_body_list` order.
+    ///
+    /// The view points straight into WASM memory: read it before calling into
+    /// the module again, since any allocation may move or detach it.
+    pub fn update_body_transforms(&mut self) -> js_sys::Float32Array {
+        let len = self.fill_transform_buffer();
+        // SAFETY: nothing allocates between creating the view and returning it
+        unsafe { js_sys::Float32Array::view(&self.transform_buffer[..len]) }
+    }
+
+    /// Like `update_body_transforms`, but blended `interpolation_alpha` of the
+    /// way from the state before the last step run by `advance`
+    pub fn update_interpolated_body_transforms(&mut self) -> js_sys::Float32Array {
+        let len = self.fill_transform_buffer();
+        // Bodies spawned or removed since then have nothing to blend with
+        if self.previous_layout_version == self.runner.world().body_layout_version() && self.previous_transforms.len() == len {
+            let alpha = self.interpolation_alpha();
+            interpolate_transforms(&self.previous_transforms, &mut self.transform_buffer[..len], alpha);
+        }
+        // SAFETY: nothing allocates between creating the view and returning it
+        unsafe { js_sys::Float32Array::view(&self.transform_buffer[..len]) }
+    }
 
+    /// Cast a ray, e.g. from the camera through the cursor, and return the
+    /// closest hit as `{ body_id, body, point, normal, distance }`, or null
+    #[allow(clippy::too_many_arguments)]
+    pub fn raycast(&mut self, ox: f32, oy: f32, oz: f32, dx: f32, dy: f32, dz: f32, max_distance: f32) -> JsValue {
+        match self.runner.world_mut().raycast(Vec3::new(ox, oy, oz), Vec3::new(dx, dy, dz), max_distance) {
+            Some(hit) => serde_wasm_bindgen::to_value(&hit).unwrap_or(JsValue::NULL),
+            None => JsValue::NULL,
+        }
+    }
+
+    /// Grab a body at a world-space point with a spring that follows `drag_to`.
+    /// Stiffness is an acceleration per metre (default 100); damping defaults to critical.
+    pub fn start_drag(
+        &mut self,
+        body_id: u64,
+        x: f32,
+        y: f32,
+        z: f32,
+        stiffness: Option<f32>,
+        damping: Option<f32>,
+    ) -> Result<(), JsError> {
+        let stiffness = stiffness.unwrap_or(DEFAULT_DRAG_STIFFNESS);
+        self.apply_input(WorldInput::StartDrag { body_id, point: Vec3::new(x, y, z), stiffness, damping })
+    }
+
+    /// Move the point the dragged body is pulled towards
+    pub fn drag_to(&mut self, x: f32, y: f32, z: f32) {
+        // Moving or releasing without a drag is a no-op, so neither can fail
+        let _ = self.apply_input(WorldInput::MoveDrag { target: Vec3::new(x, y, z) });
+    }
+
+    /// Release the dragged body
+    pub fn end_drag(&mut self) {
+        let _ = self.apply_input(WorldInput::EndDrag);
+    }
+
+    /// Apply an impulse at a world-space point of a body
+    #[allow(clippy::too_many_arguments)]
+    pub fn apply_impulse_at(
+        &mut self,
+        body_id: u64,
+        ix: f32,
+        iy: f32,
+        iz: f32,
+        px: f32,
+        py: f32,
+        pz: f32,
+    ) -> Result<(), JsError> {
+        self.apply_input(WorldInput::Impulse {
+            body_id,
+            impulse: Vec3::new(ix, iy, iz),
+            point: Vec3::new(px, py, pz),
+        })
+    }
+
+    /// Change gravity from the next step on; recorded in the report
+    pub fn set_gravity(&mut self, x: f32, y: f32, z: f32) -> Result<(), JsError> {
+        self.apply_input(WorldInput::Gravity { gravity: Vec3::new(x, y, z) })
+    }
+
+    /// Change the fixed timestep from the next step on; recorded in the report
+    pub fn set_timestep(&mut self, timestep: f32) -> Result<(), JsError> {
+        self.apply_input(WorldInput::Timestep { timestep })
+    }
+
+    /// Change solver iterations from the next step on; recorded in the report
+    pub fn set_solver_iterations(&mut self, iterations: u32) -> Result<(), JsError> {
+        self.apply_input(WorldInput::SolverIterations { iterations })
+    }
+
+    /// Change any of a body's friction, restitution and density; recorded in the report
+    pub fn set_body_material(
+        &mut self,
+        body: &str,
+        friction: Option<f32>,
+        restitution: Option<f32>,
+        density: Option<f32>,
+    ) -> Result<(), JsError> {
+        self.apply_input(WorldInput::BodyMaterial { body: body.to_string(), friction, restitution, density })
+    }
+
+    /// Change a body's total mass; recorded in the report
+    pub fn set_body_mass(&mut self, body: &str, mass: f32) -> Result<(), JsError> {
+        self.apply_input(WorldInput::BodyMass { body: body.to_string(), mass })
+    }
+
+    /// Run simulation to completion and return report
+    pub fn run_to_completion(&mut self) -> JsValue {
+        let report = self.finish();
         serde_wasm_bindgen::to_value(&report).unwrap_or(JsValue::NULL)
     }
 
     /// Get current step number
     pub fn current_step(&self) -> u64 {
-        self.world.step_count()
+        self.runner.world().step_count()
     }
 
     /// Get current simulation time
     pub fn current_time(&self) -> f32 {
-        self.world.time()
+        self.runner.world().time()
     }
 
     /// Get target step count
     pub fn target_steps(&self) -> u64 {
-        self.target_steps
+        self.runner.target_steps()
     }
 
     /// Check if simulation is complete
     pub fn is_complete(&self) -> bool {
-        self.world.step_count() >= self.target_steps
+        self.runner.is_complete()
     }
 
     /// Get number of bodies
     pub fn body_count(&self) -> usize {
-        self.world.body_count()
+        self.runner.world().body_count()
     }
 
-    /// Reset simulation to initial state
+    /// Reset simulation to initial state, undoing live parameter changes
+    /// and clearing the snapshot history
     pub fn reset(&mut self) {
-        self.world = MetricWorld::from_spec(&self.spec);
-        let scenario = create_scenario(&self.spec.spec.scenario);
-        scenario.setup(&mut self.world);
+        // The spec was already set up once in `new`, so this cannot fail
+        let _ = self.runner.reset();
+        self.history.clear();
+        let _ = self.history.capture(self.runner.world());
+        self.accumulator = 0.0;
+        self.previous_transforms.clear();
+    }
+
+    /// Jump to any step up to the target, restoring the nearest earlier snapshot
+    /// and re-simulating from it with drags, impulses and parameter changes
+    /// replayed. Later history is kept, so scrubbing back and forth stays cheap
+    /// until playback resumes from the new position.
+    pub fn seek(&mut self, step: u64) -> Result<(), JsError> {
+        let step = step.min(self.runner.target_steps());
+        self.history.seek(self.runner.world_mut(), step)
+            .map_err(|e| JsError::new(&e))?;
+        self.accumulator = 0.0;
+        self.previous_transforms.clear();
+        Ok(())
+    }
+
+    /// Go back a single step
+    pub fn step_back(&mut self) -> Result<(), JsError> {
+        self.seek(self.runner.world().step_count().saturating_sub(1))
+    }
+
+    /// Steps between periodic snapshots (default 60)
+    pub fn set_snapshot_interval(&mut self, steps: u32) {
+        self.history.set_interval(steps as u64);
+    }
+
+    /// Approximate memory budget for snapshots in bytes (default 64 MiB);
+    /// beyond it older snapshots are thinned out, except those taken when a drag starts
+    pub fn set_snapshot_memory_cap(&mut self, bytes: usize) {
+        self.history.set_memory_cap(bytes);
+    }
+
+    /// Number of snapshots held
+    pub fn snapshot_count(&self) -> usize {
+        self.history.len()
+    }
+
+    /// Approximate memory held by snapshots in bytes
+    pub fn snapshot_memory(&self) -> usize {
+        self.history.memory_usage()
     }
 }
 
+impl Simulation {
+    /// Step forward as playback, recording snapshots along the way
+    fn step_live(&mut self, steps: u64) {
+        // Playing on from an earlier step starts a new future
+        self.history.truncate_after(self.runner.world().step_count());
+        for _ in 0..steps {
+            self.runner.world_mut().step();
+            // Only controllers can refuse a snapshot, and specs cannot add any
+            let _ = self.history.record(self.runner.world());
+        }
+    }
+
+    /// Run the remaining steps and build the report
+    fn finish(&mut self) -> SimulationReport {
+        self.step_live(self.runner.remaining_steps());
+        self.runner.finish()
+    }
+
+    /// Apply a drag, impulse or parameter change and log it for seeking
+    fn apply_input(&mut self, input: WorldInput) -> Result<(), JsError> {
+        self.history.apply(self.runner.world_mut(), input).map_err(|e| JsError::new(&e))
+    }
+
+    /// Write current transforms into the reused buffer, returning the filled length
+    fn fill_transform_buffer(&mut self) -> usize {
+        let len = self.runner.world().body_count() * TRANSFORM_STRIDE;
+        if self.transform_buffer.len() != len {
+            self.transform_buffer.resize(len, 0.0);
+        }
+        self.runner.world().write_body_transforms(&mut self.transform_buffer) * TRANSFORM_STRIDE
+    }
+}
+
+/// Frame totals without per-body data, cheap to pass to JS every render frame
+#[derive(serde::Serialize)]
+struct FrameSummary {
+    step: u64,
+    time: f32,
+    energy: EnergyMetrics,
+    momentum: MomentumMetrics,
+    contacts: ContactMetrics,
+}
+
+impl From<&MetricFrame> for FrameSummary {
+    fn from(frame: &MetricFrame) -> Self {
+        let mut contacts = frame.contacts.clone();
+        contacts.pairs.clear();
+        Self {
+            step: frame.step,
+            time: frame.time,
+            energy: frame.energy.clone(),
+            momentum: frame.momentum.clone(),
+            contacts,
+        }
+    }
+}
+
+/// Body id and name, fetched once per body layout
+#[derive(serde::Serialize)]
+struct BodyInfo {
+    id: u64,
+    name: String,
+}
+
 /// Body transform for rendering
 #[derive(serde::Serialize)]
 struct BodyTransform {
@@ -199,7 +502,52 @@ pub fn get_available_scenarios() -> JsValue {
         ScenarioInfo {
             name: "friction_ramp".to_string(),
             description: "Object sliding down inclined ramp".to_string(),
-            params: vec!["ramp_angle", "ramp_length", "friction"],
+            params: vec!["ramp_angle", "ramp_length", "friction", "start"],
+        },
+        ScenarioInfo {
+            name: "newtons_cradle".to_string(),
+            description: "Row of pendulum spheres transferring momentum".to_string(),
+            params: vec!["count", "radius", "gap", "string_length", "restitution", "raised", "raise_angle"],
+        },
+        ScenarioInfo {
+            name: "box_pyramid".to_string(),
+            description: "Pyramid of boxes on ground plane".to_string(),
+            params: vec!["base_width", "box_size", "brick_offset", "spacing", "friction", "collapse_threshold"],
+        },
+        ScenarioInfo {
+            name: "brick_wall".to_string(),
+            description: "Staggered brick wall on ground plane".to_string(),
+            params: vec!["rows", "columns", "brick_size", "spacing", "friction", "collapse_threshold"],
+        },
+        ScenarioInfo {
+            name: "domino_chain".to_string(),
+            description: "Chain of dominoes toppling in sequence".to_string(),
+            params: vec!["count", "domino_size", "spacing", "curve_radius", "push_angular_velocity", "tip_angle", "friction"],
+        },
+        ScenarioInfo {
+            name: "simple_pendulum".to_string(),
+            description: "Pendulum compared against its analytic period".to_string(),
+            params: vec!["length", "bob_radius", "initial_angle", "density"],
+        },
+        ScenarioInfo {
+            name: "double_pendulum".to_string(),
+            description: "Chaotic double pendulum on chained joints".to_string(),
+            params: vec!["length1", "length2", "bob_radius", "angle1", "angle2", "density"],
+        },
+        ScenarioInfo {
+            name: "granular_pile".to_string(),
+            description: "Seeded grains poured through a hopper or into a bin".to_string(),
+            params: vec!["count", "grain_radius", "shape", "container", "container_half_width", "outlet_half_width", "hopper_height", "friction", "rolling_damping", "seed"],
+        },
+        ScenarioInfo {
+            name: "articulated_chain".to_string(),
+            description: "Hinged chain built from multibody or impulse joints".to_string(),
+            params: vec!["links", "link_length", "link_width", "density", "initial_angle", "formulation"],
+        },
+        ScenarioInfo {
+            name: "vehicle".to_string(),
+            description: "Motor-driven four-wheeled vehicle over bumps or a heightfield".to_string(),
+            params: vec!["chassis_size", "wheel_radius", "drive_speed", "max_torque", "friction", "terrain", "bump_height", "bump_spacing", "bump_count"],
         },
     ];
 
@@ -212,3 +560,38 @@ struct ScenarioInfo {
     description: String,
     params: Vec<&'static str>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use simuforge_physics::load_experiment;
+    use std::path::Path;
+
+    /// The browser's frame-paced playback must report exactly what the CLI does
+    #[test]
+    fn test_benchmarks_match_cli_reports() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("../../experiments/benchmarks");
+        let mut paths: Vec<_> = std::fs::read_dir(&dir).unwrap().map(|e| e.unwrap().path()).collect();
+        paths.sort();
+        assert!(!paths.is_empty());
+
+        for path in paths.iter().filter(|p| p.extension().is_some_and(|e| e == "yaml")) {
+            let spec = load_experiment(path).unwrap();
+
+            // The loop `simuforge run` steps through
+            let mut cli = ExperimentRunner::new(spec.clone()).unwrap();
+            cli.run_observed(|_| Ok::<_, ()>(())).unwrap();
+            let expected = serde_json::to_value(cli.finish()).unwrap();
+
+            let mut simulation = Simulation::new(&serde_json::to_string(&spec).unwrap()).unwrap();
+            for _ in 0..20 {
+                simulation.advance(0.05);
+            }
+            simulation.reset();
+            while simulation.advance(0.05) > 0 {}
+            let report = serde_json::to_value(simulation.finish()).unwrap();
+
+            assert_eq!(report, expected, "{}", path.display());
+        }
+    }
+}
@@ -10,8 +10,9 @@ import {
   Color3,
   Vector3,
   Quaternion,
+  VertexData,
 } from '@babylonjs/core';
-import type { BodyTransform } from './wasm-loader';
+import type { BodyTransform, SceneBody, SceneCollider, SceneDescription } from './wasm-loader';
 
 export interface BodyMeshOptions {
   color?: Color3;
@@ -41,6 +42,73 @@ const defaultColors = {
   selected: new Color3(1.0, 0.8, 0.2),
 };
 
+/**
+ * Mesh for one collider, placed at its offset from the body
+ */
+export function createColliderMesh(collider: SceneCollider, name: string, scene: Scene): Mesh {
+  const shape = collider.shape;
+  let mesh: Mesh;
+  switch (shape.type) {
+    case 'box': {
+      const [x, y, z] = shape.half_extents;
+      mesh = MeshBuilder.CreateBox(name, { width: x * 2, height: y * 2, depth: z * 2 }, scene);
+      break;
+    }
+    case 'sphere':
+      mesh = MeshBuilder.CreateSphere(name, { diameter: shape.radius * 2, segments: 16 }, scene);
+      break;
+    case 'capsule':
+      mesh = MeshBuilder.CreateCapsule(name, {
+        radius: shape.radius,
+        height: (shape.half_height + shape.radius) * 2,
+        tessellation: 16,
+      }, scene);
+      break;
+    case 'cylinder':
+    case 'cone':
+      mesh = MeshBuilder.CreateCylinder(name, {
+        diameterTop: shape.type === 'cone' ? 0 : shape.radius * 2,
+        diameterBottom: shape.radius * 2,
+        height: shape.half_height * 2,
+        tessellation: 24,
+      }, scene);
+      break;
+    case 'mesh': {
+      mesh = new Mesh(name, scene);
+      const data = new VertexData();
+      data.positions = shape.vertices.flat();
+      data.indices = shape.indices.flat();
+      data.normals = [];
+      VertexData.ComputeNormals(data.positions, data.indices, data.normals);
+      data.applyToMesh(mesh);
+      break;
+    }
+  }
+
+  const { position, rotation } = collider.offset;
+  mesh.position.set(position[0], position[1], position[2]);
+  mesh.rotationQuaternion = new Quaternion(rotation.x, rotation.y, rotation.z, rotation.w);
+  if (collider.sensor) {
+    mesh.visibility = 0.25;
+  }
+  return mesh;
+}
+
+/**
+ * Body node with one child mesh per collider; move it with `position` and `rotationQuaternion`
+ */
+export function createBodyMesh(body: SceneBody, scene: Scene): Mesh {
+  const root = new Mesh(body.name, scene);
+  body.colliders.forEach((collider, i) => {
+    createColliderMesh(collider, `${body.name}_${i}`, scene).parent = root;
+  });
+
+  const { position, rotation } = body.transform;
+  root.position.set(position[0], position[1], position[2]);
+  root.rotationQuaternion = new Quaternion(rotation.x, rotation.y, rotation.z, rotation.w);
+  return root;
+}
+
 /**
  * Manages visualization of physics bodies
  */
@@ -128,6 +196,42 @@ export class BodyVisualizer {
     }
   }
 
+  /**
+   * Create body meshes from the simulation's own collider geometry
+   */
+  initializeFromScene(description: SceneDescription): void {
+    this.clear();
+
+    for (const body of description.bodies) {
+      const mesh = createBodyMesh(body, this.scene);
+      const isStatic = body.body_type !== 'dynamic';
+      const isGround = body.name.toLowerCase().includes('ground') ||
+                       body.name.toLowerCase().includes('floor');
+      this.setMaterial(mesh, this.materials.get(isGround ? 'ground' : isStatic ? 'static' : 'dynamic')!);
+
+      const first = body.colliders[0]?.shape;
+      this.bodies.set(body.id, {
+        mesh,
+        shape: first?.type === 'box'
+          ? { type: 'box', halfExtents: first.half_extents }
+          : first?.type === 'sphere'
+            ? { type: 'sphere', radius: first.radius }
+            : { type: 'box' },
+        isStatic,
+      });
+    }
+  }
+
+  /**
+   * Set a material on a mesh and any collider meshes under it
+   */
+  private setMaterial(mesh: Mesh, material: StandardMaterial): void {
+    mesh.material = material;
+    for (const child of mesh.getChildMeshes()) {
+      child.material = material;
+    }
+  }
+
   private createMeshForShape(shape: BodyShape, name: string): Mesh {
     switch (shape.type) {
       case 'box': {
@@ -191,15 +295,15 @@ export class BodyVisualizer {
 
       // Update material based on state
       if (transform.id === this.selectedBody) {
-        body.mesh.material = this.materials.get('selected')!;
+        this.setMaterial(body.mesh, this.materials.get('selected')!);
       } else if (sleepingBodies?.has(transform.id)) {
-        body.mesh.material = this.materials.get('sleeping')!;
+        this.setMaterial(body.mesh, this.materials.get('sleeping')!);
       } else if (body.isStatic) {
         const isGround = transform.name.toLowerCase().includes('ground') ||
                          transform.name.toLowerCase().includes('floor');
-        body.mesh.material = this.materials.get(isGround ? 'ground' : 'static')!;
+        this.setMaterial(body.mesh, this.materials.get(isGround ? 'ground' : 'static')!);
       } else {
-        body.mesh.material = this.materials.get('dynamic')!;
+        this.setMaterial(body.mesh, this.materials.get('dynamic')!);
       }
     }
   }
@@ -5,4 +5,4 @@
 export { SimuForgeRenderer, type RendererOptions } from './renderer';
 export { WasmLoader, type WasmModule, type Simulation } from './wasm-loader';
 export { CameraController, type CameraMode } from './camera';
-export { BodyVisualizer, type BodyMeshOptions } from './body-visualizer';
+export { BodyVisualizer, createBodyMesh, createColliderMesh, type BodyMeshOptions } from './body-visualizer';
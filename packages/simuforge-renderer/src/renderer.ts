@@ -140,20 +140,8 @@ export class SimuForgeRenderer {
     // Get initial transforms
     const transforms = this.simulation.get_body_transforms() as BodyTransform[];
 
-    // Initialize body visualizations (assuming box shapes for now)
-    const shapes = transforms.map(t => ({
-      id: t.id,
-      name: t.name,
-      shape: {
-        type: 'box' as const,
-        halfExtents: [0.5, 0.5, 0.5] as [number, number, number],
-      },
-      isStatic: t.name.toLowerCase().includes('ground') ||
-                t.name.toLowerCase().includes('floor') ||
-                t.name.toLowerCase().includes('ramp'),
-    }));
-
-    this.bodyVisualizer.initializeBodies(shapes);
+    // Meshes come from the simulation's collider geometry
+    this.bodyVisualizer.initializeFromScene(this.simulation.get_scene_description());
     this.bodyVisualizer.updateTransforms(transforms);
 
     // Frame all bodies
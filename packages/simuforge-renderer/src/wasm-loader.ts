@@ -28,7 +28,19 @@ export interface MetricFrame {
     max_penetration: number;
     total_penetration: number;
     constraint_violations: number;
+    total_normal_impulse: number;
+    max_normal_impulse: number;
+    max_tangent_impulse: number;
+    max_friction_ratio: number;
+    slipping_contacts: number;
+    pairs?: Array<{
+      body_a: string;
+      body_b: string;
+      normal_force: number;
+      tangent_force: number;
+    }>;
   };
+  gravity: { x: number; y: number; z: number };
   bodies: Array<{
     id: number;
     name: string;
@@ -40,8 +52,90 @@ export interface MetricFrame {
     angular_velocity: { x: number; y: number; z: number };
     sleeping: boolean;
   }>;
+  events?: SimEvent[];
+  actuators?: ActuatorState[];
 }
 
+type Point3 = [number, number, number];
+
+export interface SceneTransform {
+  position: Point3;
+  rotation: { x: number; y: number; z: number; w: number };
+}
+
+/** Collider shape in its own frame; round shapes are centered, with their axis along Y */
+export type ShapeDescription =
+  | { type: 'box'; half_extents: Point3 }
+  | { type: 'sphere'; radius: number }
+  | { type: 'capsule' | 'cylinder' | 'cone'; half_height: number; radius: number }
+  | { type: 'mesh'; vertices: Point3[]; indices: Array<[number, number, number]> };
+
+export interface SceneCollider {
+  shape: ShapeDescription;
+  /** Pose relative to the body */
+  offset: SceneTransform;
+  sensor: boolean;
+}
+
+export interface SceneBody {
+  id: number;
+  name: string;
+  body_type: 'dynamic' | 'fixed' | 'kinematic';
+  transform: SceneTransform;
+  colliders: SceneCollider[];
+}
+
+export interface SceneDescription {
+  bodies: SceneBody[];
+}
+
+/** Debug overlay geometry for one step */
+export interface DebugGeometry {
+  step: number;
+  time: number;
+  bodies: Array<{ id: number; name: string; center_of_mass: Point3; sleeping: boolean }>;
+  aabbs: Array<{ body: string; min: Point3; max: Point3; sensor: boolean }>;
+  /** `normal` points from `body_a` to `body_b`; `depth` is negative while still apart */
+  contacts: Array<{ body_a: string; body_b: string; point: Point3; normal: Point3; depth: number }>;
+  /** `axis` is set for revolute and prismatic joints */
+  joints: Array<{ name: string; anchor_a: Point3; anchor_b: Point3; axis?: Point3 }>;
+}
+
+export interface RayHit {
+  body_id: number;
+  body: string;
+  point: [number, number, number];
+  normal: [number, number, number];
+  distance: number;
+}
+
+/** Frame totals without body states or contact pairs */
+export type FrameSummary = Pick<MetricFrame, 'step' | 'time' | 'energy' | 'momentum' | 'contacts'>;
+
+export interface ActuatorState {
+  joint: string;
+  position: number;
+  velocity: number;
+  setpoint?: number | null;
+  target_velocity?: number | null;
+  tracking_error: number;
+}
+
+export type SimEvent = { step: number; time: number } & (
+  | { type: 'collision_started' | 'collision_stopped'; body_a: string; body_b: string }
+  | { type: 'sensor_entered' | 'sensor_exited'; sensor: string; body: string }
+  | {
+      type: 'contact_force';
+      body_a: string;
+      body_b: string;
+      total_force_magnitude: number;
+      max_force_magnitude: number;
+    }
+  | { type: 'action_applied'; action: string; body?: string }
+  | { type: 'action_failed'; action: string; reason: string }
+  | { type: 'parameter_changed'; parameter: string; body?: string; previous: string; value: string }
+);
+
 export interface SimulationReport {
   status: 'pending' | 'passed' | 'failed' | 'error';
   experiment_name: string;
@@ -57,6 +151,10 @@ export interface SimulationReport {
     stability_time: number | null;
     average_contact_count: number;
     frame_count: number;
+    max_tracking_error?: number;
+    rms_tracking_error?: number;
+    parameter_changes?: SimEvent[];
+    scenario?: Record<string, number>;
   };
   criteria_results: Record<string, {
     value: number;
@@ -77,6 +175,47 @@ export interface Simulation {
   step(): MetricFrame;
   get_frame(): MetricFrame;
   get_body_transforms(): BodyTransform[];
+  /** Run `n` steps, returning totals of the last frame without body states */
+  step_n(n: number): FrameSummary;
+  /** Run the fixed steps that fit in `realDt` seconds (not a step count; see `step_n`), capped by `set_max_substeps`; returns the step count */
+  advance(realDt: number): number;
+  set_max_substeps(maxSubsteps: number): void;
+  /** Fraction of a step accumulated by `advance` but not yet simulated */
+  interpolation_alpha(): number;
+  get_summary(): FrameSummary;
+  /** Body ids and names in transform buffer order; refetch when the layout version changes */
+  get_body_list(): Array<{ id: number; name: string }>;
+  body_layout_version(): bigint;
+  transform_stride(): number;
+  /** Positions and quaternions of all bodies; a view into WASM memory, valid until the next call */
+  update_body_transforms(): Float32Array;
+  /** As `update_body_transforms`, blended by `interpolation_alpha` from the previous step */
+  update_interpolated_body_transforms(): Float32Array;
+  /** Every body's collider shapes, for building meshes */
+  get_scene_description(): SceneDescription;
+  /** Contacts, AABBs, joints and centers of mass of the current (not interpolated) state */
+  get_debug_geometry(): DebugGeometry;
+  /** Closest body hit by a ray, or null; `point` and `normal` are [x, y, z] */
+  raycast(ox: number, oy: number, oz: number, dx: number, dy: number, dz: number, maxDistance: number): RayHit | null;
+  /** Grab a body with a spring; pass `BigInt(hit.body_id)` */
+  start_drag(bodyId: bigint, x: number, y: number, z: number, stiffness?: number, damping?: number): void;
+  drag_to(x: number, y: number, z: number): void;
+  end_drag(): void;
+  apply_impulse_at(bodyId: bigint, ix: number, iy: number, iz: number, px: number, py: number, pz: number): void;
+  /** Live parameter changes take effect on the next step and are listed in the report */
+  set_gravity(x: number, y: number, z: number): void;
+  set_timestep(timestep: number): void;
+  set_solver_iterations(iterations: number): void;
+  set_body_material(body: string, friction?: number, restitution?: number, density?: number): void;
+  set_body_mass(body: string, mass: number): void;
+  /** Jump to a step by restoring the nearest earlier snapshot and re-simulating; stepping on from there discards later history */
+  seek(step: bigint): void;
+  step_back(): void;
+  set_snapshot_interval(steps: number): void;
+  /** Snapshot memory budget in bytes; older snapshots are thinned out beyond it */
+  set_snapshot_memory_cap(bytes: number): void;
+  snapshot_count(): number;
+  snapshot_memory(): number;
   run_to_completion(): SimulationReport;
   current_step(): number;
   current_time(): number;
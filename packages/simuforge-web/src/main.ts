@@ -17,8 +17,10 @@ import {
   Color4,
   MeshBuilder,
   StandardMaterial,
-  Quaternion,
+  Matrix,
 } from '@babylonjs/core';
+import { createBodyMesh } from '../../simuforge-renderer/src/body-visualizer';
+import type { SceneDescription } from '../../simuforge-renderer/src/wasm-loader';
 
 // DOM elements
 const canvas = document.getElementById('render-canvas') as HTMLCanvasElement;
@@ -33,6 +35,9 @@ const loadBtn = document.getElementById('load-btn') as HTMLButtonElement;
 const resetBtn = document.getElementById('reset-btn') as HTMLButtonElement;
 const playBtn = document.getElementById('play-btn') as HTMLButtonElement;
 const stepBtn = document.getElementById('step-btn') as HTMLButtonElement;
+const stepBackBtn = document.getElementById('step-back-btn') as HTMLButtonElement;
+const timelineInput = document.getElementById('timeline-input') as HTMLInputElement;
+const debugInput = document.getElementById('debug-input') as HTMLInputElement;
 const speedInput = document.getElementById('speed-input') as HTMLInputElement;
 const speedDisplay = document.getElementById('speed-display') as HTMLSpanElement;
 
@@ -48,11 +53,21 @@ const penetrationValue = document.getElementById('penetration-value') as HTMLSpa
 // Babylon.js setup
 let engine: Engine;
 let scene: Scene;
+let camera: ArcRotateCamera;
 let simulation: Simulation | null = null;
 let bodyMeshes: Map<number, any> = new Map();
+// Meshes in the order of the simulation's transform buffer
+let meshOrder: any[] = [];
+let bodyLayoutVersion: bigint = -1n;
 let isPlaying = false;
+let debugLines: any = null;
 let playbackSpeed = 1.0;
 
+// Plane facing the camera through the grabbed point, while a body is dragged
+let dragPlane: { point: Vector3; normal: Vector3 } | null = null;
+const IMPULSE_STRENGTH = 5;
+const PICK_DISTANCE = 1000;
+
 /**
  * Generate experiment spec JSON from form inputs
  */
@@ -107,6 +122,24 @@ function getScenarioParams(scenario: string): Record<string, unknown> {
       return { radius: 0.5, drop_height: 10, restitution: 0.8 };
     case 'friction_ramp':
       return { ramp_angle: 0.5, ramp_length: 10, friction: 0.3 };
+    case 'newtons_cradle':
+      return { count: 5, raised: 1, gap: 0.005, restitution: 1.0 };
+    case 'box_pyramid':
+      return { base_width: 5, box_size: [1, 1, 1], brick_offset: 0.5, friction: 0.5 };
+    case 'brick_wall':
+      return { rows: 6, columns: 5, brick_size: [1, 0.5, 0.5], friction: 0.6 };
+    case 'domino_chain':
+      return { count: 20, spacing: 0.5, curve_radius: 0, push_angular_velocity: 2 };
+    case 'simple_pendulum':
+      return { length: 2, bob_radius: 0.1, initial_angle: 0.3 };
+    case 'double_pendulum':
+      return { length1: 1, length2: 1, bob_radius: 0.1, angle1: 1.5, angle2: 1 };
+    case 'granular_pile':
+      return { count: 300, grain_radius: 0.1, shape: 'sphere', container: 'hopper', seed: 1 };
+    case 'articulated_chain':
+      return { links: 8, link_length: 0.5, initial_angle: 0, formulation: 'both' };
+    case 'vehicle':
+      return { terrain: 'bumps', drive_speed: 3, bump_height: 0.1, bump_spacing: 2, friction: 1.0 };
     default:
       return {};
   }
@@ -119,10 +152,13 @@ function createBodyMeshes() {
   // Clear existing meshes
   bodyMeshes.forEach(mesh => mesh.dispose());
   bodyMeshes.clear();
+  meshOrder = [];
 
   if (!simulation) return;
 
-  const transforms = simulation.get_body_transforms() as any[];
+  // Shapes are fetched once per layout; transforms come from the fast path
+  const description = simulation.get_scene_description() as SceneDescription;
+  bodyLayoutVersion = simulation.body_layout_version();
 
   // Create materials
   const dynamicMat = new StandardMaterial('dynamicMat', scene);
@@ -131,40 +167,22 @@ function createBodyMeshes() {
   const staticMat = new StandardMaterial('staticMat', scene);
   staticMat.diffuseColor = new Color3(0.4, 0.4, 0.4);
 
-  const groundMat = new StandardMaterial('groundMat', scene);
-  groundMat.diffuseColor = new Color3(0.35, 0.35, 0.35);
-
-  for (const t of transforms) {
-    const isGround = t.name.includes('ground') || t.name.includes('floor');
-    const isRamp = t.name.includes('ramp');
-    const isSphere = t.name.includes('sphere') || t.name.includes('ball');
-
-    let mesh;
-
-    if (isGround) {
-      // Ground plane - large flat box
-      mesh = MeshBuilder.CreateBox(t.name, { width: 100, height: 1, depth: 100 }, scene);
-      mesh.material = groundMat;
-    } else if (isRamp) {
-      // Ramp
-      mesh = MeshBuilder.CreateBox(t.name, { width: 10, height: 1, depth: 4 }, scene);
-      mesh.material = staticMat;
-    } else if (isSphere) {
-      // Sphere
-      mesh = MeshBuilder.CreateSphere(t.name, { diameter: 1, segments: 16 }, scene);
-      mesh.material = dynamicMat;
-    } else {
-      // Default box (stacked boxes etc)
-      mesh = MeshBuilder.CreateBox(t.name, { size: 1 }, scene);
-      mesh.material = dynamicMat;
-    }
+  const kinematicMat = new StandardMaterial('kinematicMat', scene);
+  kinematicMat.diffuseColor = new Color3(0.9, 0.6, 0.2);
 
-    // Set initial transform
-    mesh.position = new Vector3(t.position[0], t.position[1], t.position[2]);
-    mesh.rotationQuaternion = new Quaternion(t.rotation[0], t.rotation[1], t.rotation[2], t.rotation[3]);
+  // Scene description bodies are in transform buffer order
+  for (const body of description.bodies) {
+    const mesh = createBodyMesh(body, scene);
+    const material = body.body_type === 'dynamic' ? dynamicMat : body.body_type === 'kinematic' ? kinematicMat : staticMat;
+    for (const child of mesh.getChildMeshes()) {
+      child.material = material;
+    }
 
-    bodyMeshes.set(t.id, mesh);
+    bodyMeshes.set(body.id, mesh);
+    meshOrder.push(mesh);
   }
+
+  updateBodyMeshes();
 }
 
 /**
@@ -173,15 +191,88 @@ function createBodyMeshes() {
 function updateBodyMeshes() {
   if (!simulation) return;
 
-  const transforms = simulation.get_body_transforms() as any[];
+  // Bodies were spawned or removed by scripted actions
+  if (simulation.body_layout_version() !== bodyLayoutVersion) {
+    createBodyMeshes();
+    return;
+  }
+
+  const stride = simulation.transform_stride();
+  // View into WASM memory, read before any other call into the simulation
+  const transforms = simulation.update_interpolated_body_transforms();
+
+  for (let i = 0; i < meshOrder.length; i++) {
+    const mesh = meshOrder[i];
+    const o = i * stride;
+    mesh.position.set(transforms[o], transforms[o + 1], transforms[o + 2]);
+    mesh.rotationQuaternion.set(transforms[o + 3], transforms[o + 4], transforms[o + 5], transforms[o + 6]);
+  }
+
+  updateDebugOverlay();
+}
+
+const DEBUG_AWAKE = new Color4(1, 0.85, 0.2, 1);
+const DEBUG_SLEEPING = new Color4(0.5, 0.5, 0.5, 1);
+const DEBUG_CONTACT = new Color4(1, 0.2, 0.2, 1);
+const DEBUG_JOINT = new Color4(0.2, 1, 0.4, 1);
+const DEBUG_NORMAL_LENGTH = 0.3;
+const DEBUG_MARKER_SIZE = 0.1;
 
-  for (const t of transforms) {
-    const mesh = bodyMeshes.get(t.id);
-    if (mesh) {
-      mesh.position.set(t.position[0], t.position[1], t.position[2]);
-      mesh.rotationQuaternion = new Quaternion(t.rotation[0], t.rotation[1], t.rotation[2], t.rotation[3]);
+/**
+ * Redraw contact normals, AABBs, joint axes and center of mass markers.
+ * Drawn at the simulated state, so they can lead interpolated meshes by a step.
+ */
+function updateDebugOverlay() {
+  debugLines?.dispose();
+  debugLines = null;
+  if (!simulation || !debugInput.checked) return;
+
+  const geometry = simulation.get_debug_geometry();
+  const lines: Vector3[][] = [];
+  const colors: Color4[][] = [];
+  const v = (p: number[]) => new Vector3(p[0], p[1], p[2]);
+  const add = (points: Vector3[], color: Color4) => {
+    lines.push(points);
+    colors.push(points.map(() => color));
+  };
+
+  const sleeping = new Set(geometry.bodies.filter((b: any) => b.sleeping).map((b: any) => b.name));
+  for (const aabb of geometry.aabbs) {
+    const [a, b] = [aabb.min, aabb.max];
+    const color = sleeping.has(aabb.body) ? DEBUG_SLEEPING : DEBUG_AWAKE;
+    // Two faces as loops, plus the four edges joining them
+    add([v([a[0], a[1], a[2]]), v([b[0], a[1], a[2]]), v([b[0], a[1], b[2]]), v([a[0], a[1], b[2]]), v([a[0], a[1], a[2]])], color);
+    add([v([a[0], b[1], a[2]]), v([b[0], b[1], a[2]]), v([b[0], b[1], b[2]]), v([a[0], b[1], b[2]]), v([a[0], b[1], a[2]])], color);
+    for (const [x, z] of [[a[0], a[2]], [b[0], a[2]], [b[0], b[2]], [a[0], b[2]]]) {
+      add([v([x, a[1], z]), v([x, b[1], z])], color);
+    }
+  }
+
+  for (const contact of geometry.contacts) {
+    const point = v(contact.point);
+    add([point, point.add(v(contact.normal).scale(DEBUG_NORMAL_LENGTH))], DEBUG_CONTACT);
+  }
+
+  for (const joint of geometry.joints) {
+    const anchor = v(joint.anchor_a);
+    add([anchor, v(joint.anchor_b)], DEBUG_JOINT);
+    if (joint.axis) {
+      add([anchor.subtract(v(joint.axis).scale(DEBUG_NORMAL_LENGTH)), anchor.add(v(joint.axis).scale(DEBUG_NORMAL_LENGTH))], DEBUG_JOINT);
     }
   }
+
+  for (const body of geometry.bodies) {
+    const c = v(body.center_of_mass);
+    const color = body.sleeping ? DEBUG_SLEEPING : DEBUG_AWAKE;
+    for (const axis of [Vector3.Right(), Vector3.Up(), Vector3.Forward()]) {
+      add([c.subtract(axis.scale(DEBUG_MARKER_SIZE)), c.add(axis.scale(DEBUG_MARKER_SIZE))], color);
+    }
+  }
+
+  if (lines.length > 0) {
+    debugLines = MeshBuilder.CreateLineSystem('debug', { lines, colors }, scene);
+    debugLines.isPickable = false;
+  }
 }
 
 /**
@@ -195,6 +286,7 @@ function updateMetrics(frame: any): void {
   penetrationValue.textContent = frame.contacts.max_penetration.toFixed(6);
 
   stepDisplay.textContent = `Step: ${simulation?.current_step() ?? 0} / ${simulation?.target_steps() ?? 0}`;
+  timelineInput.value = `${simulation?.current_step() ?? 0}`;
   timeDisplay.textContent = `Time: ${frame.time.toFixed(3)}s`;
 }
 
@@ -231,6 +323,8 @@ async function loadExperiment(): Promise<void> {
     contactsValue.textContent = '-';
     penetrationValue.textContent = '-';
     stepDisplay.textContent = `Step: 0 / ${simulation.target_steps()}`;
+    timelineInput.max = `${simulation.target_steps()}`;
+    timelineInput.value = '0';
     timeDisplay.textContent = 'Time: 0.000s';
 
     playBtn.textContent = '▶';
@@ -241,24 +335,101 @@ async function loadExperiment(): Promise<void> {
   }
 }
 
+/**
+ * Jump to a past or future step, or one step back when `step` is null; pauses playback
+ */
+function seekTo(step: number | null): void {
+  if (!simulation) return;
+
+  try {
+    if (step === null) {
+      simulation.step_back();
+    } else {
+      simulation.seek(BigInt(step));
+    }
+  } catch (error) {
+    setStatus(`${error}`, 'warning');
+    return;
+  }
+  isPlaying = false;
+  playBtn.textContent = '▶';
+  updateMetrics(simulation.get_summary());
+  updateBodyMeshes();
+}
+
+/**
+ * Ray from the camera through the cursor
+ */
+function pointerRay() {
+  return scene.createPickingRay(scene.pointerX, scene.pointerY, Matrix.Identity(), camera);
+}
+
+/**
+ * Ctrl-drag pulls a body on a spring, Shift-click pushes it along the view ray
+ */
+function setupInteraction(): void {
+  canvas.addEventListener('pointerdown', (event) => {
+    if (!simulation || !(event.ctrlKey || event.metaKey || event.shiftKey)) return;
+
+    const ray = pointerRay();
+    const hit = simulation.raycast(
+      ray.origin.x, ray.origin.y, ray.origin.z,
+      ray.direction.x, ray.direction.y, ray.direction.z,
+      PICK_DISTANCE,
+    );
+    if (!hit) return;
+
+    const [x, y, z] = hit.point;
+    try {
+      if (event.shiftKey) {
+        const impulse = ray.direction.scale(IMPULSE_STRENGTH);
+        simulation.apply_impulse_at(BigInt(hit.body_id), impulse.x, impulse.y, impulse.z, x, y, z);
+        setStatus(`Pushed ${hit.body}`);
+      } else {
+        simulation.start_drag(BigInt(hit.body_id), x, y, z);
+        dragPlane = { point: new Vector3(x, y, z), normal: ray.direction.clone() };
+        camera.detachControl();
+        setStatus(`Dragging ${hit.body}`);
+      }
+    } catch (error) {
+      setStatus(`${error}`, 'warning');
+    }
+  });
+
+  canvas.addEventListener('pointermove', () => {
+    if (!simulation || !dragPlane) return;
+
+    const ray = pointerRay();
+    const denom = Vector3.Dot(ray.direction, dragPlane.normal);
+    if (Math.abs(denom) < 1e-6) return;
+
+    const t = Vector3.Dot(dragPlane.point.subtract(ray.origin), dragPlane.normal) / denom;
+    const target = ray.origin.add(ray.direction.scale(t));
+    simulation.drag_to(target.x, target.y, target.z);
+  });
+
+  window.addEventListener('pointerup', () => {
+    if (!dragPlane) return;
+
+    dragPlane = null;
+    simulation?.end_drag();
+    camera.attachControl(canvas, true);
+  });
+}
+
 /**
  * Animation loop
  */
 let lastTime = 0;
-let accumulator = 0;
-const timestep = 1/60;
 
 function animate(currentTime: number) {
   const deltaTime = (currentTime - lastTime) / 1000;
   lastTime = currentTime;
 
   if (isPlaying && simulation && !simulation.is_complete()) {
-    accumulator += deltaTime * playbackSpeed;
-
-    while (accumulator >= timestep) {
-      const frame = simulation.step();
-      updateMetrics(frame);
-      accumulator -= timestep;
+    // Fixed physics steps run inside WASM; rendering blends between the last two
+    if (simulation.advance(deltaTime * playbackSpeed) > 0) {
+      updateMetrics(simulation.get_summary());
     }
 
     updateBodyMeshes();
@@ -289,7 +460,7 @@ async function initApp(): Promise<void> {
     scene.clearColor = new Color4(0.15, 0.15, 0.18, 1);
 
     // Camera
-    const camera = new ArcRotateCamera('camera', -Math.PI / 2, Math.PI / 3, 25, Vector3.Zero(), scene);
+    camera = new ArcRotateCamera('camera', -Math.PI / 2, Math.PI / 3, 25, Vector3.Zero(), scene);
     camera.attachControl(canvas, true);
     camera.wheelPrecision = 20;
 
@@ -300,6 +471,8 @@ async function initApp(): Promise<void> {
     const main = new DirectionalLight('main', new Vector3(-1, -2, -1).normalize(), scene);
     main.intensity = 0.8;
 
+    setupInteraction();
+
     // Hide loading overlay
     loadingEl.classList.add('hidden');
     setStatus('Ready');
@@ -326,6 +499,7 @@ resetBtn.addEventListener('click', () => {
   if (simulation) {
     simulation.reset();
     createBodyMeshes();
+    timelineInput.value = '0';
     setStatus('Reset');
     playBtn.textContent = '▶';
     isPlaying = false;
@@ -354,6 +528,16 @@ stepBtn.addEventListener('click', () => {
   updateBodyMeshes();
 });
 
+stepBackBtn.addEventListener('click', () => {
+  seekTo(null);
+});
+
+timelineInput.addEventListener('input', () => {
+  seekTo(parseInt(timelineInput.value, 10));
+});
+
+debugInput.addEventListener('change', updateDebugOverlay);
+
 speedInput.addEventListener('input', () => {
   playbackSpeed = parseFloat(speedInput.value);
   speedDisplay.textContent = `${playbackSpeed.toFixed(1)}x`;